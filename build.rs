@@ -9,5 +9,8 @@ fn main() {
         Some(outdir) => outdir,
     };
     let mut app = build_cli();
-    app.gen_completions("gsc", Shell::Fish, outdir);
+    app.gen_completions("gsc", Shell::Fish, &outdir);
+
+    #[cfg(feature = "admin")]
+    build_admin_cli().gen_completions("gsc-admin", Shell::Fish, &outdir);
 }
@@ -0,0 +1,376 @@
+//! Tar and zip writers and readers, on the `tar`, `flate2`, and `zip`
+//! crates, for moving a whole homework as one file.
+//!
+//! Both writers take each entry’s contents as a reader and copy it
+//! straight through to the output, and the readers likewise hand each
+//! entry out as a reader, so nothing is staged on disk.
+
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use std::convert::TryFrom;
+use std::fs;
+use std::io::{self, BufReader, Read, Seek, Write};
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Tar,
+    TarGz,
+    Zip,
+}
+
+impl Format {
+    /// Guesses the format from `path`’s extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+
+        if name.ends_with(".zip") {
+            Some(Format::Zip)
+        } else if name.ends_with(".tar") {
+            Some(Format::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Format::TarGz)
+        } else {
+            None
+        }
+    }
+
+    /// `path`’s file name without the archive extension, for use as the
+    /// top-level directory inside the archive.
+    pub fn stem<'a>(&self, path: &'a Path) -> Option<&'a str> {
+        let name = path.file_name()?.to_str()?;
+        let len = name.len();
+        let lower = name.to_lowercase();
+
+        let suffix = match self {
+            Format::Zip => ".zip",
+            Format::Tar => ".tar",
+            Format::TarGz if lower.ends_with(".tgz") => ".tgz",
+            Format::TarGz => ".tar.gz",
+        };
+
+        match name.get(..len.saturating_sub(suffix.len())) {
+            Some("") | None => None,
+            Some(stem) => Some(stem),
+        }
+    }
+}
+
+pub enum Writer<W: Write + Seek> {
+    Tar(tar::Builder<W>),
+    TarGz(tar::Builder<GzEncoder<W>>),
+    Zip(zip::ZipWriter<W>),
+}
+
+impl<W: Write + Seek> Writer<W> {
+    pub fn new(format: Format, out: W) -> Self {
+        match format {
+            Format::Tar => Writer::Tar(tar::Builder::new(out)),
+            Format::TarGz => Writer::TarGz(tar::Builder::new(GzEncoder::new(
+                out,
+                Compression::default(),
+            ))),
+            Format::Zip => Writer::Zip(zip::ZipWriter::new(out)),
+        }
+    }
+
+    /// Adds a regular file named `path` whose contents, exactly `size`
+    /// bytes long, are read from `contents`.
+    pub fn append(
+        &mut self,
+        path: &str,
+        size: u64,
+        mtime: SystemTime,
+        contents: &mut dyn Read,
+    ) -> io::Result<()> {
+        let mut contents = Exactly {
+            path,
+            inner: contents,
+            remaining: size,
+        };
+
+        match self {
+            Writer::Tar(tar) => append_tar(tar, path, size, mtime, &mut contents),
+            Writer::TarGz(tar) => append_tar(tar, path, size, mtime, &mut contents),
+            Writer::Zip(zip) => {
+                let options = zip::write::FileOptions::default()
+                    .compression_method(zip::CompressionMethod::Deflated)
+                    .last_modified_time(dos_date_time(mtime))
+                    .unix_permissions(0o644)
+                    .large_file(size >= u64::from(u32::MAX));
+                zip.start_file(path, options)?;
+                io::copy(&mut contents, zip).map(drop)
+            }
+        }
+    }
+
+    /// Writes any trailer and returns the underlying writer.
+    pub fn finish(self) -> io::Result<W> {
+        match self {
+            Writer::Tar(tar) => tar.into_inner(),
+            Writer::TarGz(tar) => tar.into_inner()?.finish(),
+            Writer::Zip(mut zip) => Ok(zip.finish()?),
+        }
+    }
+}
+
+/// Adds an entry with a GNU header, which, unlike ustar, has no limit on
+/// the length of its path.
+fn append_tar<W: Write>(
+    tar: &mut tar::Builder<W>,
+    path: &str,
+    size: u64,
+    mtime: SystemTime,
+    contents: &mut dyn Read,
+) -> io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_size(size);
+    header.set_mode(0o644);
+    header.set_mtime(unix_time(mtime));
+    tar.append_data(&mut header, path, contents)
+}
+
+/// Reads exactly `remaining` bytes from `inner`, failing if it has more
+/// or fewer, so that a download cut short can’t leave a truncated entry.
+struct Exactly<'a> {
+    path: &'a str,
+    inner: &'a mut dyn Read,
+    remaining: u64,
+}
+
+impl Read for Exactly<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 {
+            return match self.inner.read(&mut [0])? {
+                0 => Ok(0),
+                _ => Err(self.wrong_size("more")),
+            };
+        }
+
+        let limit = usize::try_from(self.remaining).unwrap_or(usize::MAX);
+        let len = buf.len().min(limit);
+        match self.inner.read(&mut buf[..len])? {
+            0 => Err(self.wrong_size("fewer")),
+            n => {
+                self.remaining -= n as u64;
+                Ok(n)
+            }
+        }
+    }
+}
+
+impl Exactly<'_> {
+    fn wrong_size(&self, which: &str) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("{}: got {} bytes than expected", self.path, which),
+        )
+    }
+}
+
+/// MS-DOS time and date, in local time, clamped to the representable
+/// range (1980–2107).
+fn dos_date_time(mtime: SystemTime) -> zip::DateTime {
+    use chrono::{Datelike, Local, Timelike};
+
+    let local = chrono::DateTime::<Local>::from(mtime);
+    if local.year() < 1980 {
+        return zip::DateTime::default();
+    }
+
+    zip::DateTime::from_date_and_time(
+        local.year().min(2107) as u16,
+        local.month() as u8,
+        local.day() as u8,
+        local.hour() as u8,
+        local.minute() as u8,
+        local.second() as u8,
+    )
+    .unwrap_or_default()
+}
+
+fn unix_time(mtime: SystemTime) -> u64 {
+    mtime
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Calls `visit` with the path, size, and contents of each regular file
+/// in the archive `input`, in archive order. Directories, links, and the
+/// like are skipped.
+pub fn for_each_file<R, E, F>(format: Format, input: R, visit: F) -> Result<(), E>
+where
+    R: Read + Seek,
+    E: From<io::Error>,
+    F: FnMut(&str, u64, &mut dyn Read) -> Result<(), E>,
+{
+    match format {
+        Format::Tar => read_tar(input, visit),
+        Format::TarGz => read_tar(MultiGzDecoder::new(BufReader::new(input)), visit),
+        Format::Zip => read_zip(input, visit),
+    }
+}
+
+fn read_tar<R, E, F>(input: R, mut visit: F) -> Result<(), E>
+where
+    R: Read,
+    E: From<io::Error>,
+    F: FnMut(&str, u64, &mut dyn Read) -> Result<(), E>,
+{
+    let mut archive = tar::Archive::new(input);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let kind = entry.header().entry_type();
+
+        if kind.is_file() || kind == tar::EntryType::Continuous {
+            let path = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+            let size = entry.size();
+            visit(&path, size, &mut entry)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn read_zip<R, E, F>(input: R, mut visit: F) -> Result<(), E>
+where
+    R: Read + Seek,
+    E: From<io::Error>,
+    F: FnMut(&str, u64, &mut dyn Read) -> Result<(), E>,
+{
+    let mut archive = zip::ZipArchive::new(input).map_err(io::Error::from)?;
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(io::Error::from)?;
+
+        if file.is_file() {
+            let path = file.name().to_owned();
+            let size = file.size();
+            visit(&path, size, &mut file)?;
+
+            // The CRC is checked only once the whole entry has been read:
+            io::copy(&mut file, &mut io::sink())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A regular file in an archive being read by [`entries`].
+pub struct Entry {
+    pub path: String,
+    pub size: u64,
+    pub contents: Contents,
+}
+
+/// The contents of an [`Entry`], which, unlike the readers that
+/// [`for_each_file`] hands out, can be moved anywhere, such as into a
+/// request body. Dropping it skips the rest of the entry.
+pub struct Contents {
+    chunks: mpsc::Receiver<io::Result<Vec<u8>>>,
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl Read for Contents {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        while self.pos == self.chunk.len() {
+            match self.chunks.recv() {
+                Ok(chunk) => {
+                    self.chunk = chunk?;
+                    self.pos = 0;
+                }
+                Err(_) => return Ok(0),
+            }
+        }
+
+        let n = buf.len().min(self.chunk.len() - self.pos);
+        buf[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// The regular files in an archive, read on a thread of their own. Each entry’s contents must be read or dropped before asking for
+/// the next entry.
+pub struct Entries {
+    entries: mpsc::Receiver<Entry>,
+    reader: Option<thread::JoinHandle<io::Result<()>>>,
+}
+
+impl Iterator for Entries {
+    type Item = io::Result<Entry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Ok(entry) = self.entries.recv() {
+            return Some(Ok(entry));
+        }
+
+        // The reader has stopped, so it either finished or failed:
+        let result = self.reader.take()?.join();
+        let result = result.unwrap_or_else(|_| Err(io::Error::other("archive reader panicked")));
+        result.err().map(Err)
+    }
+}
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Starts reading the archive `file` in the background; see [`Entries`].
+pub fn entries(format: Format, file: fs::File) -> Entries {
+    let (sender, entries) = mpsc::sync_channel(0);
+
+    let reader = thread::spawn(move || {
+        for_each_file(format, BufReader::new(file), |path, size, contents| {
+            let (chunks, receiver) = mpsc::sync_channel(1);
+            let entry = Entry {
+                path: path.to_owned(),
+                size,
+                contents: Contents {
+                    chunks: receiver,
+                    chunk: Vec::new(),
+                    pos: 0,
+                },
+            };
+
+            // Nobody is asking for entries anymore:
+            if sender.send(entry).is_err() {
+                return Err(io::ErrorKind::BrokenPipe.into());
+            }
+
+            let mut buf = vec![0; CHUNK_SIZE];
+            loop {
+                let chunk = match contents.read(&mut buf) {
+                    Ok(0) => return Ok(()),
+                    Ok(n) => buf[..n].to_vec(),
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        let _ = chunks.send(Err(io::Error::new(e.kind(), e.to_string())));
+                        return Err(e);
+                    }
+                };
+
+                // If the contents were dropped, the rest is skipped:
+                if chunks.send(Ok(chunk)).is_err() {
+                    return Ok(());
+                }
+            }
+        })
+    });
+
+    Entries {
+        entries,
+        reader: Some(reader),
+    }
+}
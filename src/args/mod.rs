@@ -1,2 +1,3 @@
+pub mod parse;
 pub mod traits;
 pub mod types;
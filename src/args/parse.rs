@@ -0,0 +1,177 @@
+//! Parsing of the homework and file specs that commands take on the
+//! command line. The grammar is
+//!
+//! ```text
+//! HW         ::= [hw]N[:]
+//! HW_STRICT  ::= hwN[:]
+//! HW_FILE    ::= [hw]N:NAME
+//! HW_OPT     ::= [hw]N[:NAME]
+//! REMOTE_DST ::= HW_STRICT | HW_FILE | NAME
+//! CP_ARG     ::= :PATH | HW_FILE | PATH
+//! EVAL_SCORE ::= yes | no | X/Y | X[%]
+//! ```
+//!
+//! where `N` is a decimal number, and `hw` is case-insensitive. Leading
+//! zeros don’t matter, so `hw02` is `hw2`. Anywhere the grammar has
+//! `[hw]N`, the word `current` (again in any case) also works, and means
+//! the current homework; the functions that accept it take a closure to
+//! look that up, as it may mean asking the server.
+//!
+//! A remote `NAME` is everything after the first colon, so it may be empty
+//! (which means the whole homework) and may itself contain colons. As a
+//! remote destination, a `NAME` without a colon is a file name, even if
+//! it looks like a bare number. As an argument to `cp`, anything without a
+//! colon is a local `PATH`, and a leading colon marks a local path that
+//! would otherwise look remote, such as `:hw1:notes`. On Windows, a drive
+//! path like `C:\notes` is local too.
+//!
+//! An `EVAL_SCORE` is a self-evaluation score: `yes` or `no` (or `y` or
+//! `n`, in any case), a fraction of possible points, or a percentage,
+//! where `X` and `Y` are decimal numbers.
+
+use crate::prelude::*;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use std::borrow::Cow;
+
+lazy_static! {
+    // Case and leading zeros don’t matter, and where there can be no
+    // local file, a bare number is obviously a homework too:
+    static ref HW_ONLY: Regex = Regex::new(r"^(?i:hw)(\d+):?$").unwrap();
+    static ref BARE_HW: Regex = Regex::new(r"^(?i:hw)?(\d+):?$").unwrap();
+    static ref HW_OPT_FILE: Regex = Regex::new(r"^(?i:hw)?(\d+)(?::(.*))?$").unwrap();
+    static ref HW_FILE: Regex = Regex::new(r"^(?i:hw)?(\d+):(.*)$").unwrap();
+    static ref CURRENT: Regex = Regex::new(r"^(?i:current)(:.*)?$").unwrap();
+    static ref LOCAL_FILE: Regex = Regex::new(r"^:(.+)$").unwrap();
+    static ref DRIVE_PATH: Regex = Regex::new(r"^[A-Za-z]:[\\/]").unwrap();
+    static ref FRACTION: Regex = Regex::new(r"^\s*([\d.]+)\s*/\s*([\d.]+)\s*$").unwrap();
+}
+
+/// Parses a homework spec (`HW`).
+pub fn parse_hw(spec: &str, current: impl FnOnce() -> Result<usize>) -> Result<usize> {
+    parse_hw_with(&BARE_HW, &expand_current(spec, current)?)
+}
+
+/// Like `parse_hw`, but requires the ‘hw’ (`HW_STRICT`), for where a bare
+/// number (or ‘current’) could be a file name.
+pub fn parse_hw_strict(spec: &str) -> Result<usize> {
+    parse_hw_with(&HW_ONLY, spec)
+}
+
+fn parse_hw_with(regex: &Regex, spec: &str) -> Result<usize> {
+    if let Some(i) = regex
+        .captures(spec)
+        .and_then(|captures| captures.get(1))
+        .and_then(|s| s.as_str().parse().ok())
+    {
+        Ok(i)
+    } else {
+        Err(ErrorKind::hw_syntax("homework spec", spec))?
+    }
+}
+
+/// Parses a homework with an optional file name or pattern (`HW_OPT`);
+/// without one, the name is empty.
+pub fn parse_hw_opt_file(
+    spec: &str,
+    current: impl FnOnce() -> Result<usize>,
+) -> Result<RemotePattern> {
+    let spec = expand_current(spec, current)?;
+    let error = || ErrorKind::hw_syntax("homework or file spec", &*spec);
+    let captures = HW_OPT_FILE.captures(&spec).ok_or_else(error)?;
+    let hw = captures[1].parse().map_err(|_| error())?;
+    let name = captures.get(2).map_or("", |c| c.as_str()).to_owned();
+    Ok(RemotePattern { hw, name })
+}
+
+/// Parses a homework and file name or pattern (`HW_FILE`). The name may be
+/// empty, but the colon is required.
+pub fn parse_hw_file(
+    file_spec: &str,
+    current: impl FnOnce() -> Result<usize>,
+) -> Result<RemotePattern> {
+    let file_spec = expand_current(file_spec, current)?;
+    let error = || ErrorKind::hw_syntax("remote file or homework spec", &*file_spec);
+    let captures = HW_FILE.captures(&file_spec).ok_or_else(error)?;
+    let hw = captures[1].parse().map_err(|_| error())?;
+    let name = captures[2].to_owned();
+    Ok(RemotePattern { hw, name })
+}
+
+/// Replaces a leading ‘current’ in `spec` with the homework that `current`
+/// returns, calling it only if needed.
+pub fn expand_current<'a>(
+    spec: &'a str,
+    current: impl FnOnce() -> Result<usize>,
+) -> Result<Cow<'a, str>> {
+    match CURRENT.captures(spec) {
+        Some(captures) => {
+            let rest = captures.get(1).map_or("", |m| m.as_str());
+            Ok(format!("hw{}{}", current()?, rest).into())
+        }
+        None => Ok(spec.into()),
+    }
+}
+
+/// Parses the destination of `gsc mv` (`REMOTE_DST`).
+pub fn parse_remote_dest(
+    spec: &str,
+    current: impl FnOnce() -> Result<usize>,
+) -> Result<RemoteDestination> {
+    if spec.is_empty() {
+        Err(ErrorKind::syntax("remote file or assignment name", spec))?;
+    }
+
+    let result = if let Ok(hw) = parse_hw_strict(spec) {
+        RemoteDestination::just_hw(hw)
+    } else if spec.find(':').is_some() {
+        parse_hw_file(spec, current)?.into()
+    } else {
+        RemoteDestination::just_name(spec)
+    };
+
+    Ok(result)
+}
+
+/// Parses a source or destination of `gsc cp` (`CP_ARG`).
+pub fn parse_cp_arg(spec: &str, current: impl FnOnce() -> Result<usize>) -> Result<CpArg> {
+    if spec.is_empty() {
+        Err(ErrorKind::syntax("file name", spec))?
+    } else if let Some(captures) = LOCAL_FILE.captures(spec) {
+        Ok(CpArg::Local(captures[1].into()))
+    } else if cfg!(windows) && DRIVE_PATH.is_match(spec) {
+        Ok(CpArg::Local(spec.into()))
+    } else if spec.find(':').is_some() {
+        Ok(CpArg::Remote(parse_hw_file(spec, current)?))
+    } else {
+        Ok(CpArg::Local(spec.into()))
+    }
+}
+
+/// Parses a score for `gsc eval set` (`EVAL_SCORE`).
+pub fn parse_eval_score(spec: &str) -> Result<EvalScore> {
+    let error = || ErrorKind::syntax("score (yes, no, a fraction, or a percentage)", spec);
+
+    match spec.trim().to_lowercase().as_str() {
+        "y" | "yes" => return Ok(EvalScore::Yes),
+        "n" | "no" => return Ok(EvalScore::No),
+        _ => {}
+    }
+
+    if let Some(captures) = FRACTION.captures(spec) {
+        let num: f64 = captures[1].parse().map_err(|_| error())?;
+        let den: f64 = captures[2].parse().map_err(|_| error())?;
+        if den == 0.0 {
+            Err(error())?
+        }
+        return Ok(EvalScore::Fraction(num, den));
+    }
+
+    let percent = spec.trim().trim_end_matches('%');
+    match percent.parse() {
+        Ok(percent) => Ok(EvalScore::Percent(percent)),
+        Err(_) => Err(error())?,
+    }
+}
@@ -5,6 +5,7 @@ use std::{
 };
 
 use super::traits::FileName;
+use crate::messages::EvalType;
 use crate::prelude::*;
 
 pub type RemoteDestination = HwOptQual<String>;
@@ -24,6 +25,16 @@ pub enum CpArg {
     Remote(RemotePattern),
 }
 
+/// A score for `gsc eval set`, as given: an answer to a yes/no item, a
+/// fraction like `3/5`, or a percentage.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EvalScore {
+    Yes,
+    No,
+    Fraction(f64, f64),
+    Percent(f64),
+}
+
 impl<T: FileName> RemotePath for HwOptQual<T> {
     type Name = T;
     type Qual = HwQual<T>;
@@ -129,6 +140,40 @@ impl CpArg {
     }
 }
 
+impl EvalScore {
+    /// The score from 0 to 1 that this means for an item of type
+    /// `eval_type`, if it fits: yes/no items take `yes`, `no`, 0%, or
+    /// 100%; other items take a fraction or percentage from 0 to 1.
+    pub fn for_type(self, eval_type: EvalType) -> Option<f64> {
+        let score = match self {
+            EvalScore::Yes => 1.0,
+            EvalScore::No => 0.0,
+            EvalScore::Fraction(num, den) => num / den,
+            EvalScore::Percent(percent) => percent / 100.0,
+        };
+
+        let fits = match (eval_type, self) {
+            (EvalType::Boolean, _) => score == 0.0 || score == 1.0,
+            (EvalType::Scale, EvalScore::Yes | EvalScore::No) => false,
+            (EvalType::Informational, EvalScore::Yes | EvalScore::No) => false,
+            _ => (0.0..=1.0).contains(&score),
+        };
+
+        Some(score).filter(|_| fits)
+    }
+}
+
+impl Display for EvalScore {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            EvalScore::Yes => f.write_str("yes"),
+            EvalScore::No => f.write_str("no"),
+            EvalScore::Fraction(num, den) => write!(f, "{}/{}", num, den),
+            EvalScore::Percent(percent) => write!(f, "{}%", percent),
+        }
+    }
+}
+
 impl<T: Deref<Target = str>> HwQual<T> {
     pub fn is_whole_hw(&self) -> bool {
         self.name.is_empty()
@@ -0,0 +1,102 @@
+//! A log of the changes that course staff make with `gsc-admin`.
+//!
+//! Each grade set, extension, partnership ended, and user added or
+//! deleted appends one JSON line to the audit file, recording who did
+//! what to whom. With the `audit_post` setting, the same record is also
+//! posted to the server, so that the whole staff’s changes are kept in
+//! one place.
+
+use crate::history;
+use crate::messages::UtcDateTime;
+use crate::prelude::*;
+
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    AddUser,
+    DelUser,
+    Divorce,
+    Extend,
+    SetGrade,
+    SetAuto,
+    SetExam,
+}
+
+impl Action {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Action::AddUser => "add_user",
+            Action::DelUser => "del_user",
+            Action::Divorce => "divorce",
+            Action::Extend => "extend",
+            Action::SetGrade => "set_grade",
+            Action::SetAuto => "set_auto",
+            Action::SetExam => "set_exam",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Entry {
+    pub time: UtcDateTime,
+    /// Who made the change: the logged-in user, never `--user`.
+    pub actor: String,
+    pub action: Action,
+    /// The user whose account, submission, or grade was changed.
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hw: Option<usize>,
+    /// What it was changed to, in the action’s own terms.
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub payload: serde_json::Value,
+}
+
+impl Entry {
+    pub fn new(action: Action, target: impl Into<String>) -> Self {
+        Entry {
+            time: UtcDateTime::now(),
+            actor: String::new(),
+            action,
+            target: target.into(),
+            hw: None,
+            payload: serde_json::Value::Null,
+        }
+    }
+
+    pub fn hw(mut self, hw: usize) -> Self {
+        self.hw = Some(hw);
+        self
+    }
+
+    pub fn payload(mut self, payload: serde_json::Value) -> Self {
+        self.payload = payload;
+        self
+    }
+}
+
+impl GscClient {
+    /// Appends `entry` to the audit file, and posts it to the server if
+    /// `audit_post` is set. Failing at either is only worth a warning,
+    /// since the change itself has already been made.
+    pub(crate) fn audit(&self, mut entry: Entry) {
+        if let Ok(creds) = self.load_credentials() {
+            entry.actor = creds.username().to_owned();
+        }
+
+        if let Some(path) = self.config.get_audit_file() {
+            if let Err(e) = history::append_entry(path, &entry) {
+                ve1!("Could not write audit file {}: {}", path.display(), e);
+            }
+        }
+
+        if self.config.audit_post() {
+            let uri = format!("{}/api/audit", self.config.get_endpoint());
+            let request = self.http.post(&uri).json(&entry);
+            if let Err(e) = self.send_request(request) {
+                self.warn(format!("Could not post to the server’s audit log: {}", e));
+            }
+        }
+    }
+}
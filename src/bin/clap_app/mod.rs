@@ -1,16 +1,47 @@
+// This file is shared by `gsc`, `gsc-admin`, and build.rs, each of which
+// uses only part of it; hence the `allow(dead_code)`s.
+
 use clap::*;
 
+#[allow(dead_code)]
 pub fn build_cli() -> App<'static, 'static> {
     App::new("gsc")
         .author("Jesse A. Tov <jesse@eecs.northwestern.edu>")
         .about("Command-line interface to the GSC server")
         .version(crate_version!())
         .add_common()
-        .add_admin()
+        .subcommand(
+            SubCommand::with_name("api")
+                .about("Sends a raw request to the server")
+                .add_common()
+                .arg(
+                    clap::Arg::with_name("DATA")
+                        .short("d")
+                        .long("data")
+                        .value_name("JSON")
+                        .takes_value(true)
+                        .help("Sends JSON as the request body (@FILE reads a file, - reads stdin)"),
+                )
+                .req_arg("METHOD", "The HTTP method, e.g. GET")
+                .req_arg("PATH", "The server path, e.g. /api/whoami"),
+        )
+        .subcommand(
+            SubCommand::with_name("archive")
+                .about("Downloads a homework as a zip or tar archive")
+                .add_common()
+                .add_overwrite_opts()
+                .req_arg("HW", "The homework to archive, e.g. ‘hw3’"),
+        )
         .subcommand(
             SubCommand::with_name("auth")
                 .about("Authenticates with the server")
                 .add_common()
+                .arg(
+                    clap::Arg::with_name("STDIN")
+                        .long("stdin")
+                        .takes_value(false)
+                        .help("Reads the API key from stdin instead of prompting"),
+                )
                 .req_arg("USER", "Your username (i.e., your NetID)"),
         )
         .subcommand(
@@ -20,6 +51,58 @@ pub fn build_cli() -> App<'static, 'static> {
                 .flag("ALL", "all", "Print all files in the specified homeworks")
                 .req_args("SPEC", "The remote files or homeworks to print"),
         )
+        .subcommand(
+            SubCommand::with_name("chtype")
+                .about("Changes the type of remote files")
+                .add_common()
+                .req_args("SPEC", "The remote files to change")
+                .req_arg("TYPE", "The new type: source, test, config, or resource"),
+        )
+        .subcommand(
+            SubCommand::with_name("completions")
+                .about("Prints a shell completion script")
+                .add_common()
+                .arg(
+                    clap::Arg::with_name("SHELL")
+                        .required(true)
+                        .possible_values(&Shell::variants())
+                        .help("The shell to complete for"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("config")
+                .about("Reads or changes settings in the dotfile")
+                .add_common()
+                .subcommand(
+                    SubCommand::with_name("get")
+                        .about("Prints a setting")
+                        .add_common()
+                        .req_arg("KEY", "The setting to print"),
+                )
+                .subcommand(
+                    SubCommand::with_name("set")
+                        .about("Changes a setting")
+                        .add_common()
+                        .req_arg("KEY", "The setting to change")
+                        .req_arg("VALUE", "The new value"),
+                )
+                .subcommand(
+                    SubCommand::with_name("unset")
+                        .about("Removes a setting, restoring its default")
+                        .add_common()
+                        .req_arg("KEY", "The setting to remove"),
+                )
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("Prints all settings")
+                        .add_common(),
+                )
+                .subcommand(
+                    SubCommand::with_name("edit")
+                        .about("Opens the dotfile in $EDITOR")
+                        .add_common(),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("cp")
                 .about("Copies files to or from the server")
@@ -30,14 +113,60 @@ pub fn build_cli() -> App<'static, 'static> {
                     "all",
                     "Copy all the files in the specified source homeworks",
                 )
+                .flag(
+                    "EXTRACT",
+                    "extract",
+                    "Unpack local archives and upload the files inside",
+                )
+                .arg(
+                    clap::Arg::with_name("NO_VERIFY")
+                        .long("no-verify")
+                        .takes_value(false)
+                        .help("Skips the pre-push hook"),
+                )
+                .arg(
+                    clap::Arg::with_name("INTO")
+                        .long("into")
+                        .value_name("TEMPLATE")
+                        .takes_value(true)
+                        .conflicts_with("EXTRACT")
+                        .help("Names uploaded files by TEMPLATE, e.g. ‘jd123_{stem}{ext}’"),
+                )
+                .arg(
+                    clap::Arg::with_name("MEDIA_TYPE")
+                        .long("media-type")
+                        .value_name("TYPE")
+                        .takes_value(true)
+                        .help("Gives uploaded files media type TYPE, e.g. ‘text/x-racket’"),
+                )
                 .req_args("SRC", "The files to copy")
                 .req_arg("DST", "The destination of the files"),
         )
+        .subcommand(
+            SubCommand::with_name("deadlines")
+                .about("Lists upcoming due dates in chronological order")
+                .add_common(),
+        )
         .subcommand(
             SubCommand::with_name("deauth")
                 .about("Forgets authentication credentials")
                 .add_common(),
         )
+        .subcommand(
+            SubCommand::with_name("diff")
+                .about("Compares remote files with local copies")
+                .add_common()
+                .flag(
+                    "ALL",
+                    "all",
+                    "Compare all the files in the specified homework",
+                )
+                .req_arg("SPEC", "The remote file or homework to compare")
+                .opt_arg(
+                    "LOCAL",
+                    "The local file or directory to compare with (default: .)",
+                ),
+        )
         .subcommand(
             SubCommand::with_name("eval")
                 .about("Manages self evaluation")
@@ -53,15 +182,64 @@ pub fn build_cli() -> App<'static, 'static> {
                         .about("Performs self evaluation")
                         .req_arg("HW", "The homework to evaluate")
                         .req_arg("NUMBER", "The eval item to set")
-                        .req_arg("SCORE", "The score [0.0, 1.0]")
-                        .opt_arg("EXPLANATION", "Your justification for the score"),
+                        .req_arg("SCORE", "The score: yes or no, a fraction like 3/5, or a percentage")
+                        .opt_arg("EXPLANATION", "Your justification for the score")
+                        .arg(
+                            clap::Arg::with_name("ALLOW_EMPTY")
+                                .long("allow-empty")
+                                .takes_value(false)
+                                .help("Sets a high score without an explanation, without asking"),
+                        ),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("grade")
+                .about("Shows how each eval item adds up to a homework’s grade")
+                .add_common()
+                .req_arg("HW", "The homework whose grade to break down"),
+        )
+        .subcommand(
+            SubCommand::with_name("history")
+                .about("Shows the changes you have made to your submissions")
+                .add_common()
+                .opt_arg("HW", "The homework to show history for (default: all)"),
+        )
+        .subcommand(
+            SubCommand::with_name("log")
+                .about("Shows autograder logs")
+                .add_common()
+                .flag("LATEST", "latest", "Shows only the most recent log")
+                .flag("FOLLOW", "follow", "Keeps checking for new logs")
+                .arg(
+                    clap::Arg::with_name("INTERVAL")
+                        .long("interval")
+                        .value_name("SECS")
+                        .takes_value(true)
+                        .requires("FOLLOW")
+                        .help("How often to check for new logs (default: 10)"),
+                )
+                .opt_arg("HW", "The homework whose logs to show, e.g. ‘hw3’ (default: default_hw)"),
+        )
         .subcommand(
             SubCommand::with_name("ls")
                 .about("Lists files")
                 .add_common()
-                .req_args("SPEC", "The homeworks or files to list, e.g. ‘hw3’"),
+                .add_porcelain_opt()
+                .add_limit_opt()
+                .arg(
+                    clap::Arg::with_name("TREE")
+                        .long("tree")
+                        .takes_value(false)
+                        .conflicts_with_all(&["PORCELAIN", "JSON", "LIMIT"])
+                        .help("Shows the files as ‘cp -a’ would lay them out"),
+                )
+                .arg(
+                    clap::Arg::with_name("SPEC")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(false)
+                        .help("The homeworks or files to list, e.g. ‘hw3’ (default: default_hw)"),
+                ),
         )
         .subcommand(
             SubCommand::with_name("mv")
@@ -71,10 +249,29 @@ pub fn build_cli() -> App<'static, 'static> {
                 .req_arg("SRC", "The file to rename")
                 .req_arg("DST", "The new name "),
         )
+        .subcommand(
+            SubCommand::with_name("open")
+                .about("Opens a homework or file in the web browser")
+                .add_common()
+                .arg(
+                    clap::Arg::with_name("PRINT_URL")
+                        .long("print-url")
+                        .takes_value(false)
+                        .help("Prints the URL instead of opening it"),
+                )
+                .opt_arg("SPEC", "The homework or file to open, e.g. ‘hw3’ (default: default_hw)"),
+        )
         .subcommand(
             SubCommand::with_name("partner")
                 .about("Manages partners")
                 .add_common()
+                .add_porcelain_opt()
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("Lists all partner requests in detail")
+                        .add_common()
+                        .add_porcelain_opt(),
+                )
                 .subcommand(
                     SubCommand::with_name("request")
                         .about("Sends a partner request")
@@ -91,10 +288,49 @@ pub fn build_cli() -> App<'static, 'static> {
                         .add_partner_args(),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("ping")
+                .about("Checks whether the server is up")
+                .add_common(),
+        )
+        .subcommand(
+            SubCommand::with_name("push")
+                .about("Uploads the files committed in git")
+                .add_common()
+                .arg(
+                    clap::Arg::with_name("GIT")
+                        .long("git")
+                        .takes_value(false)
+                        .required(true)
+                        .help("Uploads the files tracked at HEAD"),
+                )
+                .arg(
+                    clap::Arg::with_name("TAG")
+                        .long("tag")
+                        .takes_value(false)
+                        .help("Tags the commit as gsc/hwN/TIMESTAMP afterward"),
+                )
+                .arg(
+                    clap::Arg::with_name("NO_VERIFY")
+                        .long("no-verify")
+                        .takes_value(false)
+                        .help("Skips the pre-push hook"),
+                )
+                .req_arg("HW", "The homework to push to, e.g. ‘hw3’"),
+        )
+        .subcommand(
+            SubCommand::with_name("regrade")
+                .about("Asks for an eval item to be graded again")
+                .add_common()
+                .req_arg("HW", "The homework of the eval item")
+                .req_arg("NUMBER", "The eval item to regrade")
+                .req_arg("REASON", "Why it should be regraded"),
+        )
         .subcommand(
             SubCommand::with_name("rm")
                 .about("Removes remote files")
                 .add_common()
+                .add_yes_opt("Deletes without asking for confirmation, except with --user")
                 .flag(
                     "ALL",
                     "all",
@@ -106,20 +342,309 @@ pub fn build_cli() -> App<'static, 'static> {
             SubCommand::with_name("status")
                 .about("Retrieves user or submission status")
                 .add_common()
+                .add_porcelain_opt()
+                .arg(
+                    clap::Arg::with_name("ALL")
+                        .short("a")
+                        .long("all")
+                        .takes_value(false)
+                        .conflicts_with("HW")
+                        .help("Summarizes the status of every open or past homework"),
+                )
                 .opt_arg("HW", "The homework to lookup, e.g. ‘hw3’"),
         )
+        .subcommand(
+            SubCommand::with_name("submit")
+                .about("Prints a receipt for what you have submitted")
+                .add_common()
+                .add_overwrite_opts()
+                .arg(
+                    clap::Arg::with_name("RECEIPT")
+                        .long("receipt")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .help("Also saves the receipt to FILE as JSON"),
+                )
+                .req_arg("HW", "The homework to make a receipt for, e.g. ‘hw3’"),
+        )
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about("Checks that a homework matches a local directory")
+                .add_common()
+                .add_porcelain_opt()
+                .req_arg("HW", "The homework to check, e.g. ‘hw3’")
+                .opt_arg("DIR", "The directory to check against (default: .)"),
+        )
         .subcommand(
             SubCommand::with_name("whoami")
-                .about("Prints your username, if authenticated")
+                .about("Shows who you are logged in as, and how")
                 .add_common(),
         )
 }
 
+/// The CLI of `gsc-admin`, course staff’s companion to `gsc`, which has
+/// the administrative commands that `gsc` leaves out.
+#[cfg(feature = "admin")]
+#[allow(dead_code)]
+pub fn build_admin_cli() -> App<'static, 'static> {
+    App::new("gsc-admin")
+        .author("Jesse A. Tov <jesse@eecs.northwestern.edu>")
+        .about("Administrative commands for the GSC server")
+        .version(crate_version!())
+        .add_common()
+        .subcommand(
+            SubCommand::with_name("completions")
+                .about("Prints a shell completion script")
+                .add_common()
+                .arg(
+                    clap::Arg::with_name("SHELL")
+                        .required(true)
+                        .possible_values(&Shell::variants())
+                        .help("The shell to complete for"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("audit")
+                .about("Shows recent changes made with gsc-admin")
+                .add_common()
+                .arg(
+                    clap::Arg::with_name("LIMIT")
+                        .long("limit")
+                        .value_name("N")
+                        .takes_value(true)
+                        .help("Shows the last N changes (default: 20)"),
+                )
+                .opt_arg("USER", "Shows only changes to this user"),
+        )
+        .subcommand(
+            SubCommand::with_name("csv")
+                .about("Prints the grade spreadsheet (only matching users’ rows with --user)")
+                .add_common()
+                .arg(
+                    clap::Arg::with_name("HW")
+                        .long("hw")
+                        .value_name("N")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Leaves out columns for other homework"),
+                )
+                .arg(
+                    clap::Arg::with_name("COLUMNS")
+                        .long("columns")
+                        .value_name("COLS")
+                        .takes_value(true)
+                        .multiple(true)
+                        .use_delimiter(true)
+                        .help("Prints only these columns, in order, e.g. ‘user,hw*’"),
+                )
+                .flag("TSV", "tsv", "Prints tab-separated values instead"),
+        )
+        .subcommand(
+            SubCommand::with_name("add_user")
+                .about("Adds a user")
+                .add_everywhere()
+                .req_arg("USER", "Name of user to add")
+                .arg(
+                    clap::Arg::with_name("GRADER_ROLE")
+                        .long("grader")
+                        .takes_value(false)
+                        .conflicts_with("ADMIN_ROLE")
+                        .help("Creates user with grader role"),
+                )
+                .arg(
+                    clap::Arg::with_name("ADMIN_ROLE")
+                        .long("admin")
+                        .takes_value(false)
+                        .conflicts_with("GRADER_ROLE")
+                        .help("Creates user with admin role"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("del_user")
+                .about("Deletes a user")
+                .add_everywhere()
+                .add_yes_opt("Deletes without asking for confirmation")
+                .req_arg("USER", "Name of user to delete"),
+        )
+        .subcommand(
+            SubCommand::with_name("diff")
+                .about("Compares two users’ submissions")
+                .add_common()
+                .req_arg("HW", "The homework to compare")
+                .req_arg("USER1", "The first user")
+                .req_arg("USER2", "The second user")
+                .opt_arg("FILE", "The files to compare, e.g. ‘*.cpp’ (default: all)"),
+        )
+        .subcommand(
+            SubCommand::with_name("divorce")
+                .about("Ends a partnership")
+                .add_common()
+                .req_arg("HW", "The homework in question")
+                .req_arg("USER", "One of the two partners"),
+        )
+        .subcommand(
+            SubCommand::with_name("extend")
+                .about("Extends a due date")
+                .add_common()
+                .flag(
+                    "EVAL",
+                    "eval",
+                    "Extends self eval instead of file submission",
+                )
+                .arg(
+                    clap::Arg::with_name("FROM_NOW")
+                        .long("from-now")
+                        .takes_value(false)
+                        .help("Interprets relative dates from now, not the current due date"),
+                )
+                .req_arg("HW", "The homework to extend")
+                .req_arg("USER", "The user to extend")
+                .req_arg(
+                    "DATESPEC",
+                    "The new due date, e.g. ‘2024-05-01 23:59’, ‘+3d’, or ‘next fri’",
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("partners")
+                .about("Looks up a partnership")
+                .add_common()
+                .req_arg("HW", "The homework to lookup")
+                .req_arg("USER", "The user to lookup"),
+        )
+        .subcommand(
+            SubCommand::with_name("permalink")
+                .about("Prints the permalink hash for a given self evaluation")
+                .add_common()
+                .req_arg("HW", "The homework of the self evaluation")
+                .req_arg("USER", "The user whose self evaluation to find")
+                .req_arg("NUMBER", "The eval item number to find")
+                .arg(
+                    clap::Arg::with_name("OPEN")
+                        .long("open")
+                        .takes_value(false)
+                        .help("Opens the permalink in the browser"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("permalinks")
+                .about("Lists every user’s self evaluation permalinks for an assignment")
+                .add_common()
+                .add_bulk_opts()
+                .req_arg("HW", "The homework of the self evaluations")
+                .arg(
+                    clap::Arg::with_name("ITEM")
+                        .long("item")
+                        .value_name("N")
+                        .takes_value(true)
+                        .help("Lists only permalinks for eval item N"),
+                )
+                .arg(
+                    clap::Arg::with_name("CSV")
+                        .long("csv")
+                        .takes_value(false)
+                        .conflicts_with("JSON")
+                        .help("Prints comma-separated values instead"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("progress")
+                .about("Shows how far grading of an assignment has gotten")
+                .add_common()
+                .add_bulk_opts()
+                .req_arg("HW", "The assignment to report on"),
+        )
+        .subcommand(
+            SubCommand::with_name("regrades")
+                .about("Lists open regrade requests for an assignment")
+                .add_common()
+                .add_bulk_opts()
+                .req_arg("HW", "The assignment to list regrade requests for"),
+        )
+        .subcommand(
+            SubCommand::with_name("set_grade")
+                .about("Records the grade for any eval item")
+                .add_common()
+                .req_arg("HW", "The homework to set the grade on")
+                .req_arg("USER", "The user whose grade to set")
+                .req_arg("NUMBER", "The eval item number to set")
+                .req_arg("SCORE", "The score [0.0, 1.0]")
+                .req_arg("COMMENT", "A comment"),
+        )
+        .subcommand(
+            SubCommand::with_name("set_auto")
+                .about("Records the result of the autograder")
+                .add_common()
+                .req_arg("HW", "The homework to set the grade on")
+                .req_arg("USER", "The user whose grade to set")
+                .req_arg("SCORE", "The score [0.0, 1.0]")
+                .req_arg("COMMENT", "A comment"),
+        )
+        .subcommand(
+            SubCommand::with_name("set_exam")
+                .about("Sets the grade for an exam")
+                .add_common()
+                .req_arg("EXAM", "The exam number whose grade to set")
+                .req_arg("USER", "The user whose grade to set")
+                .req_arg("POINTS", "The points scored")
+                .req_arg("POSSIBLE", "The points possible"),
+        )
+        .subcommand(
+            SubCommand::with_name("submissions")
+                .about("Lists submissions for a given assignment")
+                .add_common()
+                .add_bulk_opts()
+                .add_limit_opt()
+                .req_arg("HW", "The assignment to query")
+                .arg(
+                    clap::Arg::with_name("STATUS")
+                        .long("status")
+                        .value_name("STATUSES")
+                        .takes_value(true)
+                        .multiple(true)
+                        .use_delimiter(true)
+                        .help("Lists only submissions with these statuses, e.g. ‘open,self_eval’"),
+                )
+                .arg(
+                    clap::Arg::with_name("SORT")
+                        .long("sort")
+                        .value_name("KEY")
+                        .takes_value(true)
+                        .possible_values(&["id", "owner", "status", "grade", "modified"])
+                        .help("Orders the submissions by KEY (default: id)"),
+                )
+                .flag("REVERSE", "reverse", "Lists in the reverse order")
+                .arg(
+                    clap::Arg::with_name("CSV")
+                        .long("csv")
+                        .takes_value(false)
+                        .conflicts_with("JSON")
+                        .help("Prints comma-separated values instead"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("user_info")
+                .about("Summarizes a user’s submissions, grades, and partners")
+                .add_common()
+                .req_arg("USER", "The user to summarize"),
+        )
+        .subcommand(
+            SubCommand::with_name("whois")
+                .about("Finds whose submission has a given id")
+                .add_common()
+                .req_arg("SUBMISSION_ID", "The submission id to look up"),
+        )
+}
+
+#[allow(dead_code)]
 trait AppExt {
-    fn add_admin(self) -> Self;
     fn add_common(self) -> Self;
     fn add_everywhere(self) -> Self;
     fn add_overwrite_opts(self) -> Self;
+    fn add_porcelain_opt(self) -> Self;
+    fn add_yes_opt(self, help: &'static str) -> Self;
+    fn add_bulk_opts(self) -> Self;
+    fn add_limit_opt(self) -> Self;
     fn add_partner_args(self) -> Self;
     fn add_user_opt(self) -> Self;
 
@@ -134,118 +659,6 @@ trait AppExt {
 }
 
 impl<'a, 'b> AppExt for clap::App<'a, 'b> {
-    #[cfg(feature = "admin")]
-    fn add_admin(self) -> Self {
-        use clap::*;
-
-        self.subcommand(
-            SubCommand::with_name("admin")
-                .about("Administrative commands")
-                .add_common()
-                .subcommand(
-                    SubCommand::with_name("csv")
-                        .about("Prints the grade spreadsheet")
-                        .add_common(),
-                )
-                .subcommand(
-                    SubCommand::with_name("add_user")
-                        .about("Adds a user")
-                        .add_everywhere()
-                        .req_arg("USER", "Name of user to add")
-                        .arg(clap::Arg::with_name("GRADER_ROLE")
-                            .long("grader")
-                            .takes_value(false)
-                            .conflicts_with("ADMIN_ROLE")
-                            .help("Creates user with grader role"))
-                        .arg(clap::Arg::with_name("ADMIN_ROLE")
-                            .long("admin")
-                            .takes_value(false)
-                            .conflicts_with("GRADER_ROLE")
-                            .help("Creates user with admin role")),
-                )
-                .subcommand(
-                    SubCommand::with_name("del_user")
-                        .about("Deletes a user")
-                        .add_everywhere()
-                        .req_arg("USER", "Name of user to delete"),
-                )
-                .subcommand(
-                    SubCommand::with_name("divorce")
-                        .about("Ends a partnership")
-                        .add_common()
-                        .req_arg("HW", "The homework in question")
-                        .req_arg("USER", "One of the two partners"),
-                )
-                .subcommand(
-                    SubCommand::with_name("extend")
-                        .about("Extends a due date")
-                        .add_common()
-                        .flag(
-                            "EVAL",
-                            "eval",
-                            "Extends self eval instead of file submission",
-                        )
-                        .req_arg("HW", "The homework to extend")
-                        .req_arg("USER", "The user to extend")
-                        .req_arg("DATESPEC", "The new due date"),
-                )
-                .subcommand(
-                    SubCommand::with_name("partners")
-                        .about("Looks up a partnership")
-                        .add_common()
-                        .req_arg("HW", "The homework to lookup")
-                        .req_arg("USER", "The user to lookup"),
-                )
-                .subcommand(
-                    SubCommand::with_name("permalink")
-                        .about("Prints the permalink hash for a given self evaluation")
-                        .add_common()
-                        .req_arg("HW", "The homework of the self evaluation")
-                        .req_arg("USER", "The user whose self evaluation to find")
-                        .req_arg("NUMBER", "The eval item number to find"),
-                )
-                .subcommand(
-                    SubCommand::with_name("set_grade")
-                        .about("Records the grade for any eval item")
-                        .add_common()
-                        .req_arg("HW", "The homework to set the grade on")
-                        .req_arg("USER", "The user whose grade to set")
-                        .req_arg("NUMBER", "The eval item number to set")
-                        .req_arg("SCORE", "The score [0.0, 1.0]")
-                        .req_arg("COMMENT", "A comment"),
-                )
-                .subcommand(
-                    SubCommand::with_name("set_auto")
-                        .about("Records the result of the autograder")
-                        .add_common()
-                        .req_arg("HW", "The homework to set the grade on")
-                        .req_arg("USER", "The user whose grade to set")
-                        .req_arg("SCORE", "The score [0.0, 1.0]")
-                        .req_arg("COMMENT", "A comment"),
-                )
-                .subcommand(
-                    SubCommand::with_name("set_exam")
-                        .about("Sets the grade for an exam")
-                        .add_common()
-                        .req_arg("EXAM", "The exam number whose grade to set")
-                        .req_arg("USER", "The user whose grade to set")
-                        .req_arg("POINTS", "The points scored")
-                        .req_arg("POSSIBLE", "The points possible"),
-                )
-                .subcommand(
-                    SubCommand::with_name("submissions")
-                        .about("Lists submissions for a given assignment")
-                        .add_common()
-                        .req_arg("HW", "The assignment to query"),
-                ),
-        )
-    }
-
-    #[cfg(not(feature = "admin"))]
-    fn add_admin(self) -> Self {
-        self
-    }
-
     fn add_everywhere(self) -> Self {
         self.arg(
             clap::Arg::with_name("VERBOSE")
@@ -280,6 +693,101 @@ impl<'a, 'b> AppExt for clap::App<'a, 'b> {
                 .takes_value(false)
                 .help("Show human-formatted result (overrides --json)"),
         )
+        .arg(
+            clap::Arg::with_name("INSECURE")
+                .long("insecure")
+                .takes_value(false)
+                .help("Don’t verify the server’s TLS certificate"),
+        )
+        .arg(
+            clap::Arg::with_name("NO_INPUT")
+                .long("no-input")
+                .takes_value(false)
+                .help("Never asks questions, taking the safe answer instead"),
+        )
+        .arg(
+            clap::Arg::with_name("OFFLINE")
+                .long("offline")
+                .takes_value(false)
+                .help("Uses cached server data instead of connecting"),
+        )
+        .arg(
+            clap::Arg::with_name("FORCE")
+                .long("force")
+                .takes_value(false)
+                .help("Proceeds despite an incompatible server, a suspicious upload, or a closed self eval"),
+        )
+        .arg(
+            clap::Arg::with_name("STRICT")
+                .long("strict")
+                .takes_value(false)
+                .help("Stops at the first failure instead of warning and going on"),
+        )
+        .arg(
+            clap::Arg::with_name("OUTPUT")
+                .short("o")
+                .long("output")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Writes output to FILE instead of standard output"),
+        )
+        .arg(
+            clap::Arg::with_name("TRACE")
+                .long("trace")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Records all HTTP traffic to FILE"),
+        )
+        .arg(
+            clap::Arg::with_name("PROFILE")
+                .long("profile")
+                .value_name("NAME")
+                .takes_value(true)
+                .help("Uses the endpoint and credentials of the named profile"),
+        )
+        .arg(
+            clap::Arg::with_name("ENDPOINT")
+                .long("endpoint")
+                .value_name("URL")
+                .takes_value(true)
+                .conflicts_with("SERVER")
+                .help("Connects to the GSC server at URL"),
+        )
+        .arg(
+            clap::Arg::with_name("SERVER")
+                .long("server")
+                .value_name("NAME")
+                .takes_value(true)
+                .help("Connects to the named server from the dotfile’s endpoints"),
+        )
+        .arg(
+            clap::Arg::with_name("API_KEY_FILE")
+                .long("api-key-file")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Authenticates with the API key in FILE (requires $GSC_USER)"),
+        )
+        .arg(
+            clap::Arg::with_name("UTC")
+                .long("utc")
+                .takes_value(false)
+                .help("Shows dates in UTC instead of the configured time zone"),
+        )
+        .arg(
+            clap::Arg::with_name("WIDTH")
+                .long("width")
+                .value_name("COLUMNS")
+                .takes_value(true)
+                .validator(|s| s.parse::<usize>().map(drop).map_err(|e| e.to_string()))
+                .help("Fits tables and wrapped text in COLUMNS columns"),
+        )
+        .arg(
+            clap::Arg::with_name("LIMIT_RATE")
+                .long("limit-rate")
+                .value_name("RATE")
+                .takes_value(true)
+                .help("Uploads and downloads files at most RATE bytes per second (e.g., 500K)"),
+        )
         .add_everywhere()
         .add_user_opt()
     }
@@ -300,6 +808,53 @@ impl<'a, 'b> AppExt for clap::App<'a, 'b> {
             )
     }
 
+    fn add_porcelain_opt(self) -> Self {
+        self.arg(
+            clap::Arg::with_name("PORCELAIN")
+                .long("porcelain")
+                .takes_value(false)
+                .conflicts_with("JSON")
+                .help("Prints tab-separated records in a format meant for scripts"),
+        )
+    }
+
+    fn add_yes_opt(self, help: &'static str) -> Self {
+        self.arg(
+            clap::Arg::with_name("YES")
+                .short("f")
+                .long("yes")
+                .takes_value(false)
+                .help(help),
+        )
+    }
+
+    fn add_bulk_opts(self) -> Self {
+        self.arg(
+            clap::Arg::with_name("JOBS")
+                .long("jobs")
+                .value_name("N")
+                .takes_value(true)
+                .help("Sends up to N requests at once (default: the jobs setting)"),
+        )
+        .arg(
+            clap::Arg::with_name("PACE")
+                .long("pace")
+                .value_name("MS")
+                .takes_value(true)
+                .help("Waits at least MS milliseconds between starting requests"),
+        )
+    }
+
+    fn add_limit_opt(self) -> Self {
+        self.arg(
+            clap::Arg::with_name("LIMIT")
+                .long("limit")
+                .value_name("N")
+                .takes_value(true)
+                .help("Lists only the first N entries"),
+        )
+    }
+
     fn add_partner_args(self) -> Self {
         self.add_common()
             .req_arg("HW", "The homework of the partner request")
@@ -0,0 +1,185 @@
+//! What `gsc` and `gsc-admin` have in common: processing the options
+//! that every subcommand takes, parsing homework numbers, and starting
+//! and finishing a run.
+
+use gsc_client::args::parse;
+use gsc_client::config;
+use gsc_client::messages::DisplayZone;
+use gsc_client::prelude::*;
+
+use std::cell::Cell;
+use std::error::Error;
+use std::num::NonZeroUsize;
+use std::process::exit;
+use std::str::FromStr;
+
+/// Reports how a run went and exits: with the error’s category’s exit
+/// code if it failed, or 2 if it succeeded with warnings.
+pub fn exit_with(result: Result<bool>) -> ! {
+    match result {
+        Err(err) => {
+            ve1!("{}", err);
+
+            let mut source = err.source();
+
+            while let Some(error) = source {
+                ve1!("Source: {}", error);
+                source = error.source();
+            }
+
+            exit(err.category().exit_code());
+        }
+        Ok(true) => exit(2),
+        Ok(false) => exit(0),
+    }
+}
+
+/// Readies a configuration that has been loaded and processed to talk to
+/// the server, and creates the client that will.
+pub fn connect(mut config: config::Config) -> Result<GscClient> {
+    config.activate_profile()?;
+    config.activate_endpoint()?;
+    config.activate_time_zone();
+
+    if let Some(path) = config.get_project_dotfile() {
+        ve2!("Using project dotfile: {}", path.display());
+    }
+
+    GscClient::with_config(config)
+}
+
+pub fn process_common<'a>(
+    matches: &clap::ArgMatches<'a>,
+    config: &mut config::Config,
+) -> Result<()> {
+    let vs = matches.occurrences_of("VERBOSE") as isize;
+    let qs = matches.occurrences_of("QUIET") as isize;
+    let verbosity = config.get_verbosity() + vs - qs;
+    config.set_verbosity(verbosity);
+
+    if matches.is_present("HUMAN") {
+        config.set_json_output(false);
+    } else if matches.is_present("JSON") {
+        config.set_json_output(true);
+    }
+
+    if let Some(user) = matches.value_of("ME") {
+        config.set_on_behalf(user.to_owned());
+    }
+
+    if matches.is_present("INSECURE") {
+        config.set_insecure(true);
+    }
+
+    if matches.is_present("NO_INPUT") {
+        config.set_no_input(true);
+    }
+
+    if matches.is_present("OFFLINE") {
+        config.set_offline(true);
+    }
+
+    if matches.is_present("FORCE") {
+        config.set_force(true);
+    }
+
+    if matches.is_present("STRICT") {
+        config.set_strict(true);
+    }
+
+    if let Some(path) = matches.value_of_os("OUTPUT") {
+        config.set_output_file(path.into());
+    }
+
+    if let Some(path) = matches.value_of_os("TRACE") {
+        config.set_trace_file(path.into());
+    }
+
+    if let Some(name) = matches.value_of("PROFILE") {
+        config.set_profile(name.to_owned());
+    }
+
+    if let Some(url) = matches.value_of("ENDPOINT") {
+        config.set_endpoint(url.to_owned());
+    }
+
+    if let Some(name) = matches.value_of("SERVER") {
+        config.set_server(name.to_owned());
+    }
+
+    if let Some(path) = matches.value_of_os("API_KEY_FILE") {
+        config.set_api_key_file(path.into());
+    }
+
+    if matches.is_present("UTC") {
+        config.set_time_zone(DisplayZone::utc());
+    }
+
+    if let Some(width) = matches.value_of("WIDTH").and_then(|s| s.parse().ok()) {
+        config.set_width(width);
+    }
+
+    if let Some(rate) = matches.value_of("LIMIT_RATE") {
+        config.set_limit_rate(rate.parse()?);
+    }
+
+    Ok(())
+}
+
+pub fn process_yes_opt<'a>(matches: &clap::ArgMatches<'a>, config: &mut config::Config) {
+    if matches.is_present("YES") {
+        config.set_assume_yes(true);
+    }
+}
+
+/// The number of entries that `--limit` asks for, if any.
+pub fn parse_limit_opt<'a>(matches: &clap::ArgMatches<'a>) -> Result<Option<usize>> {
+    matches
+        .value_of("LIMIT")
+        .map(|limit| {
+            Ok(limit
+                .parse_descr::<NonZeroUsize>("number of entries")?
+                .get())
+        })
+        .transpose()
+}
+
+pub fn parse_hw(spec: &str, config: &config::Config) -> Result<usize> {
+    parse::parse_hw(spec, || current_hw(config))
+}
+
+/// Looks up the current homework, once, with a client configured like the
+/// one that will run the command.
+pub fn current_hw(config: &config::Config) -> Result<usize> {
+    thread_local! {
+        static CURRENT_HW: Cell<Option<usize>> = const { Cell::new(None) };
+    }
+
+    if let Some(hw) = CURRENT_HW.with(Cell::get) {
+        return Ok(hw);
+    }
+
+    let mut config = config.clone();
+    config.activate_verbosity();
+    config.activate_profile()?;
+    config.activate_endpoint()?;
+
+    let hw = GscClient::with_config(config)?.current_hw()?;
+    CURRENT_HW.with(|cell| cell.set(Some(hw)));
+    Ok(hw)
+}
+
+pub trait ParseWithDescription {
+    fn parse_descr<F: FromStr>(&self, descr: &str) -> Result<F>
+    where
+        <F as FromStr>::Err: std::error::Error + Send + 'static;
+}
+
+impl ParseWithDescription for str {
+    fn parse_descr<F: FromStr>(&self, descr: &str) -> Result<F>
+    where
+        <F as FromStr>::Err: std::error::Error + Send + 'static,
+    {
+        self.parse().chain_err(|| ErrorKind::syntax(descr, self))
+    }
+}
@@ -0,0 +1,438 @@
+use gsc_client::config;
+use gsc_client::messages::{SubmissionStatus, UserRole};
+use gsc_client::prelude::*;
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+mod clap_app;
+mod common;
+
+use self::common::{
+    parse_hw, parse_limit_opt, process_common, process_yes_opt, ParseWithDescription,
+};
+
+const DEFAULT_AUDIT_LIMIT: usize = 20;
+
+fn main() {
+    vlog::set_verbosity_level(3);
+    common::exit_with(do_it());
+}
+
+enum Command {
+    AddUser {
+        user: String,
+        role: UserRole,
+    },
+    DelUser {
+        user: String,
+    },
+    Audit {
+        user: Option<String>,
+        limit: usize,
+    },
+    Csv {
+        hws: Vec<usize>,
+        user: Option<String>,
+        columns: Vec<String>,
+        tsv: bool,
+    },
+    Diff {
+        hw: usize,
+        user1: String,
+        user2: String,
+        pattern: String,
+    },
+    Divorce {
+        user: String,
+        hw: usize,
+    },
+    Extend {
+        user: String,
+        hw: usize,
+        date: String,
+        eval: bool,
+        from_now: bool,
+    },
+    Partners {
+        user: String,
+        hw: usize,
+    },
+    Permalink {
+        user: String,
+        hw: usize,
+        number: usize,
+        open: bool,
+    },
+    Permalinks {
+        hw: usize,
+        item: Option<usize>,
+        csv: bool,
+    },
+    Progress {
+        hw: usize,
+    },
+    Regrades {
+        hw: usize,
+    },
+    SetGrade {
+        user: String,
+        hw: usize,
+        number: usize,
+        score: f64,
+        comment: String,
+    },
+    SetAuto {
+        user: String,
+        hw: usize,
+        score: f64,
+        comment: String,
+    },
+    SetExam {
+        user: String,
+        exam: usize,
+        num: usize,
+        den: usize,
+    },
+    Submissions {
+        hw: usize,
+        statuses: Vec<SubmissionStatus>,
+        sort: String,
+        reverse: bool,
+        csv: bool,
+        limit: Option<usize>,
+    },
+    UserInfo {
+        user: String,
+    },
+    Whois {
+        id: usize,
+    },
+    Completions {
+        shell: clap::Shell,
+    },
+}
+
+fn do_it() -> Result<bool> {
+    let mut config = config::Config::new();
+    let loaded = config.load_dotfile();
+    let command = process(&mut config);
+    config.activate_verbosity();
+
+    use self::Command::*;
+
+    if let Ok(Completions { shell }) = command {
+        // Buffered, since clap panics rather than report a write error:
+        let mut script = Vec::new();
+        clap_app::build_admin_cli().gen_completions_to("gsc-admin", shell, &mut script);
+        io::stdout().write_all(&script)?;
+        return Ok(false);
+    }
+
+    loaded?;
+    let command = command?;
+
+    let client = common::connect(config)?;
+
+    // ‘csv’ takes ‘--user’ to pick rows, not to act for anyone, and
+    // ‘audit’ only reads the local audit file:
+    if !matches!(command, Csv { .. } | Audit { .. }) {
+        client.announce_on_behalf();
+        client.check_staff_role()?;
+    }
+
+    let result = match command {
+        AddUser { user, role } => client.admin_add_user(&user, role),
+        DelUser { user } => client.admin_del_user(&user),
+        Audit { user, limit } => client.admin_audit(user.as_deref(), limit),
+        Csv {
+            hws,
+            user,
+            columns,
+            tsv,
+        } => client.admin_csv(&hws, user.as_deref(), &columns, tsv),
+        Diff {
+            hw,
+            user1,
+            user2,
+            pattern,
+        } => client.admin_diff(hw, &user1, &user2, &pattern),
+        Divorce { user, hw } => client.admin_divorce(&user, hw),
+        Extend {
+            user,
+            hw,
+            date,
+            eval,
+            from_now,
+        } => client.admin_extend(&user, hw, &date, eval, from_now),
+        Partners { user, hw } => client.admin_partners(&user, hw),
+        Permalink {
+            user,
+            hw,
+            number,
+            open,
+        } => client.admin_permalink(&user, hw, number, open),
+        Permalinks { hw, item, csv } => client.admin_permalinks(hw, item, csv),
+        Progress { hw } => client.admin_progress(hw),
+        Regrades { hw } => client.admin_regrades(hw),
+        SetGrade {
+            user,
+            hw,
+            number,
+            score,
+            comment,
+        } => client.admin_set_grade(&user, hw, number, score, &comment),
+        SetAuto {
+            user,
+            hw,
+            score,
+            comment,
+        } => client.admin_set_auto(&user, hw, score, &comment),
+        SetExam {
+            user,
+            exam,
+            num,
+            den,
+        } => client.admin_set_exam(&user, exam, num, den),
+        Submissions {
+            hw,
+            statuses,
+            sort,
+            reverse,
+            csv,
+            limit,
+        } => client.admin_submissions(hw, &statuses, &sort, reverse, csv, limit),
+        UserInfo { user } => client.admin_user_info(&user),
+        Whois { id } => client.admin_whois(id),
+        Completions { .. } => unreachable!("handled above"),
+    };
+
+    client.print_failure_summary();
+    result?;
+    client.finish_output()?;
+
+    Ok(client.had_warning())
+}
+
+fn process_bulk_opts(matches: &clap::ArgMatches, config: &mut config::Config) -> Result<()> {
+    if let Some(jobs) = matches.value_of("JOBS") {
+        config.set_jobs(jobs.parse_descr("number of jobs")?);
+    }
+
+    if let Some(pace) = matches.value_of("PACE") {
+        let ms = pace.parse_descr("number of milliseconds")?;
+        config.set_pace(Duration::from_millis(ms));
+    }
+
+    Ok(())
+}
+
+fn process(config: &mut config::Config) -> Result<Command> {
+    let matches = clap_app::build_admin_cli().get_matches();
+    process_common(&matches, config)?;
+
+    if let Some(submatches) = matches.subcommand_matches("completions") {
+        process_common(submatches, config)?;
+        let shell = submatches.value_of("SHELL").unwrap().parse().unwrap();
+        Ok(Command::Completions { shell })
+    } else if let Some(submatches) = matches.subcommand_matches("add_user") {
+        process_common(submatches, config)?;
+        let user = submatches.value_of("USER").unwrap().to_owned();
+        let role = if submatches.is_present("GRADER_ROLE") {
+            UserRole::Grader
+        } else if submatches.is_present("ADMIN_ROLE") {
+            UserRole::Admin
+        } else {
+            UserRole::Student
+        };
+        Ok(Command::AddUser { user, role })
+    } else if let Some(submatches) = matches.subcommand_matches("audit") {
+        process_common(submatches, config)?;
+        let user = submatches.value_of("USER").map(str::to_owned);
+        let limit = submatches
+            .value_of("LIMIT")
+            .map(|limit| limit.parse_descr("number of entries"))
+            .transpose()?
+            .unwrap_or(DEFAULT_AUDIT_LIMIT);
+        Ok(Command::Audit { user, limit })
+    } else if let Some(submatches) = matches.subcommand_matches("del_user") {
+        process_common(submatches, config)?;
+        process_yes_opt(submatches, config);
+        let user = submatches.value_of("USER").unwrap().to_owned();
+        Ok(Command::DelUser { user })
+    } else if let Some(submatches) = matches.subcommand_matches("csv") {
+        process_common(submatches, config)?;
+        let hws = submatches
+            .values_of("HW")
+            .into_iter()
+            .flatten()
+            .map(|spec| parse_hw(spec, config))
+            .collect::<Result<_>>()?;
+        // Acting on behalf of someone means nothing here, so
+        // ‘--user’ picks rows instead:
+        let user = submatches.value_of("ME").map(str::to_owned);
+        let columns = submatches
+            .values_of("COLUMNS")
+            .into_iter()
+            .flatten()
+            .map(str::to_owned)
+            .collect();
+        let tsv = submatches.is_present("TSV");
+        Ok(Command::Csv {
+            hws,
+            user,
+            columns,
+            tsv,
+        })
+    } else if let Some(submatches) = matches.subcommand_matches("diff") {
+        process_common(submatches, config)?;
+        let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+        let user1 = submatches.value_of("USER1").unwrap().to_owned();
+        let user2 = submatches.value_of("USER2").unwrap().to_owned();
+        let pattern = submatches.value_of("FILE").unwrap_or("").to_owned();
+        Ok(Command::Diff {
+            hw,
+            user1,
+            user2,
+            pattern,
+        })
+    } else if let Some(submatches) = matches.subcommand_matches("divorce") {
+        process_common(submatches, config)?;
+        let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+        let user = submatches.value_of("USER").unwrap().to_owned();
+        Ok(Command::Divorce { user, hw })
+    } else if let Some(submatches) = matches.subcommand_matches("extend") {
+        process_common(submatches, config)?;
+        let eval = submatches.is_present("EVAL");
+        let from_now = submatches.is_present("FROM_NOW");
+        let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+        let user = submatches.value_of("USER").unwrap().to_owned();
+        let date = submatches.value_of("DATESPEC").unwrap().to_owned();
+        Ok(Command::Extend {
+            hw,
+            user,
+            date,
+            eval,
+            from_now,
+        })
+    } else if let Some(submatches) = matches.subcommand_matches("partners") {
+        process_common(submatches, config)?;
+        let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+        let user = submatches.value_of("USER").unwrap().to_owned();
+        Ok(Command::Partners { user, hw })
+    } else if let Some(submatches) = matches.subcommand_matches("permalink") {
+        process_common(submatches, config)?;
+        let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+        let user = submatches.value_of("USER").unwrap().to_owned();
+        let number = submatches.value_of("NUMBER").unwrap().parse()?;
+        let open = submatches.is_present("OPEN");
+        Ok(Command::Permalink {
+            hw,
+            user,
+            number,
+            open,
+        })
+    } else if let Some(submatches) = matches.subcommand_matches("permalinks") {
+        process_common(submatches, config)?;
+        process_bulk_opts(submatches, config)?;
+        let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+        let item = submatches
+            .value_of("ITEM")
+            .map(|item| item.parse_descr("eval item number"))
+            .transpose()?;
+        let csv = submatches.is_present("CSV");
+        Ok(Command::Permalinks { hw, item, csv })
+    } else if let Some(submatches) = matches.subcommand_matches("progress") {
+        process_common(submatches, config)?;
+        process_bulk_opts(submatches, config)?;
+        let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+        Ok(Command::Progress { hw })
+    } else if let Some(submatches) = matches.subcommand_matches("regrades") {
+        process_common(submatches, config)?;
+        process_bulk_opts(submatches, config)?;
+        let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+        Ok(Command::Regrades { hw })
+    } else if let Some(submatches) = matches.subcommand_matches("set_grade") {
+        process_common(submatches, config)?;
+        let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+        let user = submatches.value_of("USER").unwrap().to_owned();
+        let number = submatches.value_of("NUMBER").unwrap().parse()?;
+        let score = submatches.value_of("SCORE").unwrap().parse()?;
+        let comment = submatches.value_of("COMMENT").unwrap().to_owned();
+        Ok(Command::SetGrade {
+            hw,
+            user,
+            number,
+            score,
+            comment,
+        })
+    } else if let Some(submatches) = matches.subcommand_matches("set_auto") {
+        process_common(submatches, config)?;
+        let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+        let user = submatches.value_of("USER").unwrap().to_owned();
+        let score = submatches.value_of("SCORE").unwrap().parse()?;
+        let comment = submatches.value_of("COMMENT").unwrap().to_owned();
+        Ok(Command::SetAuto {
+            hw,
+            user,
+            score,
+            comment,
+        })
+    } else if let Some(submatches) = matches.subcommand_matches("set_exam") {
+        process_common(submatches, config)?;
+        let exam = submatches
+            .value_of("EXAM")
+            .unwrap()
+            .parse_descr("exam number")?;
+        let user = submatches.value_of("USER").unwrap().to_owned();
+        let num = submatches
+            .value_of("POINTS")
+            .unwrap()
+            .parse_descr("points scored")?;
+        let den = submatches
+            .value_of("POSSIBLE")
+            .unwrap()
+            .parse_descr("points possible")?;
+        Ok(Command::SetExam {
+            user,
+            exam,
+            num,
+            den,
+        })
+    } else if let Some(submatches) = matches.subcommand_matches("submissions") {
+        process_common(submatches, config)?;
+        process_bulk_opts(submatches, config)?;
+        let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+        let statuses = submatches
+            .values_of("STATUS")
+            .into_iter()
+            .flatten()
+            .map(str::parse)
+            .collect::<Result<_>>()?;
+        let sort = submatches.value_of("SORT").unwrap_or("id").to_owned();
+        let reverse = submatches.is_present("REVERSE");
+        let csv = submatches.is_present("CSV");
+        let limit = parse_limit_opt(submatches)?;
+        Ok(Command::Submissions {
+            hw,
+            statuses,
+            sort,
+            reverse,
+            csv,
+            limit,
+        })
+    } else if let Some(submatches) = matches.subcommand_matches("user_info") {
+        process_common(submatches, config)?;
+        let user = submatches.value_of("USER").unwrap().to_owned();
+        Ok(Command::UserInfo { user })
+    } else if let Some(submatches) = matches.subcommand_matches("whois") {
+        process_common(submatches, config)?;
+        let id = submatches
+            .value_of("SUBMISSION_ID")
+            .unwrap()
+            .parse_descr("submission id")?;
+        Ok(Command::Whois { id })
+    } else {
+        Err(ErrorKind::NoCommandGiven)
+    }
+}
@@ -1,95 +1,82 @@
+use gsc_client::args::parse;
 use gsc_client::config;
-use gsc_client::messages::UserRole;
+use gsc_client::messages::FilePurpose;
 use gsc_client::prelude::*;
 
-use std::error::Error;
-use std::process::exit;
-use std::str::FromStr;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::env;
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
 
 mod clap_app;
+mod common;
 
-fn main() {
-    vlog::set_verbosity_level(3);
-
-    match do_it() {
-        Err(err) => {
-            ve1!("{}", err);
+use self::common::{current_hw, parse_hw, parse_limit_opt, process_common, process_yes_opt};
 
-            let mut source = err.source();
+const DEFAULT_LOG_INTERVAL_SECS: u64 = 10;
 
-            while let Some(error) = source {
-                ve1!("Source: {}", error);
-                source = error.source();
-            }
-
-            exit(1);
-        }
-        Ok(true) => exit(2),
-        Ok(false) => (),
-    }
+fn main() {
+    vlog::set_verbosity_level(3);
+    common::exit_with(do_it());
 }
 
 enum Command {
-    AdminAddUser {
-        user: String,
-        role: UserRole,
+    Api {
+        method: String,
+        path: String,
+        data: Option<String>,
     },
-    AdminDelUser {
-        user: String,
-    },
-    AdminCsv,
-    AdminDivorce {
-        user: String,
-        hw: usize,
-    },
-    AdminExtend {
-        user: String,
+    Archive {
         hw: usize,
-        date: String,
-        eval: bool,
+        dst: PathBuf,
     },
-    AdminPartners {
+    Auth {
         user: String,
-        hw: usize,
+        stdin: bool,
     },
-    AdminPermalink {
-        user: String,
-        hw: usize,
-        number: usize,
-    },
-    AdminSetGrade {
-        user: String,
-        hw: usize,
-        number: usize,
-        score: f64,
-        comment: String,
+    Cat {
+        rpats: Vec<RemotePattern>,
     },
-    AdminSetAuto {
-        user: String,
-        hw: usize,
-        score: f64,
-        comment: String,
+    Chtype {
+        rpats: Vec<RemotePattern>,
+        purpose: FilePurpose,
     },
-    AdminSetExam {
-        user: String,
-        exam: usize,
-        num: usize,
-        den: usize,
+    Completions {
+        shell: clap::Shell,
     },
-    AdminSubmissions {
-        hw: usize,
+    ConfigEdit,
+    ConfigGet {
+        key: String,
     },
-    Auth {
-        user: String,
+    ConfigList,
+    ConfigSet {
+        key: String,
+        value: String,
     },
-    Cat {
-        rpats: Vec<RemotePattern>,
+    ConfigUnset {
+        key: String,
     },
     Cp {
         srcs: Vec<CpArg>,
         dst: CpArg,
     },
+    CpExtract {
+        srcs: Vec<PathBuf>,
+        hw: usize,
+    },
+    CpUpAll {
+        srcs: Vec<PathBuf>,
+        hw: usize,
+    },
+    Deadlines,
     Deauth,
+    Diff {
+        rpat: RemotePattern,
+        local: PathBuf,
+    },
     EvalGet {
         hw: usize,
         number: usize,
@@ -97,17 +84,37 @@ enum Command {
     EvalSet {
         hw: usize,
         number: usize,
-        score: f64,
+        score: EvalScore,
         explanation: String,
+        allow_empty: bool,
+    },
+    Grade {
+        hw: usize,
+    },
+    History {
+        hw: Option<usize>,
+    },
+    Log {
+        hw: usize,
+        latest: bool,
+        follow: Option<Duration>,
     },
     Ls {
         rpats: Vec<RemotePattern>,
+        tree: bool,
+        limit: Option<usize>,
     },
     Mv {
         src: RemotePattern,
         dst: RemoteDestination,
     },
+    Open {
+        rpat: RemotePattern,
+        print_url: bool,
+    },
     Partner,
+    PartnerList,
+    Ping,
     PartnerRequest {
         hw: usize,
         them: String,
@@ -120,103 +127,149 @@ enum Command {
         hw: usize,
         them: String,
     },
+    PushGit {
+        hw: usize,
+        tag: bool,
+    },
+    Regrade {
+        hw: usize,
+        number: usize,
+        reason: String,
+    },
     Rm {
         rpats: Vec<RemotePattern>,
     },
     Status {
         hw: Option<usize>,
     },
+    StatusAll,
+    Submit {
+        hw: usize,
+        receipt: Option<PathBuf>,
+    },
+    Verify {
+        hw: usize,
+        dir: PathBuf,
+    },
     Whoami,
 }
 
 fn do_it() -> Result<bool> {
-    let mut client = GscClient::new()?;
-    let command = GscClientApp::new().process(client.config_mut())?;
-    client.config().activate_verbosity();
+    let mut config = config::Config::new();
+    let loaded = config.load_dotfile();
+    let command = GscClientApp::new().process(&mut config);
+    config.activate_verbosity();
 
     use self::Command::*;
 
+    // These don’t need a working dotfile, so that they can repair one:
     match command {
-        AdminAddUser { user, role } => client.admin_add_user(&user, role),
-        AdminDelUser { user } => client.admin_del_user(&user),
-        AdminCsv => client.admin_csv(),
-        AdminDivorce { user, hw } => client.admin_divorce(&user, hw),
-        AdminExtend {
-            user,
-            hw,
-            date,
-            eval,
-        } => client.admin_extend(&user, hw, &date, eval),
-        AdminPartners { user, hw } => client.admin_partners(&user, hw),
-        AdminPermalink { user, hw, number } => client.admin_permalink(&user, hw, number),
-        AdminSetGrade {
-            user,
-            hw,
-            number,
-            score,
-            comment,
-        } => client.admin_set_grade(&user, hw, number, score, &comment),
-        AdminSetAuto {
-            user,
-            hw,
-            score,
-            comment,
-        } => client.admin_set_auto(&user, hw, score, &comment),
-        AdminSetExam {
-            user,
-            exam,
-            num,
-            den,
-        } => client.admin_set_exam(&user, exam, num, den),
-        AdminSubmissions { hw } => client.admin_submissions(hw),
-        Auth { user } => client.auth(&user),
+        Ok(Completions { shell }) => {
+            // Buffered, since clap panics rather than report a write error:
+            let mut script = Vec::new();
+            clap_app::build_cli().gen_completions_to("gsc", shell, &mut script);
+            io::stdout().write_all(&script)?;
+            return Ok(false);
+        }
+        Ok(ConfigEdit) => return config.config_edit().map(|()| false),
+        Ok(ConfigGet { key }) => return config.config_get(&key).map(|()| false),
+        Ok(ConfigList) => return config.config_list().map(|()| false),
+        Ok(ConfigSet { key, value }) => return config.config_set(&key, &value).map(|()| false),
+        Ok(ConfigUnset { key }) => return config.config_unset(&key).map(|()| false),
+        _ => {}
+    }
+
+    loaded?;
+    let command = command?;
+
+    let mut client = common::connect(config)?;
+    let update_check = client.start_update_check();
+
+    client.announce_on_behalf();
+
+    let result = match command {
+        Api { method, path, data } => client.api(&method, &path, data.as_deref()),
+        Archive { hw, dst } => client.archive(hw, &dst),
+        Auth { user, stdin } => client.auth(&user, stdin),
         Cat { rpats } => client.cat(&rpats),
+        Chtype { rpats, purpose } => client.chtype(&rpats, purpose),
+        Completions { .. }
+        | ConfigEdit
+        | ConfigGet { .. }
+        | ConfigList
+        | ConfigSet { .. }
+        | ConfigUnset { .. } => {
+            unreachable!("handled above")
+        }
         Cp { srcs, dst } => client.cp(&srcs, &dst),
+        CpExtract { srcs, hw } => client.cp_extract(&srcs, hw),
+        CpUpAll { srcs, hw } => client.cp_up_all(&srcs, hw),
+        Deadlines => client.deadlines(),
         Deauth => client.deauth(),
+        Diff { rpat, local } => client.diff(&rpat, &local),
         EvalGet { hw, number } => client.get_eval(hw, number),
         EvalSet {
             hw,
             number,
             score,
             explanation,
-        } => client.set_eval(hw, number, score, &explanation),
-        Ls { rpats } => client.ls(&rpats),
+            allow_empty,
+        } => client.set_eval(hw, number, score, &explanation, allow_empty),
+        Grade { hw } => client.grade(hw),
+        History { hw } => client.history(hw),
+        Log { hw, latest, follow } => client.log(hw, latest, follow),
+        Ls {
+            rpats,
+            tree: false,
+            limit,
+        } => client.ls(&rpats, limit),
+        Ls {
+            rpats, tree: true, ..
+        } => client.ls_tree(&rpats),
         Mv { src, dst } => client.mv(&src, &dst),
+        Open { rpat, print_url } => client.open(&rpat, print_url),
         Partner => client.partner(),
+        PartnerList => client.partner_list(),
         PartnerRequest { hw, them } => client.partner_request(hw, &them),
         PartnerAccept { hw, them } => client.partner_accept(hw, &them),
         PartnerCancel { hw, them } => client.partner_cancel(hw, &them),
+        Ping => client.ping(),
+        PushGit { hw, tag } => client.push_git(hw, tag),
+        Regrade { hw, number, reason } => client.regrade(hw, number, &reason),
         Rm { rpats } => client.rm(&rpats),
         Status { hw: Some(i) } => client.status_hw(i),
         Status { hw: None } => client.status_user(),
+        StatusAll => client.status_all(),
+        Submit { hw, receipt } => client.submit(hw, receipt.as_deref()),
+        Verify { hw, dir } => client.verify(hw, &dir),
         Whoami => client.whoami(),
-    }?;
+    };
+
+    client.print_failure_summary();
+    result?;
+    client.finish_output()?;
 
+    client.finish_update_check(update_check);
     Ok(client.had_warning())
 }
 
 struct GscClientApp<'a: 'b, 'b>(clap::App<'a, 'b>);
 
-fn process_common<'a>(matches: &clap::ArgMatches<'a>, config: &mut config::Config) {
-    let vs = matches.occurrences_of("VERBOSE") as isize;
-    let qs = matches.occurrences_of("QUIET") as isize;
-    let verbosity = config.get_verbosity() + vs - qs;
-    config.set_verbosity(verbosity);
-    config.set_json_output(matches.is_present("JSON") && !matches.is_present("HUMAN"));
-
-    if let Some(user) = matches.value_of("ME") {
-        config.set_on_behalf(user.to_owned());
+fn process_porcelain_opt<'a>(matches: &clap::ArgMatches<'a>, config: &mut config::Config) {
+    if matches.is_present("PORCELAIN") {
+        config.set_json_output(false);
+        config.set_porcelain_output(true);
     }
 }
 
 fn process_overwrite_opts<'a>(matches: &clap::ArgMatches<'a>, config: &mut config::Config) {
-    config.set_overwrite_policy(if matches.is_present("ALWAYS") {
-        config::OverwritePolicy::Always
+    if matches.is_present("ALWAYS") {
+        config.set_overwrite_policy(config::OverwritePolicy::Always);
     } else if matches.is_present("NEVER") {
-        config::OverwritePolicy::Never
-    } else {
-        config::OverwritePolicy::Ask
-    });
+        config.set_overwrite_policy(config::OverwritePolicy::Never);
+    } else if matches.is_present("ASK") {
+        config.set_overwrite_policy(config::OverwritePolicy::Ask);
+    }
 }
 
 impl<'a, 'b> GscClientApp<'a, 'b> {
@@ -224,126 +277,120 @@ impl<'a, 'b> GscClientApp<'a, 'b> {
         GscClientApp(clap_app::build_cli())
     }
 
-    fn process(self, config: &mut config::Config) -> Result<Command> {
-        let matches = self.0.get_matches();
-        process_common(&matches, config);
-
-        if let Some(submatches) = matches.subcommand_matches("admin") {
-            process_common(submatches, config);
-
-            if let Some(subsubmatches) = submatches.subcommand_matches("add_user") {
-                process_common(subsubmatches, config);
-                let user = subsubmatches.value_of("USER").unwrap().to_owned();
-                let role =
-                    if subsubmatches.is_present("GRADER_ROLE") {
-                        UserRole::Grader
-                    } else if subsubmatches.is_present("ADMIN_ROLE") {
-                        UserRole::Admin
-                    } else {
-                        UserRole::Student
-                    };
-                Ok(Command::AdminAddUser { user, role })
-            } else if let Some(subsubmatches) = submatches.subcommand_matches("del_user") {
-                process_common(subsubmatches, config);
-                let user = subsubmatches.value_of("USER").unwrap().to_owned();
-                Ok(Command::AdminDelUser { user })
-            } else if let Some(subsubmatches) = submatches.subcommand_matches("csv") {
-                process_common(subsubmatches, config);
-                Ok(Command::AdminCsv)
-            } else if let Some(subsubmatches) = submatches.subcommand_matches("divorce") {
-                process_common(subsubmatches, config);
-                let hw = parse_hw(subsubmatches.value_of("HW").unwrap())?;
-                let user = subsubmatches.value_of("USER").unwrap().to_owned();
-                Ok(Command::AdminDivorce { user, hw })
-            } else if let Some(subsubmatches) = submatches.subcommand_matches("extend") {
-                process_common(subsubmatches, config);
-                let eval = subsubmatches.is_present("EVAL");
-                let hw = parse_hw(subsubmatches.value_of("HW").unwrap())?;
-                let user = subsubmatches.value_of("USER").unwrap().to_owned();
-                let date = subsubmatches.value_of("DATESPEC").unwrap().to_owned();
-                Ok(Command::AdminExtend {
-                    hw,
-                    user,
-                    date,
-                    eval,
-                })
-            } else if let Some(subsubmatches) = submatches.subcommand_matches("partners") {
-                process_common(subsubmatches, config);
-                let hw = parse_hw(subsubmatches.value_of("HW").unwrap())?;
-                let user = subsubmatches.value_of("USER").unwrap().to_owned();
-                Ok(Command::AdminPartners { user, hw })
-            } else if let Some(subsubmatches) = submatches.subcommand_matches("permalink") {
-                process_common(subsubmatches, config);
-                let hw = parse_hw(subsubmatches.value_of("HW").unwrap())?;
-                let user = subsubmatches.value_of("USER").unwrap().to_owned();
-                let number = subsubmatches.value_of("NUMBER").unwrap().parse()?;
-                Ok(Command::AdminPermalink { hw, user, number })
-            } else if let Some(subsubmatches) = submatches.subcommand_matches("set_grade") {
-                process_common(subsubmatches, config);
-                let hw = parse_hw(subsubmatches.value_of("HW").unwrap())?;
-                let user = subsubmatches.value_of("USER").unwrap().to_owned();
-                let number = subsubmatches.value_of("NUMBER").unwrap().parse()?;
-                let score = subsubmatches.value_of("SCORE").unwrap().parse()?;
-                let comment = subsubmatches.value_of("COMMENT").unwrap().to_owned();
-                Ok(Command::AdminSetGrade {
-                    hw,
-                    user,
-                    number,
-                    score,
-                    comment,
-                })
-            } else if let Some(subsubmatches) = submatches.subcommand_matches("set_auto") {
-                process_common(subsubmatches, config);
-                let hw = parse_hw(subsubmatches.value_of("HW").unwrap())?;
-                let user = subsubmatches.value_of("USER").unwrap().to_owned();
-                let score = subsubmatches.value_of("SCORE").unwrap().parse()?;
-                let comment = subsubmatches.value_of("COMMENT").unwrap().to_owned();
-                Ok(Command::AdminSetAuto {
-                    hw,
-                    user,
-                    score,
-                    comment,
-                })
-            } else if let Some(subsubmatches) = submatches.subcommand_matches("set_exam") {
-                process_common(subsubmatches, config);
-                let exam = subsubmatches
-                    .value_of("EXAM")
-                    .unwrap()
-                    .parse_descr("exam number")?;
-                let user = subsubmatches.value_of("USER").unwrap().to_owned();
-                let num = subsubmatches
-                    .value_of("POINTS")
-                    .unwrap()
-                    .parse_descr("points scored")?;
-                let den = subsubmatches
-                    .value_of("POSSIBLE")
-                    .unwrap()
-                    .parse_descr("points possible")?;
-                Ok(Command::AdminSetExam {
-                    user,
-                    exam,
-                    num,
-                    den,
-                })
-            } else if let Some(subsubmatches) = submatches.subcommand_matches("submissions") {
-                process_common(subsubmatches, config);
-                let hw = parse_hw(subsubmatches.value_of("HW").unwrap())?;
-                Ok(Command::AdminSubmissions { hw })
-            } else {
-                Err(ErrorKind::NoCommandGiven.into())
+    /// Parses the command line, first expanding any alias from the dotfile
+    /// that appears where the subcommand should be. Built-in subcommands
+    /// can’t be shadowed, since only a subcommand that clap doesn’t
+    /// recognize is looked up.
+    fn get_matches_expanding_aliases(
+        &mut self,
+        config: &config::Config,
+    ) -> Result<clap::ArgMatches<'a>> {
+        let mut args: Vec<OsString> = env::args_os().collect();
+        let mut expanded = HashSet::new();
+
+        loop {
+            let error = match self.0.get_matches_from_safe_borrow(&args) {
+                Ok(matches) => return Ok(matches),
+                Err(error) => error,
+            };
+
+            let name = match (&error.kind, &error.info) {
+                (clap::ErrorKind::InvalidSubcommand, Some(info))
+                | (clap::ErrorKind::UnrecognizedSubcommand, Some(info))
+                | (clap::ErrorKind::UnknownArgument, Some(info)) => info[0].clone(),
+                _ => error.exit(),
+            };
+
+            let alias = match config.get_alias(&name) {
+                Some(alias) => alias,
+                None => match self.unknown_command(&name, &args, config) {
+                    Some(kind) => Err(kind)?,
+                    None => error.exit(),
+                },
+            };
+
+            // The alias has to be in the subcommand’s place, not an
+            // argument to some other subcommand:
+            let position = match (1..args.len())
+                .find(|&i| args[i] == *name && self.is_top_level(&args[..i]))
+            {
+                Some(position) => position,
+                None => error.exit(),
+            };
+
+            if !expanded.insert(name.clone()) {
+                Err(ErrorKind::AliasLoop(name.clone()))?;
             }
+
+            let words: Vec<OsString> = alias.split_whitespace().map(OsString::from).collect();
+            if words.is_empty() {
+                Err(ErrorKind::EmptyAlias(name.clone()))?;
+            }
+
+            args.splice(position..=position, words);
+        }
+    }
+
+    /// Suggests a subcommand or alias for `name`, if it’s in the
+    /// subcommand position of `args` and near enough to one. (Clap makes
+    /// its own suggestions, but only among close built-in subcommands.)
+    fn unknown_command(
+        &mut self,
+        name: &str,
+        args: &[OsString],
+        config: &config::Config,
+    ) -> Option<ErrorKind> {
+        (1..args.len()).find(|&i| args[i] == *name && self.is_top_level(&args[..i]))?;
+
+        if name == "admin" {
+            return Some(ErrorKind::AdminCommandsMoved);
+        }
+
+        let subcommands = self.0.p.subcommands.iter().map(|app| app.get_name());
+        let aliases = config.get_alias_names();
+        ErrorKind::unknown_command(name, subcommands.chain(aliases))
+    }
+
+    /// Whether `args` parse as global options alone, with no subcommand.
+    fn is_top_level(&mut self, args: &[OsString]) -> bool {
+        match self.0.get_matches_from_safe_borrow(args) {
+            Ok(matches) => matches.subcommand_name().is_none(),
+            Err(_) => false,
+        }
+    }
+
+    fn process(mut self, config: &mut config::Config) -> Result<Command> {
+        let matches = self.get_matches_expanding_aliases(config)?;
+        process_common(&matches, config)?;
+
+        if let Some(submatches) = matches.subcommand_matches("api") {
+            process_common(submatches, config)?;
+            let method = submatches.value_of("METHOD").unwrap().to_owned();
+            let path = submatches.value_of("PATH").unwrap().to_owned();
+            let data = submatches.value_of("DATA").map(str::to_owned);
+            Ok(Command::Api { method, path, data })
+        } else if let Some(submatches) = matches.subcommand_matches("archive") {
+            process_common(submatches, config)?;
+            process_overwrite_opts(submatches, config);
+            let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+            // The archive itself is the output:
+            let dst = config
+                .take_output_file()
+                .unwrap_or_else(|| PathBuf::from(format!("hw{}.zip", hw)));
+            Ok(Command::Archive { hw, dst })
         } else if let Some(submatches) = matches.subcommand_matches("auth") {
-            process_common(submatches, config);
+            process_common(submatches, config)?;
             let user = submatches.value_of("USER").unwrap().to_owned();
-            Ok(Command::Auth { user })
+            let stdin = submatches.is_present("STDIN");
+            Ok(Command::Auth { user, stdin })
         } else if let Some(submatches) = matches.subcommand_matches("cat") {
-            process_common(submatches, config);
+            process_common(submatches, config)?;
             let all = submatches.is_present("ALL");
 
             let mut rpats = Vec::new();
 
             for arg in submatches.values_of("SPEC").unwrap() {
-                let rpat = parse_hw_opt_file(arg)?;
+                let rpat = parse_hw_opt_file(arg, config)?;
 
                 if rpat.is_whole_hw() && !all {
                     Err(ErrorKind::CommandRequiresFlag("cat".to_owned()))?;
@@ -353,17 +400,95 @@ impl<'a, 'b> GscClientApp<'a, 'b> {
             }
 
             Ok(Command::Cat { rpats })
+        } else if let Some(submatches) = matches.subcommand_matches("chtype") {
+            process_common(submatches, config)?;
+
+            let mut rpats = Vec::new();
+            for arg in submatches.values_of("SPEC").unwrap() {
+                rpats.push(parse_hw_file(arg, config)?);
+            }
+
+            let purpose = submatches.value_of("TYPE").unwrap().parse()?;
+
+            Ok(Command::Chtype { rpats, purpose })
+        } else if let Some(submatches) = matches.subcommand_matches("completions") {
+            process_common(submatches, config)?;
+            let shell = submatches.value_of("SHELL").unwrap().parse().unwrap();
+            Ok(Command::Completions { shell })
+        } else if let Some(submatches) = matches.subcommand_matches("config") {
+            process_common(submatches, config)?;
+
+            if let Some(subsubmatches) = submatches.subcommand_matches("get") {
+                process_common(subsubmatches, config)?;
+                let key = subsubmatches.value_of("KEY").unwrap().to_owned();
+                Ok(Command::ConfigGet { key })
+            } else if let Some(subsubmatches) = submatches.subcommand_matches("set") {
+                process_common(subsubmatches, config)?;
+                let key = subsubmatches.value_of("KEY").unwrap().to_owned();
+                let value = subsubmatches.value_of("VALUE").unwrap().to_owned();
+                Ok(Command::ConfigSet { key, value })
+            } else if let Some(subsubmatches) = submatches.subcommand_matches("unset") {
+                process_common(subsubmatches, config)?;
+                let key = subsubmatches.value_of("KEY").unwrap().to_owned();
+                Ok(Command::ConfigUnset { key })
+            } else if let Some(subsubmatches) = submatches.subcommand_matches("list") {
+                process_common(subsubmatches, config)?;
+                Ok(Command::ConfigList)
+            } else if let Some(subsubmatches) = submatches.subcommand_matches("edit") {
+                process_common(subsubmatches, config)?;
+                Ok(Command::ConfigEdit)
+            } else {
+                Err(ErrorKind::NoCommandGiven)
+            }
         } else if let Some(submatches) = matches.subcommand_matches("cp") {
-            process_common(submatches, config);
+            process_common(submatches, config)?;
             let all = submatches.is_present("ALL");
 
             process_overwrite_opts(&submatches, config);
 
+            if submatches.is_present("NO_VERIFY") {
+                config.set_no_verify(true);
+            }
+
+            if submatches.is_present("EXTRACT") {
+                let dst = submatches.value_of("DST").unwrap();
+                let expanded = expand_current(dst, config)?;
+                let hw = parse_hw(&expanded, config)
+                    .map_err(|_| ErrorKind::ExtractNeedsHomework(dst.to_owned()))?;
+
+                let mut srcs = Vec::new();
+                for src in submatches.values_of("SRC").unwrap() {
+                    match parse_cp_arg(src, config)? {
+                        CpArg::Local(filename) => srcs.push(filename),
+                        CpArg::Remote(rpat) => Err(ErrorKind::CannotCopyRemoteToRemote(
+                            rpat,
+                            RemotePattern::just_hw(hw),
+                        ))?,
+                    }
+                }
+
+                return Ok(Command::CpExtract { srcs, hw });
+            }
+
             let mut srcs = Vec::new();
-            let dst = parse_cp_arg(submatches.value_of("DST").unwrap())?;
+            let dst_spec = submatches.value_of("DST").unwrap();
+            let dst = parse_cp_arg(dst_spec, config)?;
+
+            if let Some(media_type) = submatches.value_of("MEDIA_TYPE") {
+                config.set_media_type(media_type.parse()?);
+            }
+
+            if let Some(template) = submatches.value_of("INTO") {
+                match &dst {
+                    CpArg::Remote(rpat) if rpat.is_whole_hw() => {}
+                    _ => Err(ErrorKind::IntoNeedsHomework(dst_spec.to_owned()))?,
+                }
+
+                config.set_upload_name_template(template.parse()?);
+            }
 
             for src in submatches.values_of("SRC").unwrap() {
-                let arg = parse_cp_arg(src)?;
+                let arg = parse_cp_arg(src, config)?;
 
                 if arg.is_whole_hw() && !all {
                     Err(ErrorKind::CommandRequiresFlag("cp".to_owned()))?;
@@ -372,32 +497,70 @@ impl<'a, 'b> GscClientApp<'a, 'b> {
                 srcs.push(arg);
             }
 
+            // With ‘-a’, local directories are uploaded as ‘cp -a’ lays
+            // them out:
+            if let CpArg::Remote(rpat) = &dst {
+                let dirs: Vec<_> = srcs
+                    .iter()
+                    .filter_map(|src| match src {
+                        CpArg::Local(path) if path.is_dir() => Some(path.clone()),
+                        _ => None,
+                    })
+                    .collect();
+
+                if all && rpat.is_whole_hw() && dirs.len() == srcs.len() {
+                    return Ok(Command::CpUpAll {
+                        srcs: dirs,
+                        hw: rpat.hw,
+                    });
+                }
+            }
+
             Ok(Command::Cp { srcs, dst })
+        } else if let Some(submatches) = matches.subcommand_matches("deadlines") {
+            process_common(submatches, config)?;
+            Ok(Command::Deadlines)
         } else if let Some(submatches) = matches.subcommand_matches("deauth") {
-            process_common(submatches, config);
+            process_common(submatches, config)?;
             Ok(Command::Deauth)
+        } else if let Some(submatches) = matches.subcommand_matches("diff") {
+            process_common(submatches, config)?;
+
+            let rpat = parse_hw_opt_file(submatches.value_of("SPEC").unwrap(), config)?;
+            if rpat.is_whole_hw() && !submatches.is_present("ALL") {
+                Err(ErrorKind::CommandRequiresFlag("diff".to_owned()))?;
+            }
+
+            let local = submatches
+                .value_of_os("LOCAL")
+                .unwrap_or(".".as_ref())
+                .into();
+
+            Ok(Command::Diff { rpat, local })
         } else if let Some(submatches) = matches.subcommand_matches("eval") {
-            process_common(submatches, config);
+            process_common(submatches, config)?;
 
             let mut process_eval = |matches: &clap::ArgMatches| -> Result<_> {
-                process_common(matches, config);
+                process_common(matches, config)?;
                 let hw = matches.value_of("HW").unwrap();
                 let number = matches.value_of("NUMBER").unwrap();
-                Ok((parse_hw(hw)?, number.parse()?))
+                Ok((parse_hw(hw, config)?, number.parse()?))
             };
 
             if let Some(subsubmatches) = submatches.subcommand_matches("set") {
                 let (hw, number) = process_eval(subsubmatches)?;
-                let score = 0.01 * subsubmatches.value_of("SCORE").unwrap().parse::<f64>()?;
+                let score = parse::parse_eval_score(subsubmatches.value_of("SCORE").unwrap())?;
                 let explanation = subsubmatches
                     .value_of("EXPLANATION")
                     .unwrap_or("")
                     .to_owned();
+                let allow_empty = subsubmatches.is_present("ALLOW_EMPTY");
                 Ok(Command::EvalSet {
                     hw,
                     number,
                     score,
                     explanation,
+                    allow_empty,
                 })
             } else if let Some(subsubmatches) = submatches.subcommand_matches("get") {
                 let (hw, number) = process_eval(subsubmatches)?;
@@ -405,36 +568,103 @@ impl<'a, 'b> GscClientApp<'a, 'b> {
             } else {
                 panic!("No other eval commands");
             }
+        } else if let Some(submatches) = matches.subcommand_matches("grade") {
+            process_common(submatches, config)?;
+            let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+            Ok(Command::Grade { hw })
+        } else if let Some(submatches) = matches.subcommand_matches("history") {
+            process_common(submatches, config)?;
+            let hw = submatches
+                .value_of("HW")
+                .map(|spec| parse_hw(spec, config))
+                .transpose()?;
+            Ok(Command::History { hw })
+        } else if let Some(submatches) = matches.subcommand_matches("log") {
+            process_common(submatches, config)?;
+
+            let hw = match submatches.value_of("HW") {
+                Some(hw_spec) => parse_hw(hw_spec, config)?,
+                None => config.get_default_hw().ok_or(ErrorKind::NoHomeworkGiven)?,
+            };
+
+            let follow = if submatches.is_present("FOLLOW") {
+                let secs = match submatches.value_of("INTERVAL") {
+                    Some(secs) => secs
+                        .parse()
+                        .map_err(|_| ErrorKind::syntax("number of seconds", secs))?,
+                    None => DEFAULT_LOG_INTERVAL_SECS,
+                };
+                Some(Duration::from_secs(secs))
+            } else {
+                None
+            };
+
+            Ok(Command::Log {
+                hw,
+                latest: submatches.is_present("LATEST"),
+                follow,
+            })
         } else if let Some(submatches) = matches.subcommand_matches("ls") {
-            process_common(submatches, config);
+            process_common(submatches, config)?;
+            process_porcelain_opt(submatches, config);
 
-            let ls_specs = submatches.values_of("SPEC").unwrap();
             let mut rpats = Vec::new();
 
-            for ls_spec in ls_specs {
-                rpats.push(parse_hw_opt_file(ls_spec)?);
+            if let Some(ls_specs) = submatches.values_of("SPEC") {
+                for ls_spec in ls_specs {
+                    rpats.push(parse_hw_opt_file(ls_spec, config)?);
+                }
+            } else if let Some(hw) = config.get_default_hw() {
+                rpats.push(RemotePattern {
+                    hw,
+                    name: String::new(),
+                });
+            } else {
+                Err(ErrorKind::NoHomeworkGiven)?;
             }
 
-            Ok(Command::Ls { rpats })
+            let tree = submatches.is_present("TREE");
+            let limit = parse_limit_opt(submatches)?;
+            Ok(Command::Ls { rpats, tree, limit })
         } else if let Some(submatches) = matches.subcommand_matches("mv") {
-            process_common(submatches, config);
+            process_common(submatches, config)?;
             process_overwrite_opts(submatches, config);
 
-            let src = parse_hw_file(submatches.value_of("SRC").unwrap())?;
-            let dst = parse_remote_dest(submatches.value_of("DST").unwrap())?;
+            let src = parse_hw_file(submatches.value_of("SRC").unwrap(), config)?;
+            let dst = parse_remote_dest(submatches.value_of("DST").unwrap(), config)?;
 
             Ok(Command::Mv { src, dst })
+        } else if let Some(submatches) = matches.subcommand_matches("open") {
+            process_common(submatches, config)?;
+
+            let rpat = match submatches.value_of("SPEC") {
+                Some(spec) => parse_hw_opt_file(spec, config)?,
+                None => RemotePattern {
+                    hw: config.get_default_hw().ok_or(ErrorKind::NoHomeworkGiven)?,
+                    name: String::new(),
+                },
+            };
+
+            Ok(Command::Open {
+                rpat,
+                print_url: submatches.is_present("PRINT_URL"),
+            })
         } else if let Some(submatches) = matches.subcommand_matches("partner") {
-            process_common(submatches, config);
+            process_common(submatches, config)?;
+            process_porcelain_opt(submatches, config);
 
             let mut process_partner = |matches: &clap::ArgMatches| -> Result<_> {
-                process_common(matches, config);
+                process_common(matches, config)?;
                 let hw = matches.value_of("HW").unwrap();
                 let them = matches.value_of("USER").unwrap();
-                Ok((parse_hw(hw)?, them.to_owned()))
+                Ok((parse_hw(hw, config)?, them.to_owned()))
             };
 
-            if let Some(subsubmatches) = submatches.subcommand_matches("request") {
+            if let Some(subsubmatches) = submatches.subcommand_matches("list") {
+                process_common(subsubmatches, config)?;
+                process_porcelain_opt(subsubmatches, config);
+                Ok(Command::PartnerList)
+            } else if let Some(subsubmatches) = submatches.subcommand_matches("request") {
                 let (hw, them) = process_partner(subsubmatches)?;
                 Ok(Command::PartnerRequest { hw, them })
             } else if let Some(subsubmatches) = submatches.subcommand_matches("accept") {
@@ -446,13 +676,33 @@ impl<'a, 'b> GscClientApp<'a, 'b> {
             } else {
                 Ok(Command::Partner)
             }
+        } else if let Some(submatches) = matches.subcommand_matches("ping") {
+            process_common(submatches, config)?;
+            Ok(Command::Ping)
+        } else if let Some(submatches) = matches.subcommand_matches("push") {
+            process_common(submatches, config)?;
+
+            if submatches.is_present("NO_VERIFY") {
+                config.set_no_verify(true);
+            }
+
+            let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+            let tag = submatches.is_present("TAG");
+            Ok(Command::PushGit { hw, tag })
+        } else if let Some(submatches) = matches.subcommand_matches("regrade") {
+            process_common(submatches, config)?;
+            let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+            let number = submatches.value_of("NUMBER").unwrap().parse()?;
+            let reason = submatches.value_of("REASON").unwrap().to_owned();
+            Ok(Command::Regrade { hw, number, reason })
         } else if let Some(submatches) = matches.subcommand_matches("rm") {
-            process_common(submatches, config);
+            process_common(submatches, config)?;
+            process_yes_opt(submatches, config);
             let all = submatches.is_present("ALL");
             let mut rpats = Vec::new();
 
             for arg in submatches.values_of("SPEC").unwrap() {
-                let rpat = parse_hw_opt_file(arg)?;
+                let rpat = parse_hw_opt_file(arg, config)?;
 
                 if rpat.is_whole_hw() && !all {
                     Err(ErrorKind::CommandRequiresFlag("rm".to_owned()))?;
@@ -463,108 +713,53 @@ impl<'a, 'b> GscClientApp<'a, 'b> {
 
             Ok(Command::Rm { rpats })
         } else if let Some(submatches) = matches.subcommand_matches("status") {
-            process_common(submatches, config);
+            process_common(submatches, config)?;
+            process_porcelain_opt(submatches, config);
+            if submatches.is_present("ALL") {
+                return Ok(Command::StatusAll);
+            }
             let hw = match submatches.value_of("HW") {
-                Some(hw_spec) => Some(parse_hw(hw_spec)?),
+                Some(hw_spec) => Some(parse_hw(hw_spec, config)?),
                 None => None,
             };
             Ok(Command::Status { hw })
+        } else if let Some(submatches) = matches.subcommand_matches("submit") {
+            process_common(submatches, config)?;
+            process_overwrite_opts(submatches, config);
+            let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+            let receipt = submatches.value_of_os("RECEIPT").map(PathBuf::from);
+            Ok(Command::Submit { hw, receipt })
+        } else if let Some(submatches) = matches.subcommand_matches("verify") {
+            process_common(submatches, config)?;
+            process_porcelain_opt(submatches, config);
+            let hw = parse_hw(submatches.value_of("HW").unwrap(), config)?;
+            let dir = submatches.value_of_os("DIR").unwrap_or(".".as_ref()).into();
+            Ok(Command::Verify { hw, dir })
         } else if let Some(submatches) = matches.subcommand_matches("whoami") {
-            process_common(submatches, config);
+            process_common(submatches, config)?;
             Ok(Command::Whoami)
         } else {
-            Err(ErrorKind::NoCommandGiven.into())
+            Err(ErrorKind::NoCommandGiven)
         }
     }
 }
 
-mod re {
-    use lazy_static::lazy_static;
-    use regex::Regex;
-
-    lazy_static! {
-        pub static ref HW_ONLY: Regex = Regex::new(r"^hw(\d+):?$").unwrap();
-        pub static ref HW_OPT_FILE: Regex = Regex::new(r"^hw(\d+)(?::(.*))?$").unwrap();
-        pub static ref HW_FILE: Regex = Regex::new(r"^hw(\d+):(.*)$").unwrap();
-        pub static ref LOCAL_FILE: Regex = Regex::new(r"^:(.+)$").unwrap();
-    }
-}
-
-trait ParseWithDescription {
-    fn parse_descr<F: FromStr>(&self, descr: &str) -> Result<F>
-    where
-        <F as FromStr>::Err: std::error::Error + Send + 'static;
-}
-
-impl ParseWithDescription for str {
-    fn parse_descr<F: FromStr>(&self, descr: &str) -> Result<F>
-    where
-        <F as FromStr>::Err: std::error::Error + Send + 'static,
-    {
-        self.parse().chain_err(|| ErrorKind::syntax(descr, self))
-    }
-}
-
-fn parse_hw(spec: &str) -> Result<usize> {
-    if let Some(i) = re::HW_ONLY
-        .captures(spec)
-        .and_then(|captures| captures.get(1))
-        .and_then(|s| s.as_str().parse().ok())
-    {
-        Ok(i)
-    } else {
-        Err(ErrorKind::syntax("homework spec", spec))?
-    }
+fn parse_hw_opt_file(spec: &str, config: &config::Config) -> Result<RemotePattern> {
+    parse::parse_hw_opt_file(spec, || current_hw(config))
 }
 
-fn parse_hw_opt_file(spec: &str) -> Result<RemotePattern> {
-    let captures = re::HW_OPT_FILE
-        .captures(spec)
-        .ok_or_else(|| ErrorKind::syntax("homework or file spec", spec))?;
-    let capture1 = captures.get(1).unwrap().as_str();
-    let capture2 = captures.get(2).map(|c| c.as_str());
-    let hw = capture1.parse().unwrap();
-    let name = capture2.unwrap_or("").to_owned();
-    Ok(RemotePattern { hw, name })
+fn parse_hw_file(spec: &str, config: &config::Config) -> Result<RemotePattern> {
+    parse::parse_hw_file(spec, || current_hw(config))
 }
 
-fn parse_hw_file(file_spec: &str) -> Result<RemotePattern> {
-    let captures = re::HW_FILE
-        .captures(file_spec)
-        .ok_or_else(|| ErrorKind::syntax("remote file or homework spec", file_spec))?;
-    let capture1 = captures.get(1).unwrap().as_str();
-    let capture2 = captures.get(2).unwrap().as_str();
-    let hw = capture1.parse().unwrap();
-    let name = capture2.to_owned();
-    Ok(RemotePattern { hw, name })
+fn parse_remote_dest(spec: &str, config: &config::Config) -> Result<RemoteDestination> {
+    parse::parse_remote_dest(spec, || current_hw(config))
 }
 
-fn parse_remote_dest(spec: &str) -> Result<RemoteDestination> {
-    if spec.is_empty() {
-        Err(ErrorKind::syntax("remote file or assignment name", spec))?;
-    }
-
-    let result = if let Ok(hw) = parse_hw(spec) {
-        RemoteDestination::just_hw(hw)
-    } else if spec.find(':').is_some() {
-        parse_hw_file(spec)?.into()
-    } else {
-        RemoteDestination::just_name(spec)
-    };
-
-    Ok(result)
+fn parse_cp_arg(spec: &str, config: &config::Config) -> Result<CpArg> {
+    parse::parse_cp_arg(spec, || current_hw(config))
 }
 
-fn parse_cp_arg(spec: &str) -> Result<CpArg> {
-    if spec.is_empty() {
-        Err(ErrorKind::syntax("file name", spec))?
-    } else if let Some(captures) = re::LOCAL_FILE.captures(spec) {
-        let filename = captures.get(1).unwrap().as_str().to_owned();
-        Ok(CpArg::Local(filename.into()))
-    } else if let Some(_) = spec.find(':') {
-        let rp = parse_hw_file(spec)?;
-        Ok(CpArg::Remote(rp))
-    } else {
-        Ok(CpArg::Local(spec.into()))
-    }
+fn expand_current<'a>(spec: &'a str, config: &config::Config) -> Result<Cow<'a, str>> {
+    parse::expand_current(spec, || current_hw(config))
 }
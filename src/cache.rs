@@ -0,0 +1,236 @@
+use crate::messages::UtcDateTime;
+use crate::prelude::*;
+use crate::util::HumanDuration;
+
+use reqwest::{blocking, header, StatusCode};
+use serde_derive::{Deserialize, Serialize};
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The largest response body worth caching. The cache is for API metadata
+/// like file lists and submissions; anything bigger, or of unknown size,
+/// is passed through untouched.
+const MAX_CACHED_BYTES: u64 = 1024 * 1024;
+
+/// An on-disk cache of JSON responses, keyed by the user they were sent
+/// for and URI, and revalidated with the server using `ETag` and
+/// `Last-Modified`, or served as is offline.
+#[derive(Debug)]
+pub(crate) struct HttpCache {
+    dir: PathBuf,
+    offline: bool,
+    told_age: AtomicBool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct CacheEntry {
+    /// Whose credentials the request was sent with, if any.
+    pub user: Option<String>,
+    pub uri: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub fetched: UtcDateTime,
+    pub body: String,
+}
+
+impl HttpCache {
+    pub fn new(dir: impl Into<PathBuf>, offline: bool) -> Self {
+        HttpCache {
+            dir: dir.into(),
+            offline,
+            told_age: AtomicBool::new(false),
+        }
+    }
+
+    /// Sends GET `request` for `user` using `send`, revalidating any cached
+    /// response. When offline, answers from the cache without sending
+    /// anything. File contents are never cached.
+    pub fn send<F>(
+        &self,
+        user: Option<&str>,
+        mut request: blocking::Request,
+        send: F,
+    ) -> Result<blocking::Response>
+    where
+        F: FnOnce(blocking::Request) -> Result<blocking::Response>,
+    {
+        if is_file_contents(request.url()) {
+            if self.offline {
+                Err(ErrorKind::NotCached(request.url().to_string()))?;
+            }
+            return send(request);
+        }
+
+        let uri = request.url().to_string();
+        let cached = self.lookup(user, &uri);
+
+        if self.offline {
+            let entry = cached.ok_or_else(|| ErrorKind::NotCached(uri))?;
+
+            if !self.told_age.swap(true, Ordering::Relaxed) {
+                ve1!(
+                    "Offline: showing data cached {} ago.",
+                    HumanDuration(entry.fetched.time_until())
+                );
+            }
+
+            return Ok(entry.into_response());
+        }
+
+        if let Some(entry) = &cached {
+            entry.add_validators(request.headers_mut());
+        }
+
+        let response = send(request)?;
+        self.update(user, &uri, response, cached)
+    }
+
+    /// The response cached for GET `uri` sent for `user`, if any.
+    pub fn lookup(&self, user: Option<&str>, uri: &str) -> Option<CacheEntry> {
+        let contents = fs::read_to_string(self.path_for(user, uri)).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&contents).ok()?;
+
+        if entry.user.as_deref() == user && entry.uri == uri {
+            Some(entry)
+        } else {
+            None
+        }
+    }
+
+    pub fn store(&self, entry: &CacheEntry) {
+        if let Err(e) = self.try_store(entry) {
+            ve3!("Could not cache response for {}: {}", entry.uri, e);
+        }
+    }
+
+    fn try_store(&self, entry: &CacheEntry) -> Result<()> {
+        create_private_dir(&self.dir)?;
+        let path = self.path_for(entry.user.as_deref(), &entry.uri);
+        fs::write(path, serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e)?,
+            _ => Ok(()),
+        }
+    }
+
+    /// Offers `response` to the cache. A 304 response is replaced by the
+    /// body cached in `previous`; a fresh, small JSON response is saved
+    /// for revalidation next time, or for use offline.
+    fn update(
+        &self,
+        user: Option<&str>,
+        uri: &str,
+        response: blocking::Response,
+        previous: Option<CacheEntry>,
+    ) -> Result<blocking::Response> {
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(entry) = previous {
+                ve3!(
+                    "< Not modified; using cached response from {}",
+                    entry.fetched
+                );
+                return Ok(entry.into_response());
+            }
+        }
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value: &header::HeaderValue| value.to_str().ok())
+                .map(str::to_owned)
+        };
+
+        let etag = header(header::ETAG);
+        let last_modified = header(header::LAST_MODIFIED);
+        let is_json = header(header::CONTENT_TYPE)
+            .map(|ct| ct.starts_with("application/json"))
+            .unwrap_or(false);
+
+        let is_small = response
+            .content_length()
+            .is_some_and(|len| len <= MAX_CACHED_BYTES);
+
+        if !response.status().is_success() || !is_json || !is_small {
+            return Ok(response);
+        }
+
+        let entry = CacheEntry {
+            user: user.map(str::to_owned),
+            uri: uri.to_owned(),
+            etag,
+            last_modified,
+            fetched: UtcDateTime::now(),
+            body: response.text()?,
+        };
+
+        self.store(&entry);
+        Ok(entry.into_response())
+    }
+
+    fn path_for(&self, user: Option<&str>, uri: &str) -> PathBuf {
+        let key = format!("{} {}", user.unwrap_or(""), uri);
+        self.dir
+            .join(format!("{:016x}.json", fnv1a(key.as_bytes())))
+    }
+}
+
+impl CacheEntry {
+    pub fn add_validators(&self, headers: &mut header::HeaderMap) {
+        let validators = [
+            (header::IF_NONE_MATCH, &self.etag),
+            (header::IF_MODIFIED_SINCE, &self.last_modified),
+        ];
+
+        for (name, value) in validators.iter() {
+            if let Some(value) = value {
+                if let Ok(value) = header::HeaderValue::from_str(value) {
+                    headers.insert(name.clone(), value);
+                }
+            }
+        }
+    }
+
+    pub fn into_response(self) -> blocking::Response {
+        http::Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(self.body.into_bytes())
+            .expect("cached response is well formed")
+            .into()
+    }
+}
+
+/// Whether `url` is for the contents of a submitted file, like
+/// `/api/submissions/…/files/main.cpp`, rather than a list or metadata.
+fn is_file_contents(url: &reqwest::Url) -> bool {
+    let segments: Vec<&str> = url.path_segments().map_or_else(Vec::new, Iterator::collect);
+    matches!(segments.as_slice(), [.., "files", name] if !name.is_empty())
+}
+
+fn create_private_dir(dir: &std::path::Path) -> Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+
+    builder.create(dir)?;
+    Ok(())
+}
+
+// FNV-1a, for cache filenames that are stable across Rust versions.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
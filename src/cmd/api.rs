@@ -0,0 +1,53 @@
+use crate::prelude::*;
+
+use reqwest::Method;
+
+use std::fs;
+use std::io::{self, Read};
+
+impl GscClient {
+    /// Sends an authenticated `method` request to server path `path`,
+    /// with `data` (JSON, or `@FILE`, or `-` for stdin) as the body, and
+    /// prints the response.
+    pub fn api(&self, method: &str, path: &str, data: Option<&str>) -> Result<()> {
+        let method = Method::from_bytes(method.to_uppercase().as_bytes())
+            .map_err(|_| ErrorKind::syntax("HTTP method", method))?;
+
+        if !path.starts_with('/') {
+            Err(ErrorKind::syntax("server path (starting with ‘/’)", path))?;
+        }
+
+        let uri = format!("{}{}", self.config().get_endpoint(), path);
+        let mut request = self.http.request(method, &uri);
+
+        if let Some(data) = data {
+            let body = read_data(data)?;
+            serde_json::from_str::<serde_json::Value>(&body)
+                .chain_err(|| "Request data is not valid JSON")?;
+            request = request
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body);
+        }
+
+        let text = self.send_request(request)?.text()?;
+        if !text.is_empty() {
+            self.print_output(text.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads `--data` as curl does: `@FILE` names a file, and `-` means
+/// stdin.
+fn read_data(data: &str) -> Result<String> {
+    if data == "-" {
+        let mut body = String::new();
+        io::stdin().read_to_string(&mut body)?;
+        Ok(body)
+    } else if let Some(path) = data.strip_prefix('@') {
+        fs::read_to_string(path).chain_err(|| format!("Could not read {}", path))
+    } else {
+        Ok(data.to_owned())
+    }
+}
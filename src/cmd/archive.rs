@@ -0,0 +1,82 @@
+use crate::archive;
+use crate::filename;
+use crate::messages::{self, FilePurpose};
+use crate::prelude::*;
+
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+impl GscClient {
+    /// Downloads the non-log files of homework `hw` into the archive
+    /// `dst`, laid out as by `cp -a`. Each file goes straight from the
+    /// server into the archive.
+    pub fn archive(&self, hw: usize, dst: &Path) -> Result<()> {
+        let format = archive::Format::from_path(dst)
+            .ok_or_else(|| ErrorKind::UnknownArchiveFormat(dst.to_owned()))?;
+        let top = format
+            .stem(dst)
+            .map(str::to_owned)
+            .unwrap_or_else(|| format!("hw{}", hw));
+
+        let metas: Vec<messages::FileMeta> = self
+            .fetch_matching_file_list(&HwQual::just_hw(hw))?
+            .into_iter()
+            .filter(|meta| meta.purpose != FilePurpose::Log)
+            .collect();
+
+        let policy = &mut self.config.get_overwrite_policy();
+        if !self.is_okay_to_write_local(policy, dst)? {
+            return Ok(());
+        }
+
+        let file = fs::File::create(dst)
+            .chain_err(|| format!("Could not create archive: {}", dst.display()))?;
+
+        let result = self.write_archive(format, file, &top, &metas);
+        if result.is_err() {
+            let _ = fs::remove_file(dst);
+        }
+        result?;
+
+        v2!("Wrote {} file(s) to ‘{}’.", metas.len(), dst.display());
+        Ok(())
+    }
+
+    fn write_archive(
+        &self,
+        format: archive::Format,
+        file: fs::File,
+        top: &str,
+        metas: &[messages::FileMeta],
+    ) -> Result<()> {
+        let mut writer = archive::Writer::new(format, BufWriter::new(file));
+
+        for meta in metas {
+            filename::check(&meta.name)?;
+            let path = match meta.purpose.to_dir() {
+                "." => format!("{}/{}", top, meta.name),
+                dir => format!("{}/{}/{}", top, dir, meta.name),
+            };
+
+            let uri = format!("{}{}", self.config.get_endpoint(), meta.uri);
+            ve2!("Downloading ‘hw{}:{}’ -> ‘{}’...", meta.hw, meta.name, path);
+            let mut response = self.stream_request(self.http.get(&uri))?;
+
+            writer
+                .append(
+                    &path,
+                    meta.byte_count as u64,
+                    meta.upload_time.to_system_time(),
+                    &mut response,
+                )
+                .chain_err(|| format!("Could not add ‘{}’ to archive", path))?;
+        }
+
+        writer
+            .finish()
+            .and_then(|mut out| out.flush())
+            .chain_err(|| "Could not finish archive")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::audit;
+use crate::history;
+use crate::prelude::*;
+use crate::render;
+
+impl GscClient {
+    /// Shows the most recent `limit` changes in the local audit log, only
+    /// those made to `user` if given, oldest first.
+    pub fn admin_audit(&self, user: Option<&str>, limit: usize) -> Result<()> {
+        let path = self
+            .config()
+            .get_audit_file()
+            .ok_or(ErrorKind::NoAuditFile)?;
+
+        let mut entries: Vec<_> = history::read_entries::<audit::Entry>(path)?
+            .into_iter()
+            .filter(|entry| user.map_or(true, |user| entry.target == user))
+            .collect();
+        let skip = entries.len().saturating_sub(limit);
+        entries.drain(..skip);
+
+        if self.config().json_output() {
+            return self.print_output(serde_json::to_string_pretty(&entries)?);
+        }
+
+        if entries.is_empty() {
+            v2!("No audit entries in {}.", path.display());
+            return Ok(());
+        }
+
+        let mut table = render::Table::new("{:<}  {:<}  {:<}  {:<}  {:<}");
+
+        for entry in &entries {
+            let target = match entry.hw {
+                Some(hw) => format!("{} hw{}", entry.target, hw),
+                None => entry.target.clone(),
+            };
+            let payload = if entry.payload.is_null() {
+                String::new()
+            } else {
+                entry.payload.to_string()
+            };
+
+            table.add_row(
+                render::Row::new()
+                    .with_cell(entry.time.format_display("%Y-%m-%d %H:%M:%S (%z)"))
+                    .with_cell(&entry.actor)
+                    .with_cell(entry.action.as_str())
+                    .with_cell(target)
+                    .with_cell(payload),
+            );
+        }
+
+        self.print_table(&table);
+        Ok(())
+    }
+}
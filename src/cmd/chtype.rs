@@ -0,0 +1,46 @@
+use crate::history;
+use crate::messages::{FileMeta, FileMetaChange, FilePurpose};
+use crate::prelude::*;
+
+impl GscClient {
+    /// Changes the type of each remote file matching `rpats` to `purpose`.
+    pub fn chtype(&self, rpats: &[RemotePattern], purpose: FilePurpose) -> Result<()> {
+        for rpat in rpats {
+            self.try_warn(|| {
+                for file in self.fetch_nonempty_matching_file_list(rpat)? {
+                    if file.purpose == purpose {
+                        v2!("Remote file ‘{}’ is already {}.", file, purpose);
+                        continue;
+                    }
+
+                    self.set_purpose(&file, purpose)?;
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Changes the type of remote file `file` to `purpose`.
+    pub(crate) fn set_purpose(&self, file: &FileMeta, purpose: FilePurpose) -> Result<()> {
+        let message = FileMetaChange {
+            purpose: Some(purpose),
+            ..FileMetaChange::default()
+        };
+
+        let uri = format!("{}{}", self.config().get_endpoint(), file.uri);
+        let request = self.http.patch(&uri).json(&message);
+        v2!("Changing type of remote file ‘{}’ to {}...", file, purpose);
+        self.send_request(request)
+            .map_err(|error| error.processing(file))?;
+
+        let entry = history::Entry::new(history::Action::Chtype, file.hw)
+            .file(file.name.as_str())
+            .detail(format!("-> {}", purpose));
+        self.record(entry);
+
+        Ok(())
+    }
+}
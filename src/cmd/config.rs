@@ -0,0 +1,156 @@
+use crate::config::{read_dotfile_at, Config, Dotfile};
+use crate::prelude::*;
+
+use serde_yaml::{Mapping, Value};
+
+use std::{env, fs, path::Path, process};
+
+impl Config {
+    pub fn config_get(&self, key: &str) -> Result<()> {
+        let path = self.require_dotfile()?;
+        check_known_setting(key)?;
+
+        match read_mapping(path)?.get(&Value::from(key)) {
+            Some(Value::String(s)) => v1!("{}", s),
+            Some(value) => v1!("{}", to_yaml(value)?),
+            None => ve2!("{} is not set in {}", key, path.display()),
+        }
+
+        Ok(())
+    }
+
+    pub fn config_set(&self, key: &str, value: &str) -> Result<()> {
+        let path = self.require_dotfile()?;
+        check_known_setting(key)?;
+
+        let mut mapping = read_mapping(path)?;
+
+        // Let YAML decide the type, but fall back to a string for values
+        // like ‘+05:30’ that merely look like something else:
+        let parsed = serde_yaml::from_str(value).unwrap_or(Value::Null);
+        mapping.insert(Value::from(key), parsed);
+        if validate(&mapping).is_err() {
+            mapping.insert(Value::from(key), Value::from(value));
+            validate(&mapping).chain_err(|| format!("Invalid value for {}: ‘{}’", key, value))?;
+        }
+
+        write_mapping(path, &mapping)?;
+        v2!("Set {} in {}", key, path.display());
+        Ok(())
+    }
+
+    pub fn config_unset(&self, key: &str) -> Result<()> {
+        let path = self.require_dotfile()?;
+        check_known_setting(key)?;
+
+        let mut mapping = read_mapping(path)?;
+        if mapping.remove(&Value::from(key)).is_some() {
+            write_mapping(path, &mapping)?;
+            v2!("Unset {} in {}", key, path.display());
+        }
+
+        Ok(())
+    }
+
+    pub fn config_list(&self) -> Result<()> {
+        let path = self.require_dotfile()?;
+        let mapping = read_mapping(path)?;
+
+        if self.json_output() {
+            v1!("{}", serde_json::to_string_pretty(&mapping)?);
+        } else if !mapping.is_empty() {
+            v1!("{}", to_yaml(&Value::Mapping(mapping))?);
+        }
+
+        Ok(())
+    }
+
+    /// Opens the dotfile in `$VISUAL` or `$EDITOR`, and then checks that
+    /// it still parses.
+    pub fn config_edit(&self) -> Result<()> {
+        let path = self.require_dotfile()?;
+
+        let editor = env::var("VISUAL")
+            .or_else(|_| env::var("EDITOR"))
+            .unwrap_or_else(|_| DEFAULT_EDITOR.to_owned());
+        let mut words = editor.split_whitespace();
+        let program = words.next().unwrap_or(DEFAULT_EDITOR);
+
+        let status = process::Command::new(program)
+            .args(words)
+            .arg(path)
+            .status()
+            .chain_err(|| format!("Could not run editor: {}", editor))?;
+
+        if !status.success() {
+            ve1!("Editor exited with {}", status);
+        }
+
+        read_dotfile_at(path)?;
+        Ok(())
+    }
+
+    fn require_dotfile(&self) -> Result<&Path> {
+        self.get_dotfile().ok_or_else(|| ErrorKind::NoDotfile)
+    }
+}
+
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Unknown keys land in `Dotfile::unknown`, whereas known keys either
+/// accept the null probe or reject it as the wrong type.
+fn check_known_setting(key: &str) -> Result<()> {
+    let mut probe = Mapping::new();
+    probe.insert(Value::from(key), Value::Null);
+
+    match serde_yaml::from_value::<Dotfile>(Value::Mapping(probe)) {
+        Ok(dotfile) if !dotfile.unknown.is_empty() => {
+            Err(ErrorKind::UnknownSetting(key.to_owned()))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn read_mapping(path: &Path) -> Result<Mapping> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Mapping::new()),
+        Err(error) => {
+            let message = format!("Could not read dotfile: {}", path.display());
+            return Err(Error::with_chain(error, message));
+        }
+    };
+
+    match serde_yaml::from_str(&contents)
+        .chain_err(|| format!("Could not parse dotfile: {}", path.display()))?
+    {
+        Value::Null => Ok(Mapping::new()),
+        Value::Mapping(mapping) => Ok(mapping),
+        _ => Err(format!("Dotfile is not a mapping: {}", path.display()).into()),
+    }
+}
+
+fn validate(mapping: &Mapping) -> Result<Dotfile> {
+    Ok(serde_yaml::from_value(Value::Mapping(mapping.clone()))?)
+}
+
+/// Writes the dotfile by way of a temporary file, so that a failure
+/// can’t leave it half-written.
+fn write_mapping(path: &Path, mapping: &Mapping) -> Result<()> {
+    let contents = to_yaml(&Value::Mapping(mapping.clone()))? + "\n";
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+
+    fs::write(&tmp, contents)
+        .and_then(|()| fs::rename(&tmp, path))
+        .chain_err(|| format!("Could not write dotfile: {}", path.display()))
+}
+
+fn to_yaml(value: &Value) -> Result<String> {
+    let yaml = serde_yaml::to_string(value)?;
+    Ok(yaml.trim_start_matches("---\n").trim_end().to_owned())
+}
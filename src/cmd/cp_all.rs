@@ -0,0 +1,159 @@
+use crate::cmd::extract::IgnoreSet;
+use crate::messages::FilePurpose;
+use crate::prelude::*;
+use crate::util::Summary;
+
+use std::collections::HashMap;
+use std::fs;
+use std::iter;
+use std::path::{Path, PathBuf};
+
+impl GscClient {
+    /// Uploads each of the local directories `srcs` to homework `hw`,
+    /// undoing `cp -a`: files in the `src`, `test`, and `Resources`
+    /// subdirectories get the type that their subdirectory stands for,
+    /// and files at the top level get whatever type the server guesses.
+    pub fn cp_up_all(&self, srcs: &[PathBuf], hw: usize) -> Result<()> {
+        let uploads = self.local_files_all(srcs)?;
+
+        for (path, _, _) in &uploads {
+            self.check_upload(path)?;
+        }
+
+        self.warn_if_late(hw)?;
+        self.run_pre_push_hook(hw)?;
+
+        let mut summary = Summary::transfer("Copied");
+        let mut purposes = HashMap::new();
+
+        for (path, name, purpose) in uploads {
+            let bytes = self
+                .upload_file(&path, &RemotePattern::hw_name(hw, name.as_str()))
+                .map_err(|error| error.processing(path.display()))?;
+            summary.add(bytes);
+
+            if let Some(purpose) = purpose {
+                purposes.insert(name, purpose);
+            }
+        }
+
+        self.correct_purposes(hw, &purposes)?;
+        self.finish_summary(&summary);
+        Ok(())
+    }
+
+    /// Gives each just-uploaded file of homework `hw` named in `purposes`
+    /// the type that its subdirectory stood for.
+    pub(crate) fn correct_purposes(
+        &self,
+        hw: usize,
+        purposes: &HashMap<String, FilePurpose>,
+    ) -> Result<()> {
+        // The server guesses each file’s type from its name, so we only
+        // need to correct the guesses that don’t match the directory:
+        if !purposes.is_empty() {
+            for file in self.fetch_matching_file_list(&RemotePattern::just_hw(hw))? {
+                match purposes.get(&file.name) {
+                    Some(&purpose) if purpose != file.purpose => {
+                        self.set_purpose(&file, purpose)?
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The files in the local directories `srcs` as `cp -a` lays them
+    /// out, each with its remote name and the type its subdirectory
+    /// implies, if any. The server keeps files flat, so files in
+    /// different subdirectories can’t have the same name.
+    pub(crate) fn local_files_all(
+        &self,
+        srcs: &[PathBuf],
+    ) -> Result<Vec<(PathBuf, String, Option<FilePurpose>)>> {
+        let ignore = IgnoreSet::new(self.config.get_ignore_patterns())?;
+        let template = self.config.get_upload_name_template();
+
+        let mut files = Vec::new();
+        let mut names = HashMap::new();
+
+        for src in srcs {
+            for (path, purpose) in files_by_purpose(src, &ignore)? {
+                let name = self.get_base_filename(&path)?;
+                let name = match template {
+                    Some(template) => template.apply(name),
+                    None => name.to_owned(),
+                };
+
+                if let Some(other) = names.insert(name.clone(), path.clone()) {
+                    Err(ErrorKind::DuplicateFileName(
+                        name.clone(),
+                        other.display().to_string(),
+                        path.display().to_string(),
+                    ))?;
+                }
+                files.push((path, name, purpose));
+            }
+        }
+
+        Ok(files)
+    }
+}
+
+/// The subdirectories that `cp -a` makes, by the type each stands for.
+const PURPOSE_DIRS: [FilePurpose; 3] = [
+    FilePurpose::Source,
+    FilePurpose::Test,
+    FilePurpose::Resource,
+];
+
+/// Where the file at `relative`, a path inside a directory that `cp -a`
+/// laid out, belongs: its name, and the type its subdirectory implies,
+/// if any. Files in any other subdirectory don’t belong anywhere.
+pub(crate) fn place_by_purpose(relative: &str) -> Option<(&str, Option<FilePurpose>)> {
+    match relative.split_once('/') {
+        None => Some((relative, None)),
+        Some((dir, name)) if !name.contains('/') => PURPOSE_DIRS
+            .iter()
+            .find(|purpose| purpose.to_dir() == dir)
+            .map(|&purpose| (name, Some(purpose))),
+        Some(_) => None,
+    }
+}
+
+/// The files in `dir` as `cp -a` lays them out, each with the type that
+/// its subdirectory implies, if any.
+fn files_by_purpose(dir: &Path, ignore: &IgnoreSet) -> Result<Vec<(PathBuf, Option<FilePurpose>)>> {
+    let subdirs = PURPOSE_DIRS
+        .iter()
+        .map(|&purpose| (dir.join(purpose.to_dir()), Some(purpose)))
+        .filter(|(path, _)| path.is_dir());
+
+    let mut files = Vec::new();
+
+    for (path, purpose) in iter::once((dir.to_owned(), None)).chain(subdirs) {
+        let mut entries = fs::read_dir(&path)
+            .chain_err(|| format!("Could not read directory: {}", path.display()))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort();
+
+        for entry in entries {
+            let relative = entry.strip_prefix(dir).unwrap_or(&entry);
+            let relative = relative.to_string_lossy().replace('\\', "/");
+
+            if !entry.is_file() {
+                continue;
+            } else if ignore.is_match(&relative) {
+                ve2!("Skipping ‘{}’", entry.display());
+                continue;
+            }
+
+            files.push((entry, purpose));
+        }
+    }
+
+    Ok(files)
+}
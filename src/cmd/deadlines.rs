@@ -0,0 +1,67 @@
+use crate::messages::{self, SubmissionStatus, UtcDateTime};
+use crate::prelude::*;
+use crate::render;
+use crate::util::HumanDuration;
+
+impl GscClient {
+    pub fn deadlines(&self) -> Result<()> {
+        let (who, creds) = self.load_effective_credentials()?;
+        let endpoint = self.config().get_endpoint();
+
+        let uris: Vec<String> = self
+            .fetch_submissions(&who, &creds)?
+            .into_iter()
+            .filter(|s| s.status != SubmissionStatus::Closed)
+            .map(|s| format!("{}{}", endpoint, s.uri))
+            .collect();
+
+        let submissions: Vec<messages::Submission> =
+            self.fetch_in_parallel(&uris, |fetcher, uri| fetcher.get(uri)?.message())?;
+
+        let mut deadlines: Vec<(UtcDateTime, usize, &str)> = Vec::new();
+
+        for submission in &submissions {
+            let hw = submission.assignment_number;
+            deadlines.push((submission.due_date.clone(), hw, "submission"));
+            deadlines.push((submission.eval_date.clone(), hw, "self-eval"));
+        }
+
+        deadlines.retain(|(date, _, _)| date.time_until() > chrono::Duration::zero());
+        deadlines.sort();
+
+        if self.config().json_output() {
+            let json: Vec<_> = deadlines
+                .iter()
+                .map(|(date, hw, kind)| {
+                    serde_json::json!({
+                        "assignment_number": hw,
+                        "kind": kind,
+                        "date": date,
+                    })
+                })
+                .collect();
+            return self.print_output(serde_json::to_string_pretty(&json)?);
+        }
+
+        if deadlines.is_empty() {
+            ve1!("No upcoming deadlines.");
+            return Ok(());
+        }
+
+        let mut table = render::Table::new("  hw{:<}  {:<}  {:<}  due in {:<}");
+
+        for (date, hw, kind) in &deadlines {
+            table.add_row(
+                render::Row::new()
+                    .with_cell(hw)
+                    .with_cell(kind)
+                    .with_cell(date)
+                    .with_cell(HumanDuration(date.time_until())),
+            );
+        }
+
+        self.print_table(&table);
+
+        Ok(())
+    }
+}
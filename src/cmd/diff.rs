@@ -0,0 +1,172 @@
+use crate::diff;
+use crate::messages::{self, FilePurpose};
+use crate::prelude::*;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+impl GscClient {
+    /// Shows how the local copies of the remote files matching `rpat`
+    /// differ from the server’s. `local` may be a file, if `rpat` is a
+    /// single file, or a directory laid out as by `cp -a`.
+    pub fn diff(&self, rpat: &RemotePattern, local: &Path) -> Result<()> {
+        let metas: Vec<messages::FileMeta> = self
+            .fetch_nonempty_matching_file_list(rpat)?
+            .into_iter()
+            .filter(|meta| meta.purpose != FilePurpose::Log)
+            .collect();
+
+        let local_is_dir = local.is_dir();
+        if !local_is_dir && metas.len() != 1 {
+            Err(ErrorKind::DiffNeedsDirectory(rpat.to_string()))?;
+        }
+
+        let endpoint = self.config().get_endpoint();
+        let uris: Vec<String> = metas
+            .iter()
+            .map(|meta| format!("{}{}", endpoint, meta.uri))
+            .collect();
+        let contents = self.fetch_in_parallel(&uris, |fetcher, uri| {
+            Ok(fetcher.get(uri)?.bytes()?.to_vec())
+        })?;
+
+        let color = self.config().use_color_on(&std::io::stdout());
+
+        for (meta, remote) in metas.iter().zip(contents) {
+            self.try_warn(|| {
+                let path = if local_is_dir {
+                    local_path_for(local, meta)
+                } else {
+                    local.to_owned()
+                };
+
+                let local_contents = match fs::read(&path) {
+                    Ok(contents) => contents,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                        v1!("Only on server: {}", meta);
+                        return Ok(());
+                    }
+                    Err(e) => Err(e).chain_err(|| format!("Could not read {}", path.display()))?,
+                };
+
+                let label = path.display().to_string();
+                show_diff(&meta.to_string(), &label, &remote, &local_contents, color);
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl GscClient {
+    /// Compares the files matching `pattern` in two users’ submissions for
+    /// homework `hw`.
+    pub fn admin_diff(&self, hw: usize, user1: &str, user2: &str, pattern: &str) -> Result<()> {
+        let creds = self.load_credentials()?;
+        let uri1 = self.get_uri_for_submission(user1, hw, &creds)?;
+        let uri2 = self.get_uri_for_submission(user2, hw, &creds)?;
+
+        if uri1 == uri2 {
+            v1!("{} and {} are partners on hw{}.", user1, user2, hw);
+            return Ok(());
+        }
+
+        let files1 = self.fetch_user_files(&uri1, pattern)?;
+        let files2 = self.fetch_user_files(&uri2, pattern)?;
+
+        if files1.is_empty() && files2.is_empty() {
+            Err(ErrorKind::NoSuchRemoteFile(RemotePattern::hw_name(
+                hw, pattern,
+            )))?;
+        }
+
+        let color = self.config().use_color_on(&std::io::stdout());
+        let mut names: Vec<&String> = files1.keys().chain(files2.keys()).collect();
+        names.sort();
+        names.dedup();
+
+        for name in names {
+            let label = |user: &str| format!("{}/hw{}:{}", user, hw, name);
+
+            match (files1.get(name), files2.get(name)) {
+                (Some(contents1), Some(contents2)) => {
+                    show_diff(&label(user1), &label(user2), contents1, contents2, color)
+                }
+                (Some(_), None) => v1!("Only in {}: hw{}:{}", user1, hw, name),
+                (None, Some(_)) => v1!("Only in {}: hw{}:{}", user2, hw, name),
+                (None, None) => unreachable!(),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The contents of the non-log files matching `pattern` in the
+    /// submission at `submission_uri`, by name.
+    fn fetch_user_files(
+        &self,
+        submission_uri: &str,
+        pattern: &str,
+    ) -> Result<BTreeMap<String, Vec<u8>>> {
+        let matcher = crate::glob(pattern)?;
+        let request = self.http.get(&format!("{}/files", submission_uri));
+        let metas: Vec<messages::FileMeta> = self.send_request(request)?.message()?;
+        let metas: Vec<_> = metas
+            .into_iter()
+            .filter(|meta| meta.purpose != FilePurpose::Log && matcher.is_match(&meta.name))
+            .collect();
+
+        let endpoint = self.config().get_endpoint();
+        let uris: Vec<String> = metas
+            .iter()
+            .map(|meta| format!("{}{}", endpoint, meta.uri))
+            .collect();
+        let contents = self.fetch_in_parallel(&uris, |fetcher, uri| {
+            Ok(fetcher.get(uri)?.bytes()?.to_vec())
+        })?;
+
+        Ok(metas
+            .into_iter()
+            .map(|meta| meta.name)
+            .zip(contents)
+            .collect())
+    }
+}
+
+/// Where `cp -a` would have put `meta` under `dir`, or else directly in
+/// `dir`.
+fn local_path_for(dir: &Path, meta: &messages::FileMeta) -> PathBuf {
+    let flat = dir.join(&meta.name);
+
+    match meta.purpose.to_dir() {
+        "." => flat,
+        subdir => {
+            let nested = dir.join(subdir).join(&meta.name);
+            if nested.exists() {
+                nested
+            } else {
+                flat
+            }
+        }
+    }
+}
+
+fn show_diff(old_label: &str, new_label: &str, old: &[u8], new: &[u8], color: bool) {
+    let texts = (std::str::from_utf8(old), std::str::from_utf8(new));
+    let (old_text, new_text) = match texts {
+        (Ok(o), Ok(n)) if !o.contains('\0') && !n.contains('\0') => (o, n),
+        _ => {
+            if old != new {
+                v1!("Binary files {} and {} differ", old_label, new_label);
+            }
+            return;
+        }
+    };
+
+    match diff::unified(old_label, new_label, old_text, new_text, color) {
+        Some(text) => print!("{}", text),
+        None => v2!("{} and {} are identical", old_label, new_label),
+    }
+}
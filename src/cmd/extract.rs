@@ -0,0 +1,195 @@
+use crate::archive;
+use crate::cmd::cp_all::place_by_purpose;
+use crate::messages::FilePurpose;
+use crate::prelude::*;
+use crate::throttle::Throttled;
+use crate::util::Summary;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use reqwest::blocking;
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+impl GscClient {
+    /// Unpacks each of the local archives `srcs` and uploads the files
+    /// inside to homework `hw`, skipping files that match the ignore
+    /// patterns. Each archive is taken to be laid out as by `cp -a`,
+    /// possibly inside one top-level directory, so files in its `src`,
+    /// `test`, and `Resources` subdirectories get the types those stand
+    /// for, as with `cp -a`.
+    pub fn cp_extract(&self, srcs: &[PathBuf], hw: usize) -> Result<()> {
+        let formats = srcs
+            .iter()
+            .map(|src| {
+                archive::Format::from_path(src)
+                    .ok_or_else(|| ErrorKind::UnknownArchiveFormat(src.clone()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Everything is checked before anything is uploaded:
+        let mut plans = Vec::new();
+        let mut names = HashMap::new();
+        for (src, &format) in srcs.iter().zip(&formats) {
+            plans.push(self.plan_extract(src, format, &mut names)?);
+        }
+
+        self.warn_if_late(hw)?;
+        self.run_pre_push_hook(hw)?;
+
+        let rate = self.config.get_limit_rate();
+        let mut summary = Summary::transfer("Copied");
+        let mut purposes = HashMap::new();
+
+        for ((src, format), mut plan) in srcs.iter().zip(formats).zip(plans) {
+            let file = open_archive(src)?;
+
+            for entry in archive::entries(format, file) {
+                let entry =
+                    entry.chain_err(|| format!("Could not extract archive: {}", src.display()))?;
+                let (name, purpose) = match plan.remove(&entry.path) {
+                    Some(upload) => upload,
+                    None => continue,
+                };
+
+                let member = member(src, &entry.path);
+                let dst = RemotePattern::hw_name(hw, name.as_str());
+                let body = blocking::Body::sized(Throttled::new(entry.contents, rate), entry.size);
+                v2!("Uploading ‘{}’ -> ‘{}’...", member, dst);
+                let bytes = self
+                    .upload_body(&member, body, entry.size, &dst)
+                    .map_err(|error| error.processing(&member))?;
+                summary.add(bytes);
+
+                if let Some(purpose) = purpose {
+                    purposes.insert(name, purpose);
+                }
+            }
+        }
+
+        self.correct_purposes(hw, &purposes)?;
+        self.finish_summary(&summary);
+        Ok(())
+    }
+
+    /// Decides where each file in the archive `src` goes, checking that it
+    /// looks right to upload and that no two files, here or in the
+    /// archives in `names`, go to the same name. Returns each file’s
+    /// remote name and type, by its path in the archive.
+    fn plan_extract(
+        &self,
+        src: &Path,
+        format: archive::Format,
+        names: &mut HashMap<String, String>,
+    ) -> Result<HashMap<String, (String, Option<FilePurpose>)>> {
+        let ignore = IgnoreSet::new(self.config.get_ignore_patterns())?;
+        let template = self.config.get_upload_name_template();
+
+        let mut files = Vec::new();
+        let input = BufReader::new(open_archive(src)?);
+        archive::for_each_file(format, input, |path, size, contents| {
+            let mut start = Vec::with_capacity(4);
+            contents.take(4).read_to_end(&mut start)?;
+            files.push((path.to_owned(), size, start));
+            Ok::<_, io::Error>(())
+        })
+        .chain_err(|| format!("Could not extract archive: {}", src.display()))?;
+
+        // The length of the top-level directory, which is dropped:
+        let top = top_dir(files.iter().map(|(path, _, _)| path.as_str())).map(str::len);
+        let mut plan = HashMap::new();
+
+        for (path, size, start) in files {
+            let relative = path.trim_start_matches("./");
+            let relative = match top {
+                Some(len) => &relative[len + 1..],
+                None => relative,
+            };
+            let member = member(src, &path);
+
+            let (name, purpose) = match place_by_purpose(relative) {
+                Some(place) if !ignore.is_match(relative) => place,
+                _ => {
+                    ve2!("Skipping ‘{}’", member);
+                    continue;
+                }
+            };
+            let name = match template {
+                Some(template) => template.apply(name),
+                None => name.to_owned(),
+            };
+
+            if let Some(other) = names.insert(name.clone(), member.clone()) {
+                Err(ErrorKind::DuplicateFileName(
+                    name.clone(),
+                    other,
+                    member.clone(),
+                ))?;
+            }
+
+            self.check_upload_contents(&member, size, &start)?;
+            plan.insert(path, (name, purpose));
+        }
+
+        Ok(plan)
+    }
+}
+
+fn open_archive(src: &Path) -> Result<fs::File> {
+    fs::File::open(src).chain_err(|| format!("Could not open archive: {}", src.display()))
+}
+
+/// How to name file `path` in archive `src` in messages.
+fn member(src: &Path, path: &str) -> String {
+    format!("{}/{}", src.display(), path)
+}
+
+/// The one directory that all of `paths` are inside, if there is one.
+fn top_dir<'a>(paths: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let mut top = None;
+
+    for path in paths {
+        let dir = path.trim_start_matches("./").split_once('/')?.0;
+        match top {
+            Some(top) if top != dir => return None,
+            _ => top = Some(dir),
+        }
+    }
+
+    top
+}
+
+/// Ignore patterns work as in `.gitignore`: a pattern containing a slash
+/// matches the whole path, and any other pattern matches any one
+/// component of it, so that ignoring a directory ignores its contents.
+pub(crate) struct IgnoreSet {
+    paths: GlobSet,
+    components: GlobSet,
+}
+
+impl IgnoreSet {
+    pub(crate) fn new<'a>(patterns: impl Iterator<Item = &'a str>) -> Result<Self> {
+        let mut paths = GlobSetBuilder::new();
+        let mut components = GlobSetBuilder::new();
+
+        for pattern in patterns {
+            if pattern.contains('/') {
+                paths.add(Glob::new(pattern.trim_start_matches('/'))?);
+            } else {
+                components.add(Glob::new(pattern)?);
+            }
+        }
+
+        Ok(IgnoreSet {
+            paths: paths.build()?,
+            components: components.build()?,
+        })
+    }
+
+    pub(crate) fn is_match(&self, path: &str) -> bool {
+        let path = path.trim_start_matches("./");
+        self.paths.is_match(path) || path.split('/').any(|c| self.components.is_match(c))
+    }
+}
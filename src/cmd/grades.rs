@@ -0,0 +1,198 @@
+use crate::messages;
+use crate::porcelain::Record;
+use crate::prelude::*;
+use crate::render;
+use crate::suggest;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::json;
+
+lazy_static! {
+    /// A column about one homework, like `hw3` or `Homework 03 self eval`.
+    static ref HW_COLUMN: Regex = Regex::new(r"(?i)(?:homework|hw)[\s_-]*0*(\d+)").unwrap();
+}
+
+/// Headers that name the column of usernames; otherwise it’s the first.
+const USER_COLUMNS: &[&str] = &["username", "user", "login", "name"];
+
+impl GscClient {
+    /// Shows the grade for homework `hw` item by item: each eval item’s
+    /// weight, self and grader scores, and what it contributes, followed
+    /// by the overall grade.
+    pub fn grade(&self, hw: usize) -> Result<()> {
+        let submission = self.fetch_submission(hw)?;
+
+        let uri = format!("{}{}", self.config().get_endpoint(), submission.evals_uri);
+        let request = self.http.get(&uri);
+        let shorts: Vec<messages::EvalShort> = self.send_request(request)?.message()?;
+        let uris: Vec<String> = shorts
+            .iter()
+            .map(|eval| format!("{}{}", self.config().get_endpoint(), eval.uri))
+            .collect();
+        let mut evals: Vec<messages::Eval> =
+            self.fetch_in_parallel(&uris, |fetcher, uri| fetcher.get(uri)?.message())?;
+        evals.sort_by_key(|eval| eval.sequence);
+
+        if self.config().json_output() {
+            let items: Vec<_> = evals
+                .iter()
+                .map(|eval| {
+                    json!({
+                        "sequence": eval.sequence,
+                        "weight": eval.value,
+                        "self_score": eval.self_eval.as_ref().map(|e| e.score),
+                        "grader_score": eval.grader_eval.as_ref().map(|e| e.score),
+                        "contribution": eval.contribution(),
+                    })
+                })
+                .collect();
+            let json = json!({
+                "assignment_number": hw,
+                "status": submission.status,
+                "grade": submission.grade,
+                "items": items,
+            });
+            return self.print_output(serde_json::to_string_pretty(&json)?);
+        }
+
+        v1!(
+            "{}",
+            render::grade(hw, &submission, &evals, self.config().get_width())
+        );
+
+        Ok(())
+    }
+
+    /// Prints the grade spreadsheet as CSV, or as TSV with `tsv`, or as
+    /// JSON with `--json`. It has only the rows for users matching the
+    /// pattern `user`, and only the `columns` named, if any, in that order.
+    /// Given `hws`, it leaves out the columns for any other homework.
+    pub fn admin_csv(
+        &self,
+        hws: &[usize],
+        user: Option<&str>,
+        columns: &[String],
+        tsv: bool,
+    ) -> Result<()> {
+        let uri = format!("{}/api/grades.csv", self.config().get_endpoint());
+        let request = self.http.get(&uri);
+        let response = self.send_request(request)?;
+
+        let mut reader = csv::Reader::from_reader(response);
+        let error = || "Could not read the grade spreadsheet";
+        let headers = reader.headers().chain_err(error)?.clone();
+        let keep = select_columns(&headers, hws, columns)?;
+
+        let matcher = user.map(crate::glob).transpose()?;
+        let user_column = headers
+            .iter()
+            .position(|header| USER_COLUMNS.contains(&header.to_lowercase().as_str()))
+            .unwrap_or(0);
+
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        for record in reader.records() {
+            let record = record.chain_err(error)?;
+            let username = record.get(user_column).unwrap_or("");
+            if matcher.as_ref().map_or(true, |m| m.is_match(username)) {
+                rows.push(
+                    keep.iter()
+                        .map(|&i| record.get(i).unwrap_or("").to_owned())
+                        .collect(),
+                );
+            }
+        }
+
+        let headers: Vec<&str> = keep.iter().map(|&i| &headers[i]).collect();
+
+        if self.config().json_output() {
+            let rows: Vec<_> = rows.iter().map(|row| JsonRow(&headers, row)).collect();
+            self.print_output(serde_json::to_string_pretty(&rows)?)
+        } else if tsv {
+            let line = |cells: &[&str]| {
+                cells
+                    .iter()
+                    .fold(Record::new(), |record, cell| record.field(cell))
+            };
+
+            self.print_output(line(&headers))?;
+            for row in &rows {
+                let cells: Vec<&str> = row.iter().map(String::as_str).collect();
+                self.print_output(line(&cells))?;
+            }
+            Ok(())
+        } else {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            writer.write_record(&headers).chain_err(error)?;
+            for row in &rows {
+                writer.write_record(row).chain_err(error)?;
+            }
+
+            let text = writer.into_inner().map_err(|e| e.into_error())?;
+            self.with_output(|out| out.write_all(&text))
+        }
+    }
+}
+
+/// The indices of the columns to print: those named in `columns` (which
+/// may be wildcard patterns, and ignore case) or else all of them, less
+/// those about homework not in `hws`, if it’s not empty.
+fn select_columns(
+    headers: &csv::StringRecord,
+    hws: &[usize],
+    columns: &[String],
+) -> Result<Vec<usize>> {
+    let mut keep = Vec::new();
+
+    if columns.is_empty() {
+        keep.extend(0..headers.len());
+    }
+
+    for column in columns {
+        let matcher = globset::GlobBuilder::new(column)
+            .case_insensitive(true)
+            .build()?
+            .compile_matcher();
+        let before = keep.len();
+        keep.extend(
+            headers
+                .iter()
+                .enumerate()
+                .filter(|&(i, header)| matcher.is_match(header) && !keep.contains(&i))
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>(),
+        );
+
+        if keep.len() == before {
+            Err(match suggest::closest(column, headers.iter()) {
+                Some(header) => {
+                    ErrorKind::SyntaxErrorSuggest("column".into(), column.clone(), header.into())
+                }
+                None => ErrorKind::syntax("column", column.as_str()),
+            })?;
+        }
+    }
+
+    if !hws.is_empty() {
+        keep.retain(|&i| match HW_COLUMN.captures(&headers[i]) {
+            Some(captures) => captures[1].parse().is_ok_and(|hw| hws.contains(&hw)),
+            None => true,
+        });
+    }
+
+    Ok(keep)
+}
+
+/// A row as a JSON object, keeping the columns in order.
+struct JsonRow<'a>(&'a [&'a str], &'a [String]);
+
+impl Serialize for JsonRow<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (header, cell) in self.0.iter().zip(self.1) {
+            map.serialize_entry(header, cell)?;
+        }
+        map.end()
+    }
+}
@@ -0,0 +1,56 @@
+use crate::history;
+use crate::prelude::*;
+use crate::render;
+
+impl GscClient {
+    /// Shows the local history of changes to submissions, for homework
+    /// `hw` or for all of them.
+    pub fn history(&self, hw: Option<usize>) -> Result<()> {
+        let path = self
+            .config()
+            .get_history_file()
+            .ok_or(ErrorKind::NoHistoryFile)?;
+
+        let entries: Vec<_> = history::read_entries::<history::Entry>(path)?
+            .into_iter()
+            .filter(|entry| hw.map_or(true, |hw| entry.hw == hw))
+            .collect();
+
+        if self.config().json_output() {
+            return self.print_output(serde_json::to_string_pretty(&entries)?);
+        }
+
+        if entries.is_empty() {
+            v2!("No history in {}.", path.display());
+            return Ok(());
+        }
+
+        let mut table = render::Table::new("{:<}  {:<}  {:<}  {:<}  {:>}  {:<}");
+
+        for entry in &entries {
+            let target = match &entry.file {
+                Some(file) => format!("hw{}:{}", entry.hw, file),
+                None => format!("hw{}", entry.hw),
+            };
+            // The server’s own timestamp is the better evidence:
+            let time = entry.server_time.as_ref().unwrap_or(&entry.time);
+            let bytes = entry
+                .bytes
+                .map(|n| n.separate_with_commas())
+                .unwrap_or_default();
+
+            table.add_row(
+                render::Row::new()
+                    .with_cell(time.format_display("%Y-%m-%d %H:%M:%S (%z)"))
+                    .with_cell(&entry.user)
+                    .with_cell(entry.action.as_str())
+                    .with_cell(target)
+                    .with_cell(bytes)
+                    .with_cell(entry.detail.as_deref().unwrap_or("")),
+            );
+        }
+
+        self.print_table(&table);
+        Ok(())
+    }
+}
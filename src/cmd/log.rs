@@ -0,0 +1,76 @@
+use crate::messages::{self, FilePurpose, UtcDateTime};
+use crate::prelude::*;
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+impl GscClient {
+    /// Prints the autograder logs for homework `hw`, oldest first, or only
+    /// the newest if `latest`. Given a `follow` interval, keeps polling
+    /// for logs that are new or have been replaced.
+    pub fn log(&self, hw: usize, latest: bool, follow: Option<Duration>) -> Result<()> {
+        let mut logs = self.fetch_logs(hw)?;
+        let mut seen: HashMap<String, UtcDateTime> = HashMap::new();
+
+        if logs.is_empty() && follow.is_none() {
+            ve1!("There are no logs for hw{}.", hw);
+            return Ok(());
+        }
+
+        if latest {
+            let older = logs.len().saturating_sub(1);
+            for log in logs.drain(..older) {
+                seen.insert(log.name, log.upload_time);
+            }
+        }
+
+        let headings = !latest || follow.is_some();
+
+        loop {
+            for log in &logs {
+                if seen.get(&log.name) != Some(&log.upload_time) {
+                    self.print_log(log, headings)?;
+                    seen.insert(log.name.clone(), log.upload_time.clone());
+                }
+            }
+
+            match follow {
+                Some(interval) => thread::sleep(interval),
+                None => return Ok(()),
+            }
+
+            self.forget_responses();
+            logs = self.fetch_logs(hw)?;
+        }
+    }
+
+    fn fetch_logs(&self, hw: usize) -> Result<Vec<messages::FileMeta>> {
+        let files: Vec<messages::FileMeta> = self.fetch_file_list(hw, None)?;
+
+        let mut logs: Vec<_> = files
+            .into_iter()
+            .filter(|file| file.purpose == FilePurpose::Log)
+            .collect();
+        logs.sort_by(|a, b| a.upload_time.cmp(&b.upload_time));
+
+        Ok(logs)
+    }
+
+    fn print_log(&self, log: &messages::FileMeta, heading: bool) -> Result<()> {
+        let uri = format!("{}{}", self.config().get_endpoint(), log.uri);
+        let mut response = self.send_request(self.http.get(&uri))?;
+
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+
+        if heading {
+            writeln!(stdout, "==> {} ({}) <==", log, log.upload_time)?;
+        }
+
+        response.copy_to(&mut stdout)?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
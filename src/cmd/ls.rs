@@ -1,47 +1,67 @@
+use crate::porcelain;
 use crate::prelude::*;
+use crate::render;
 
 impl GscClient {
-    fn json_ls(&self, rpats: &[RemotePattern]) -> Result<()> {
+    fn json_ls(&self, rpats: &[RemotePattern], limit: Option<usize>) -> Result<()> {
         for rpat in rpats {
             assert!(rpat.name.is_empty(), "not handled");
 
-            let response = self.fetch_raw_file_list(rpat.hw)?;
-            let json = response.text()?;
-            v1!("{}", json);
+            let files: Vec<serde_json::Value> = self.fetch_file_list(rpat.hw, limit)?;
+            self.print_output(serde_json::to_string_pretty(&files)?)?;
         }
 
         Ok(())
     }
 
-    pub fn ls(&self, rpats: &[RemotePattern]) -> Result<()> {
+    /// Lists the files matching each of `rpats`, only the first `limit`
+    /// for each if given.
+    pub fn ls(&self, rpats: &[RemotePattern], limit: Option<usize>) -> Result<()> {
         if self.config().json_output() {
-            return self.json_ls(rpats);
+            return self.json_ls(rpats, limit);
         }
 
         for rpat in rpats {
             self.try_warn(|| {
-                let files = self.fetch_nonempty_matching_file_list(&rpat)?;
+                let files = self.fetch_first_matching_files(rpat, limit)?;
+                if files.is_empty() {
+                    Err(ErrorKind::NoSuchRemoteFile(rpat.clone()))?
+                }
+
+                if self.config().porcelain_output() {
+                    for file in &files {
+                        self.print_output(porcelain::file(file))?;
+                    }
+
+                    return Ok(());
+                }
 
                 if rpats.len() > 1 {
                     v1!("{}:", rpat);
                 }
 
-                let mut table = tabular::Table::new("{:>}  {:<}  [{:<}] {:<}");
+                // Listing files doesn’t depend on knowing which are late:
+                let due = self.fetch_submission(rpat.hw).ok().map(|s| s.due_date);
+                let width = self.config().get_width();
+                v1!("{}", render::file_list(&files, due.as_ref(), width));
 
-                for file in &files {
-                    table.add_row(
-                        tabular::Row::new()
-                            .with_cell(file.byte_count.separate_with_commas())
-                            .with_cell(&file.upload_time)
-                            .with_cell(file.purpose.to_char())
-                            .with_cell(&file.name),
-                    );
-                }
+                Ok(())
+            })?;
+        }
 
-                v1!("{}", table);
+        Ok(())
+    }
 
+    /// Lists the files matching each of `rpats` as a tree, laid out as
+    /// `cp -a` would download them.
+    pub fn ls_tree(&self, rpats: &[RemotePattern]) -> Result<()> {
+        for rpat in rpats {
+            self.try_warn(|| {
+                let files = self.fetch_nonempty_matching_file_list(rpat)?;
+                let width = self.config().get_width();
+                v1!("{}", render::file_tree(rpat.hw, &files, width));
                 Ok(())
-            });
+            })?;
         }
 
         Ok(())
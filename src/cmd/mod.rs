@@ -1,2 +1,27 @@
+pub mod api;
+pub mod archive;
+pub mod audit;
+pub mod chtype;
+pub mod config;
+pub mod cp_all;
+pub mod deadlines;
+pub mod diff;
+pub mod extract;
+pub mod grades;
+pub mod history;
+pub mod log;
 pub mod ls;
 pub mod mv;
+pub mod open;
+pub mod partner;
+pub mod permalinks;
+pub mod ping;
+pub mod progress;
+pub mod push;
+pub mod regrade;
+pub mod status_all;
+pub mod submissions;
+pub mod submit;
+pub mod user_info;
+pub mod verify;
+pub mod whoami;
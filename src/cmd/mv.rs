@@ -1,3 +1,5 @@
+use crate::filename;
+use crate::history;
 use crate::messages::FileMetaChange;
 use crate::prelude::*;
 
@@ -20,7 +22,7 @@ impl GscClient {
             }
 
             if part_dst.name != src.name && !part_dst.name.is_empty() {
-                dst.name = &part_dst.name;
+                dst.name = filename::check(&part_dst.name)?;
                 message.name = Some(dst.name.to_owned());
             }
 
@@ -41,8 +43,13 @@ impl GscClient {
             v2!("Moving remote file ‘{}’ to ‘{}’...", src, dst);
             self.send_request(request)?;
 
+            let entry = history::Entry::new(history::Action::Mv, src.hw)
+                .file(src.name.as_str())
+                .detail(format!("-> {}", dst));
+            self.record(entry);
+
             Ok(())
-        });
+        })?;
 
         Ok(())
     }
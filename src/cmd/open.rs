@@ -0,0 +1,82 @@
+use crate::prelude::*;
+
+use std::env;
+use std::process;
+
+impl GscClient {
+    /// Opens the web page for homework `rpat.hw`, or for file `rpat.name`
+    /// in it, in the browser, or just prints its URL if `print_url`.
+    pub fn open(&self, rpat: &RemotePattern, print_url: bool) -> Result<()> {
+        let api_uri = if rpat.is_whole_hw() {
+            let (who, creds) = self.load_effective_credentials()?;
+            self.get_uri_for_submission(&who, rpat.hw, &creds)?
+        } else {
+            let file = self.fetch_one_matching_filename(rpat)?;
+            format!("{}{}", self.config().get_endpoint(), file.uri)
+        };
+
+        self.show_url(&self.web_url(&api_uri), print_url)
+    }
+
+    /// The web UI’s page for the API resource at `api_uri`, which lives
+    /// at the same path minus the `/api` prefix.
+    pub(crate) fn web_url(&self, api_uri: &str) -> String {
+        let path = api_uri
+            .strip_prefix(self.config().get_endpoint())
+            .unwrap_or(api_uri);
+        let path = path.strip_prefix("/api").unwrap_or(path);
+        format!(
+            "{}{}",
+            self.config().get_web_url().trim_end_matches('/'),
+            path
+        )
+    }
+
+    /// The web URL of a self-eval permalink, which is normally a bare
+    /// token, but might be a path or a whole URL.
+    pub(crate) fn permalink_url(&self, permalink: &str) -> String {
+        if permalink.contains("://") {
+            permalink.to_owned()
+        } else if permalink.starts_with('/') {
+            self.web_url(permalink)
+        } else {
+            self.web_url(&format!("/permalink/{}", permalink))
+        }
+    }
+
+    pub(crate) fn show_url(&self, url: &str, print_url: bool) -> Result<()> {
+        if print_url {
+            v1!("{}", url);
+            return Ok(());
+        }
+
+        v2!("Opening {}", url);
+        open_in_browser(url)
+    }
+}
+
+/// Opens `url` with `$BROWSER` if set, or else the platform’s usual way
+/// of opening things.
+fn open_in_browser(url: &str) -> Result<()> {
+    let mut command = match env::var("BROWSER") {
+        Ok(browser) if !browser.is_empty() => process::Command::new(browser),
+        _ if cfg!(windows) => {
+            let mut command = process::Command::new("cmd");
+            command.args(["/C", "start", ""]);
+            command
+        }
+        _ if cfg!(target_os = "macos") => process::Command::new("open"),
+        _ => process::Command::new("xdg-open"),
+    };
+
+    let status = command
+        .arg(url)
+        .status()
+        .chain_err(|| format!("Could not open browser; the URL is {}", url))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Browser exited with {}; the URL is {}", status, url).into())
+    }
+}
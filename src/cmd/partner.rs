@@ -0,0 +1,102 @@
+use crate::config::prompt_letter;
+use crate::credentials::Credentials;
+use crate::messages::{self, PartnerRequestStatus};
+use crate::porcelain;
+use crate::prelude::*;
+use crate::render;
+
+impl GscClient {
+    pub fn partner_list(&self) -> Result<()> {
+        let (who, creds) = self.load_effective_credentials()?;
+        let uri = self.user_uri(&who);
+        let request = self.http.get(&uri);
+        let response = self.send_request_with_credentials(request, &creds)?;
+        let user: messages::User = response.message()?;
+
+        if self.config().json_output() {
+            return self.print_output(serde_json::to_string_pretty(&user.partner_requests)?);
+        }
+
+        if self.config().porcelain_output() {
+            return self.print_porcelain_partners(&user);
+        }
+
+        if user.partner_requests.is_empty() {
+            ve1!("No partner requests.");
+            return Ok(());
+        }
+
+        v1!(
+            "Partner requests for {}:\n{}",
+            user.name,
+            render::partner_history(&user.partner_requests)
+        );
+
+        Ok(())
+    }
+
+    pub(crate) fn print_porcelain_partners(&self, user: &messages::User) -> Result<()> {
+        for p in &user.partner_requests {
+            self.print_output(porcelain::partner_request(p))?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn resolve_incoming_partner_requests(&self, user: &messages::User) -> Result<()> {
+        if self.config().json_output()
+            || self.config().porcelain_output()
+            || self.config().get_unattended().is_some()
+        {
+            return Ok(());
+        }
+
+        let options = [
+            "[Y]es, accept this request",
+            "[N]o, reject this request",
+            "[S]kip this request for now",
+        ];
+
+        for p in &user.partner_requests {
+            if p.status != PartnerRequestStatus::Incoming {
+                continue;
+            }
+
+            let prompt = format!(
+                "Accept hw{} partner request from {} [Y/N/S]? ",
+                p.assignment_number, p.user
+            );
+
+            let answer = prompt_letter(&prompt, &options, |c| match c {
+                'y' | 'n' | 's' => Some(c),
+                _ => None,
+            })?;
+
+            match answer {
+                'y' => self.partner_accept(p.assignment_number, &p.user)?,
+                'n' => self.partner_cancel(p.assignment_number, &p.user)?,
+                _ => v2!("Skipping request from {}.", p.user),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn fetch_incoming_partner_requests(
+        &self,
+        who: &str,
+        creds: &Credentials,
+        hw: usize,
+    ) -> Result<Vec<messages::PartnerRequest>> {
+        let uri = self.user_uri(who);
+        let request = self.http.get(&uri);
+        let response = self.send_request_with_credentials(request, creds)?;
+        let user: messages::User = response.message()?;
+
+        Ok(user
+            .partner_requests
+            .into_iter()
+            .filter(|p| p.assignment_number == hw && p.status == PartnerRequestStatus::Incoming)
+            .collect())
+    }
+}
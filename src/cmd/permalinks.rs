@@ -0,0 +1,71 @@
+use crate::prelude::*;
+use crate::render;
+
+use serde_derive::Serialize;
+
+use std::iter;
+
+#[derive(Serialize)]
+struct PermalinkRow {
+    user: String,
+    hw: usize,
+    item: usize,
+    permalink: String,
+}
+
+impl GscClient {
+    /// Lists the self-eval permalink of every user with a submission for
+    /// homework `hw`, for eval item `item` only if given, as a table, as
+    /// CSV with `csv`, or as JSON with `--json`. Partners each get a row.
+    pub fn admin_permalinks(&self, hw: usize, item: Option<usize>, csv: bool) -> Result<()> {
+        let submissions = self.fetch_hw_evals(hw, item)?;
+
+        let mut rows = Vec::new();
+        for (submission, evals) in &submissions {
+            let owners = iter::once(&submission.owner1).chain(&submission.owner2);
+            for owner in owners {
+                for eval in evals {
+                    if let Some(self_eval) = &eval.self_eval {
+                        rows.push(PermalinkRow {
+                            user: owner.name.clone(),
+                            hw,
+                            item: eval.sequence,
+                            permalink: self.permalink_url(&self_eval.permalink),
+                        });
+                    }
+                }
+            }
+        }
+
+        rows.sort_by(|a, b| (&a.user, a.item).cmp(&(&b.user, b.item)));
+
+        if self.config().json_output() {
+            return self.print_output(serde_json::to_string_pretty(&rows)?);
+        }
+
+        if csv {
+            let error = || "Could not write the permalinks as CSV";
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            for row in &rows {
+                writer.serialize(row).chain_err(error)?;
+            }
+
+            let text = writer.into_inner().map_err(|e| e.into_error())?;
+            return self.with_output(|out| out.write_all(&text));
+        }
+
+        let mut table = render::Table::new("  {:<}  {:>}  {:<}");
+        for row in rows {
+            table.add_row(
+                render::Row::new()
+                    .with_cell(row.user)
+                    .with_cell(row.item)
+                    .with_cell(row.permalink),
+            );
+        }
+
+        self.print_table(&table);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use crate::prelude::*;
+
+use std::time::Instant;
+
+impl GscClient {
+    /// Checks whether the server is up by requesting `/api/whoami`
+    /// without credentials. This bypasses the cache and doesn’t retry, so
+    /// that the latency reported is that of a single request.
+    pub fn ping(&self) -> Result<()> {
+        let endpoint = self.config().get_endpoint();
+
+        if self.config().offline() {
+            Err(ErrorKind::Offline("GET".to_owned()))?;
+        }
+
+        let uri = format!("{}/api/whoami", endpoint);
+        let request = self.http.get(&uri).build()?;
+
+        if let Some(tracer) = &self.tracer {
+            tracer.request(&request)?;
+        }
+
+        let start = Instant::now();
+        let result = self.http.execute(request);
+        let latency = start.elapsed();
+
+        let result = match (result, &self.tracer) {
+            (Ok(response), Some(tracer)) => tracer.response(response).map_err(|e| e.to_string()),
+            (result, _) => result.map_err(|e| e.to_string()),
+        };
+
+        let status = result.as_ref().ok().map(|response| response.status());
+        let up = status.is_some_and(|status| !status.is_server_error());
+
+        if self.config().json_output() {
+            let json = serde_json::json!({
+                "endpoint": endpoint,
+                "up": up,
+                "status": status.map(|status| status.as_u16()),
+                "latency_ms": latency.as_millis() as u64,
+            });
+            self.print_output(serde_json::to_string_pretty(&json)?)?;
+        } else if up {
+            v1!("{} is up ({} ms)", endpoint, latency.as_millis());
+        }
+
+        match (result, status) {
+            (Err(message), _) => Err(ErrorKind::ServerUnreachable(endpoint.to_owned(), message))?,
+            (_, Some(status)) if !up => Err(ErrorKind::ServerUnavailable(
+                endpoint.to_owned(),
+                status.to_string(),
+            ))?,
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::messages::GraderEvalStatus;
+use crate::prelude::*;
+use crate::render;
+
+use serde_json::json;
+
+use std::collections::BTreeMap;
+
+/// How far along grading is for one eval item.
+#[derive(Default)]
+struct ItemProgress {
+    ungraded: usize,
+    by_status: BTreeMap<GraderEvalStatus, usize>,
+}
+
+/// How many evals one grader has finished, and how many they’ve started.
+#[derive(Default)]
+struct GraderProgress {
+    completed: usize,
+    in_progress: usize,
+}
+
+impl ItemProgress {
+    fn count(&self, status: GraderEvalStatus) -> usize {
+        self.by_status.get(&status).copied().unwrap_or(0)
+    }
+}
+
+impl GscClient {
+    /// Shows how grading of homework `hw` is going: for each eval item, how
+    /// many submissions have a grader eval in each status (or none), and
+    /// for each grader, how many evals they have completed.
+    pub fn admin_progress(&self, hw: usize) -> Result<()> {
+        let submissions = self.fetch_hw_evals(hw, None)?;
+
+        let mut items: BTreeMap<usize, ItemProgress> = BTreeMap::new();
+        let mut graders: BTreeMap<String, GraderProgress> = BTreeMap::new();
+
+        for eval in submissions.iter().flat_map(|(_, evals)| evals) {
+            let item = items.entry(eval.sequence).or_default();
+            match &eval.grader_eval {
+                Some(grader_eval) => {
+                    *item.by_status.entry(grader_eval.status).or_default() += 1;
+                    let grader = graders.entry(grader_eval.grader.clone()).or_default();
+                    if grader_eval.status == GraderEvalStatus::Ready {
+                        grader.completed += 1;
+                    } else {
+                        grader.in_progress += 1;
+                    }
+                }
+                None => item.ungraded += 1,
+            }
+        }
+
+        if self.config().json_output() {
+            let items: Vec<_> = items
+                .iter()
+                .map(|(sequence, item)| {
+                    json!({
+                        "sequence": sequence,
+                        "ungraded": item.ungraded,
+                        "editing": item.count(GraderEvalStatus::Editing),
+                        "held_back": item.count(GraderEvalStatus::HeldBack),
+                        "ready": item.count(GraderEvalStatus::Ready),
+                        "regrade": item.count(GraderEvalStatus::Regrade),
+                    })
+                })
+                .collect();
+            let graders: Vec<_> = graders
+                .iter()
+                .map(|(name, grader)| {
+                    json!({
+                        "grader": name,
+                        "completed": grader.completed,
+                        "in_progress": grader.in_progress,
+                    })
+                })
+                .collect();
+            let json = json!({
+                "assignment_number": hw,
+                "submissions": submissions.len(),
+                "items": items,
+                "graders": graders,
+            });
+            return self.print_output(serde_json::to_string_pretty(&json)?);
+        }
+
+        let mut table = render::Table::new("  {:>}  {:>}  {:>}  {:>}  {:>}  {:>}");
+        table.add_row(
+            render::Row::new()
+                .with_cell("Item")
+                .with_cell("Ungraded")
+                .with_cell("Editing")
+                .with_cell("Held back")
+                .with_cell("Ready")
+                .with_cell("Regrade"),
+        );
+
+        for (sequence, item) in &items {
+            table.add_row(
+                render::Row::new()
+                    .with_cell(sequence)
+                    .with_cell(item.ungraded)
+                    .with_cell(item.count(GraderEvalStatus::Editing))
+                    .with_cell(item.count(GraderEvalStatus::HeldBack))
+                    .with_cell(item.count(GraderEvalStatus::Ready))
+                    .with_cell(item.count(GraderEvalStatus::Regrade)),
+            );
+        }
+
+        v1!(
+            "Grading progress for hw{} ({} submissions):\n",
+            hw,
+            submissions.len()
+        );
+        self.print_table(&table);
+
+        if !graders.is_empty() {
+            let mut table = render::Table::new("  {:<}  {:>}  {:>}").flex(0);
+            table.add_row(
+                render::Row::new()
+                    .with_cell("Grader")
+                    .with_cell("Completed")
+                    .with_cell("In progress"),
+            );
+
+            for (name, grader) in &graders {
+                table.add_row(
+                    render::Row::new()
+                        .with_cell(name)
+                        .with_cell(grader.completed)
+                        .with_cell(grader.in_progress),
+                );
+            }
+
+            v1!("");
+            self.print_table(&table);
+        }
+
+        Ok(())
+    }
+}
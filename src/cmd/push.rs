@@ -0,0 +1,131 @@
+use crate::prelude::*;
+
+use std::collections::HashMap;
+use std::process::Command;
+
+/// The file that records which commit was pushed.
+const COMMIT_FILE: &str = "gsc-commit.txt";
+
+/// A file tracked at `HEAD`, as listed by `git ls-tree`.
+struct TrackedFile {
+    path: String,
+    object: String,
+}
+
+impl GscClient {
+    /// Uploads the files tracked at git `HEAD` under the current directory
+    /// to homework `hw`, along with a file recording the commit hash, and
+    /// then tags the commit if `tag` is set.
+    pub fn push_git(&self, hw: usize, tag: bool) -> Result<()> {
+        let commit = git_string(&["rev-parse", "--verify", "HEAD"])?;
+        let files = tracked_files()?;
+
+        // Files are uploaded by base name, so directories mustn’t
+        // disagree about which file is which:
+        let mut names = HashMap::new();
+        for file in &files {
+            if let Some(other) = names.insert(base_name(&file.path), &file.path) {
+                Err(ErrorKind::DuplicateFileName(
+                    base_name(&file.path).to_owned(),
+                    other.clone(),
+                    file.path.clone(),
+                ))?;
+            }
+        }
+
+        if !git_string(&["status", "--porcelain", "--untracked-files=no", "."])?.is_empty() {
+            self.warn("Warning: uncommitted changes will not be pushed.");
+        }
+
+        let mut blobs = Vec::new();
+        for file in &files {
+            let contents = git(&["cat-file", "blob", &file.object])?;
+            self.check_upload_contents(&file.path, contents.len() as u64, &contents)?;
+            blobs.push((file, contents));
+        }
+
+        self.warn_if_late(hw)?;
+        self.run_pre_push_hook(hw)?;
+
+        for (file, contents) in blobs {
+            let dst = RemotePattern::hw_name(hw, base_name(&file.path));
+            v2!("Uploading ‘{}’ at {:.7} -> ‘{}’...", file.path, commit, dst);
+            let bytes = contents.len() as u64;
+            self.upload_body(&file.path, contents, bytes, &dst)?;
+        }
+
+        let dst = RemotePattern::hw_name(hw, COMMIT_FILE);
+        v2!("Recording commit {} in ‘{}’...", commit, dst);
+        let contents = format!("{}\n", commit);
+        let bytes = contents.len() as u64;
+        self.upload_body(COMMIT_FILE, contents, bytes, &dst)?;
+
+        if tag {
+            let now = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+            let name = format!("gsc/hw{}/{}", hw, now);
+            git(&["tag", &name, &commit])?;
+            v2!("Tagged {:.7} as ‘{}’.", commit, name);
+        }
+
+        v1!(
+            "Pushed {} file(s) from commit {:.7} to hw{}.",
+            files.len(),
+            commit,
+            hw
+        );
+        Ok(())
+    }
+}
+
+/// The regular files tracked at `HEAD` under the current directory.
+/// Symbolic links and submodules are skipped.
+fn tracked_files() -> Result<Vec<TrackedFile>> {
+    let listing = git(&["ls-tree", "-r", "-z", "HEAD"])?;
+    let mut files = Vec::new();
+
+    for entry in listing.split(|&b| b == 0).filter(|entry| !entry.is_empty()) {
+        let entry = String::from_utf8_lossy(entry);
+
+        // Each entry is ‘<mode> <type> <object>\t<path>’:
+        let (info, path) = match entry.split_once('\t') {
+            Some(parts) => parts,
+            None => continue,
+        };
+        let info: Vec<&str> = info.split(' ').collect();
+
+        match info.as_slice() {
+            ["100644", "blob", object] | ["100755", "blob", object] => files.push(TrackedFile {
+                path: path.to_owned(),
+                object: (*object).to_owned(),
+            }),
+            _ => ve2!("Skipping ‘{}’, which is not a regular file", path),
+        }
+    }
+
+    Ok(files)
+}
+
+fn base_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn git(args: &[&str]) -> Result<Vec<u8>> {
+    let command = format!("git {}", args.join(" "));
+    ve3!("Running {}", command);
+
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .chain_err(|| format!("Could not run {}", command))?;
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+        Err(ErrorKind::GitFailed(command, stderr))
+    }
+}
+
+fn git_string(args: &[&str]) -> Result<String> {
+    Ok(String::from_utf8_lossy(&git(args)?).trim().to_owned())
+}
@@ -0,0 +1,111 @@
+use crate::messages::{self, GraderEvalStatus};
+use crate::prelude::*;
+use crate::render;
+
+use serde_json::json;
+
+impl GscClient {
+    /// Asks for item `number` of homework `hw` to be graded again, for
+    /// `reason`, by setting its grader eval’s status to `regrade`.
+    pub fn regrade(&self, hw: usize, number: usize, reason: &str) -> Result<()> {
+        let submission = self.fetch_submission(hw)?;
+        let uri = format!(
+            "{}{}/{}",
+            self.config().get_endpoint(),
+            submission.evals_uri,
+            number
+        );
+        let request = self.http.get(&uri);
+        let eval: messages::Eval = self.send_request(request)?.message()?;
+
+        let grader_eval = eval
+            .grader_eval
+            .ok_or(ErrorKind::NotGradedYet(hw, number))?;
+
+        if grader_eval.status == GraderEvalStatus::Regrade {
+            self.warn(format!(
+                "A regrade of hw{} item {} has already been requested.",
+                hw, number
+            ));
+            return Ok(());
+        }
+
+        let message = messages::RegradeRequest {
+            status: GraderEvalStatus::Regrade,
+            regrade_reason: reason,
+        };
+        let request = self.http.patch(&format!("{}/grader", uri)).json(&message);
+        let _: messages::GraderEval = self.send_request(request)?.message()?;
+
+        v1!("Requested a regrade of hw{} item {}.", hw, number);
+
+        Ok(())
+    }
+
+    /// Lists the open regrade requests for homework `hw`: the grader evals
+    /// whose status is `regrade`, with who asked and why.
+    pub fn admin_regrades(&self, hw: usize) -> Result<()> {
+        let mut requests = Vec::new();
+
+        for (submission, evals) in self.fetch_hw_evals(hw, None)? {
+            let owners = std::iter::once(&submission.owner1)
+                .chain(&submission.owner2)
+                .map(|owner| owner.name.as_str())
+                .collect::<Vec<_>>()
+                .join(" and ");
+
+            for eval in evals {
+                if let Some(grader_eval) = eval.grader_eval {
+                    if grader_eval.status == GraderEvalStatus::Regrade {
+                        requests.push((owners.clone(), eval.sequence, grader_eval));
+                    }
+                }
+            }
+        }
+
+        if self.config().json_output() {
+            let json: Vec<_> = requests
+                .iter()
+                .map(|(owners, sequence, grader_eval)| {
+                    json!({
+                        "owners": owners,
+                        "sequence": sequence,
+                        "grader": grader_eval.grader,
+                        "score": grader_eval.score,
+                        "regrade_reason": grader_eval.regrade_reason,
+                        "uri": grader_eval.uri,
+                    })
+                })
+                .collect();
+            return self.print_output(serde_json::to_string_pretty(&json)?);
+        }
+
+        if requests.is_empty() {
+            v1!("No regrade requests for hw{}.", hw);
+            return Ok(());
+        }
+
+        let mut table = render::Table::new("  {:<}  {:>}  {:<}  {:<}");
+        table.add_row(
+            render::Row::new()
+                .with_cell("Submission")
+                .with_cell("Item")
+                .with_cell("Grader")
+                .with_cell("Reason"),
+        );
+
+        for (owners, sequence, grader_eval) in requests {
+            table.add_row(
+                render::Row::new()
+                    .with_cell(owners)
+                    .with_cell(sequence)
+                    .with_cell(grader_eval.grader)
+                    .with_cell(grader_eval.regrade_reason.unwrap_or_default()),
+            );
+        }
+
+        self.print_table(&table);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use crate::messages::{self, SubmissionStatus};
+use crate::porcelain;
+use crate::prelude::*;
+use crate::render;
+
+struct HwStatus {
+    submission: serde_json::Value,
+    file_count: usize,
+}
+
+impl GscClient {
+    pub fn status_all(&self) -> Result<()> {
+        let (who, creds) = self.load_effective_credentials()?;
+        let endpoint = self.config().get_endpoint();
+
+        let uris: Vec<String> = self
+            .fetch_submissions(&who, &creds)?
+            .into_iter()
+            .filter(|s| s.status != SubmissionStatus::Future)
+            .map(|s| format!("{}{}", endpoint, s.uri))
+            .collect();
+
+        let statuses = self.fetch_in_parallel(&uris, |fetcher, uri| {
+            let submission = fetcher.get(uri)?.message()?;
+            let files: Vec<serde_json::Value> = fetcher.get(&format!("{}/files", uri))?.json()?;
+            Ok(HwStatus {
+                submission,
+                file_count: files.len(),
+            })
+        })?;
+
+        if self.config().json_output() {
+            let json: Vec<_> = statuses
+                .into_iter()
+                .map(|status| {
+                    let mut submission = status.submission;
+                    submission["file_count"] = status.file_count.into();
+                    submission
+                })
+                .collect();
+            return self.print_output(serde_json::to_string_pretty(&json)?);
+        }
+
+        if self.config().porcelain_output() {
+            for status in statuses {
+                let submission: messages::Submission = serde_json::from_value(status.submission)?;
+                let record = porcelain::submission(
+                    submission.assignment_number,
+                    submission.status,
+                    submission.grade,
+                )
+                .date(&submission.due_date)
+                .date(&submission.eval_date)
+                .field(status.file_count)
+                .field(submission.bytes_used);
+                self.print_output(record)?;
+            }
+
+            return Ok(());
+        }
+
+        let mut table = render::Table::new("  {:<}  {:<}  {:<}  {:<}  {:>}  {:>}");
+        table.add_row(
+            render::Row::new()
+                .with_cell("")
+                .with_cell("Status")
+                .with_cell("Due")
+                .with_cell("Self-eval due")
+                .with_cell("Files")
+                .with_cell("Bytes used"),
+        );
+
+        for status in statuses {
+            let submission: messages::Submission = serde_json::from_value(status.submission)?;
+            table.add_row(
+                render::Row::new()
+                    .with_cell(format!("hw{}", submission.assignment_number))
+                    .with_cell(submission.status)
+                    .with_cell(submission.due_date)
+                    .with_cell(submission.eval_date)
+                    .with_cell(status.file_count)
+                    .with_cell(submission.bytes_used.separate_with_commas()),
+            );
+        }
+
+        v1!("Status for {}:\n", who);
+        self.print_table(&table);
+
+        Ok(())
+    }
+}
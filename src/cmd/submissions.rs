@@ -0,0 +1,203 @@
+use crate::messages::{self, SubmissionStatus, UtcDateTime};
+use crate::prelude::*;
+use crate::render;
+
+use serde_derive::Serialize;
+
+/// The keys that `gsc-admin submissions --sort` accepts.
+const SORT_KEYS: &[&str] = &["id", "owner", "status", "grade", "modified"];
+
+#[derive(Serialize)]
+struct SubmissionRow {
+    id: usize,
+    owner1: String,
+    owner2: Option<String>,
+    status: SubmissionStatus,
+    grade: f64,
+    last_modified: UtcDateTime,
+}
+
+impl GscClient {
+    /// Lists the submissions for homework `hw` with their grades and when
+    /// they last changed, as a table, as CSV with `csv`, or as JSON with
+    /// `--json`. Given `statuses`, it lists only submissions with one of
+    /// them, and given `limit`, only the first that many that the server
+    /// lists. It orders them by `sort`, one of `SORT_KEYS`, backward with
+    /// `reverse`.
+    pub fn admin_submissions(
+        &self,
+        hw: usize,
+        statuses: &[SubmissionStatus],
+        sort: &str,
+        reverse: bool,
+        csv: bool,
+        limit: Option<usize>,
+    ) -> Result<()> {
+        let endpoint = self.config.get_endpoint();
+
+        let mut uris = Vec::new();
+        self.for_each_hw_submission(hw, |s| {
+            if statuses.is_empty() || statuses.contains(&s.status) {
+                uris.push(format!("{}{}", endpoint, s.uri));
+            }
+            limit.map_or(true, |limit| uris.len() < limit)
+        })?;
+
+        let mut rows = self.fetch_in_parallel(&uris, |fetcher, uri| {
+            let submission: messages::Submission = fetcher.get(uri)?.message()?;
+            Ok(SubmissionRow {
+                id: submission.id,
+                owner1: submission.owner1.name,
+                owner2: submission.owner2.map(|o| o.name),
+                status: submission.status,
+                grade: submission.grade,
+                last_modified: submission.last_modified,
+            })
+        })?;
+
+        match sort {
+            "id" => rows.sort_by_key(|row| row.id),
+            "owner" => rows.sort_by(|a, b| a.owner1.cmp(&b.owner1)),
+            "status" => rows.sort_by_key(|row| row.status),
+            "grade" => rows.sort_by(|a, b| a.grade.total_cmp(&b.grade)),
+            "modified" => rows.sort_by(|a, b| a.last_modified.cmp(&b.last_modified)),
+            _ => Err(
+                match crate::suggest::closest(sort, SORT_KEYS.iter().copied()) {
+                    Some(key) => {
+                        ErrorKind::SyntaxErrorSuggest("sort key".into(), sort.into(), key.into())
+                    }
+                    None => ErrorKind::syntax("sort key", sort),
+                },
+            )?,
+        }
+
+        if reverse {
+            rows.reverse();
+        }
+
+        if self.config().json_output() {
+            return self.print_output(serde_json::to_string_pretty(&rows)?);
+        }
+
+        if csv {
+            let error = || "Could not write the submissions as CSV";
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            for row in &rows {
+                writer.serialize(row).chain_err(error)?;
+            }
+
+            let text = writer.into_inner().map_err(|e| e.into_error())?;
+            return self.with_output(|out| out.write_all(&text));
+        }
+
+        let mut table = render::Table::new(" {:>}  {:<}  {:<}  {:<}  {:>}  {:<}").flex(1);
+        table.add_row(
+            render::Row::new()
+                .with_cell("Id")
+                .with_cell("Owner")
+                .with_cell("Partner")
+                .with_cell("Status")
+                .with_cell("Grade")
+                .with_cell("Last modified"),
+        );
+
+        for row in rows {
+            table.add_row(
+                render::Row::new()
+                    .with_cell(row.id)
+                    .with_cell(row.owner1)
+                    .with_cell(row.owner2.unwrap_or_default())
+                    .with_cell(row.status)
+                    .with_cell(format!("{:.1}%", 100.0 * row.grade))
+                    .with_cell(row.last_modified),
+            );
+        }
+
+        self.print_table(&table);
+
+        Ok(())
+    }
+}
+
+impl GscClient {
+    /// The submissions for homework `hw`, without their details.
+    pub(crate) fn fetch_hw_submissions(&self, hw: usize) -> Result<Vec<messages::SubmissionShort>> {
+        let mut submissions = Vec::new();
+        self.for_each_hw_submission(hw, |s| {
+            submissions.push(s);
+            true
+        })?;
+        Ok(submissions)
+    }
+
+    /// Passes each submission for homework `hw` to `f` as it’s read from
+    /// the server, since for a big class the list is long, until `f`
+    /// returns `false`.
+    pub(crate) fn for_each_hw_submission(
+        &self,
+        hw: usize,
+        f: impl FnMut(messages::SubmissionShort) -> bool,
+    ) -> Result<()> {
+        let uri = format!("{}/api/submissions/hw{}", self.config.get_endpoint(), hw);
+        let creds = self.load_credentials()?;
+        self.for_each_listed(&uri, &creds, true, f)
+    }
+
+    /// The submissions for homework `hw`, each with its evals (only item
+    /// `item`, if given), fetched in parallel.
+    pub(crate) fn fetch_hw_evals(
+        &self,
+        hw: usize,
+        item: Option<usize>,
+    ) -> Result<Vec<(messages::SubmissionShort, Vec<messages::Eval>)>> {
+        let endpoint = self.config.get_endpoint();
+        let submissions = self.fetch_hw_submissions(hw)?;
+        let uris: Vec<String> = submissions
+            .iter()
+            .map(|s| format!("{}{}/evals", endpoint, s.uri))
+            .collect();
+
+        let evals = self.fetch_in_parallel(&uris, |fetcher, uri| {
+            let shorts: Vec<messages::EvalShort> = fetcher.get(uri)?.message()?;
+            shorts
+                .iter()
+                .filter(|eval| item.map_or(true, |item| eval.sequence == item))
+                .map(|eval| {
+                    let uri = format!("{}{}", endpoint, eval.uri);
+                    fetcher.get(&uri)?.message()
+                })
+                .collect()
+        })?;
+
+        Ok(submissions.into_iter().zip(evals).collect())
+    }
+
+    /// Looks up submission `id` (as `gsc-admin submissions` or the server
+    /// logs show it) and prints whose it is, for which homework, and its
+    /// status.
+    pub fn admin_whois(&self, id: usize) -> Result<()> {
+        let uri = format!("{}/api/submissions/{}", self.config.get_endpoint(), id);
+        let request = self.http.get(&uri);
+        let response = self.send_request(request)?;
+
+        if self.config().json_output() {
+            return self.print_output(response.text()?);
+        }
+
+        let submission: messages::Submission = response.message()?;
+        let owners = match &submission.owner2 {
+            Some(owner2) => format!("{} and {}", submission.owner1.name, owner2.name),
+            None => submission.owner1.name.clone(),
+        };
+
+        v1!(
+            "Submission {} is hw{} for {}.",
+            submission.id,
+            submission.assignment_number,
+            owners
+        );
+        v1!("Status: {}", submission.status);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use crate::messages::{self, FilePurpose, SubmissionStatus, UtcDateTime};
+use crate::prelude::*;
+use crate::render;
+
+use serde_derive::Serialize;
+use sha2::{Digest, Sha256};
+
+use std::fs;
+use std::path::Path;
+
+/// A record of exactly what was submitted, for the student to keep.
+#[derive(Serialize, Debug)]
+struct Receipt<'a> {
+    user: &'a str,
+    assignment_number: usize,
+    status: SubmissionStatus,
+    due_date: &'a UtcDateTime,
+    generated: UtcDateTime,
+    files: Vec<ReceiptFile<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+struct ReceiptFile<'a> {
+    name: &'a str,
+    purpose: FilePurpose,
+    byte_count: usize,
+    upload_time: &'a UtcDateTime,
+    sha256: String,
+}
+
+impl GscClient {
+    /// Prints a receipt for homework `hw`: each submitted file with its
+    /// size, upload time, and SHA-256 hash. Also writes the receipt as
+    /// JSON to `receipt_file`, if given.
+    pub fn submit(&self, hw: usize, receipt_file: Option<&Path>) -> Result<()> {
+        let (who, creds) = self.load_effective_credentials()?;
+        let uri = self.get_uri_for_submission(&who, hw, &creds)?;
+
+        let request = self.http.get(&uri);
+        let submission: messages::Submission = self.send_request(request)?.message()?;
+
+        let metas: Vec<messages::FileMeta> = self.fetch_file_list(hw, None)?;
+        let metas: Vec<_> = metas
+            .iter()
+            .filter(|meta| meta.purpose != FilePurpose::Log)
+            .collect();
+
+        let endpoint = self.config().get_endpoint();
+        let uris: Vec<String> = metas
+            .iter()
+            .map(|meta| format!("{}{}", endpoint, meta.uri))
+            .collect();
+        let hashes = self.fetch_in_parallel(&uris, |fetcher, uri| {
+            Ok(hex_digest(&fetcher.get(uri)?.bytes()?))
+        })?;
+
+        let receipt = Receipt {
+            user: &who,
+            assignment_number: hw,
+            status: submission.status,
+            due_date: &submission.due_date,
+            generated: UtcDateTime::now(),
+            files: metas
+                .iter()
+                .zip(hashes)
+                .map(|(meta, sha256)| ReceiptFile {
+                    name: &meta.name,
+                    purpose: meta.purpose,
+                    byte_count: meta.byte_count,
+                    upload_time: &meta.upload_time,
+                    sha256,
+                })
+                .collect(),
+        };
+
+        let json = serde_json::to_string_pretty(&receipt)?;
+
+        if let Some(path) = receipt_file {
+            let mut policy = self.config().get_overwrite_policy();
+            if self.is_okay_to_write_local(&mut policy, path)? {
+                fs::write(path, format!("{}\n", json))
+                    .chain_err(|| format!("Could not write receipt: {}", path.display()))?;
+                v2!("Wrote receipt to {}", path.display());
+            }
+        }
+
+        if self.config().json_output() {
+            return self.print_output(json);
+        }
+
+        v1!(
+            "Receipt for hw{} ({}), submitted by {}, as of {}:\n",
+            hw,
+            receipt.status,
+            who,
+            receipt.generated
+        );
+
+        // Digests are no use cut short, so names give way instead:
+        let mut table = render::Table::new("  {:>}  {:<}  [{:<}] {:<}  {:<}").flex(3);
+
+        for file in &receipt.files {
+            table.add_row(
+                render::Row::new()
+                    .with_cell(file.byte_count.separate_with_commas())
+                    .with_cell(file.upload_time)
+                    .with_cell(file.purpose.to_char())
+                    .with_cell(file.name)
+                    .with_cell(&file.sha256),
+            );
+        }
+
+        self.print_table(&table);
+
+        Ok(())
+    }
+}
+
+/// The SHA-256 digest of `data`, in lowercase hexadecimal.
+fn hex_digest(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,21 @@
+use crate::messages;
+use crate::prelude::*;
+
+impl GscClient {
+    pub fn admin_user_info(&self, username: &str) -> Result<()> {
+        let uri = self.user_uri(username);
+        let request = self.http.get(&uri);
+        let response = self.send_request(request)?;
+
+        if self.config().json_output() {
+            return self.print_output(response.text()?);
+        }
+
+        let user: messages::User = response.message()?;
+
+        v1!("User {} ({}):\n", user.name, user.role);
+        self.print_user_record(&user, true);
+
+        Ok(())
+    }
+}
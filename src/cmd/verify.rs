@@ -0,0 +1,103 @@
+use crate::cmd::extract::IgnoreSet;
+use crate::filename;
+use crate::messages::FilePurpose;
+use crate::porcelain;
+use crate::prelude::*;
+
+use thousands::Separable;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+impl GscClient {
+    /// Checks that the submission for homework `hw` matches the local
+    /// directory `dir`, laid out as by `cp -a`: every local file must be
+    /// on the server with the same size (and the type its subdirectory
+    /// implies, if any), and the server must have no other files, nor
+    /// any that the ignore patterns would have kept from uploading.
+    pub fn verify(&self, hw: usize, dir: &Path) -> Result<()> {
+        let ignore = IgnoreSet::new(self.config.get_ignore_patterns())?;
+
+        let mut local = BTreeMap::new();
+        for (path, name, purpose) in self.local_files_all(&[dir.to_owned()])? {
+            let size = fs::metadata(&path)
+                .chain_err(|| format!("Could not read {}", path.display()))?
+                .len() as usize;
+            local.insert(name, (size, purpose));
+        }
+
+        let remote: BTreeMap<_, _> = self
+            .fetch_matching_file_list(&RemotePattern::just_hw(hw))?
+            .into_iter()
+            .filter(|file| file.purpose != FilePurpose::Log)
+            .map(|file| (file.name.clone(), file))
+            .collect();
+
+        let mut names: Vec<&String> = local.keys().chain(remote.keys()).collect();
+        names.sort();
+        names.dedup();
+
+        let porcelain = self.config().porcelain_output();
+        let mut report = tabular::Table::new("  {:<}  {:<}  {:<}");
+        let mut problems = 0;
+
+        for name in names {
+            let (verdict, detail) = match (local.get(name), remote.get(name)) {
+                (Some(&(size, _)), Some(file)) if size != file.byte_count => (
+                    "size",
+                    format!(
+                        "{} bytes here, {} on the server",
+                        size.separate_with_commas(),
+                        file.byte_count.separate_with_commas()
+                    ),
+                ),
+                (Some(&(_, Some(purpose))), Some(file)) if purpose != file.purpose => (
+                    "type",
+                    format!("{} here, {} on the server", purpose, file.purpose),
+                ),
+                (Some(_), Some(_)) => ("ok", String::new()),
+                (Some(_), None) => ("missing", "not on the server".to_owned()),
+                (None, Some(_)) if ignore.is_match(name) => {
+                    ("forbidden", "matches the ignore setting".to_owned())
+                }
+                (None, Some(_)) => ("extra", "only on the server".to_owned()),
+                (None, None) => unreachable!(),
+            };
+
+            if verdict != "ok" {
+                problems += 1;
+            }
+
+            if porcelain {
+                self.print_output(porcelain::verify(hw, verdict, name))?;
+                continue;
+            }
+
+            report.add_row(
+                tabular::Row::new()
+                    .with_cell(verdict)
+                    .with_cell(filename::display(name))
+                    .with_cell(detail),
+            );
+        }
+
+        if !porcelain {
+            v1!("{}", report);
+        }
+
+        if problems > 0 {
+            self.warn(format!(
+                "hw{} doesn’t match {}: {} problem{}.",
+                hw,
+                dir.display(),
+                problems,
+                if problems == 1 { "" } else { "s" }
+            ));
+        } else if !porcelain {
+            v1!("hw{} matches {}.", hw, dir.display());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use crate::messages::{UserRole, UtcDateTime};
+use crate::prelude::*;
+use crate::render;
+
+use serde_derive::Serialize;
+
+#[derive(Serialize)]
+struct WhoAmI<'a> {
+    username: String,
+    role: Option<UserRole>,
+    endpoint: &'a str,
+    credentials: String,
+    /// When the server last accepted the credentials, if we know.
+    validated: Option<UtcDateTime>,
+    /// Whether `validated` comes from the cache, because we’re offline.
+    cached: bool,
+}
+
+impl GscClient {
+    /// Prints who the server says we’re logged in as, along with the
+    /// details that matter when that isn’t what we expected: the role,
+    /// the endpoint, where the credentials came from, and when they last
+    /// worked. Offline, the username and when they last worked come from
+    /// the credentials file, and the role from the cache.
+    pub fn whoami(&self) -> Result<()> {
+        let creds = self.load_credentials()?;
+
+        let (username, validated, cached) = if self.config.offline() {
+            let uri = self.user_uri(creds.username());
+            let validated = creds.validated().map(UtcDateTime::from).or_else(|| {
+                self.cache
+                    .as_ref()
+                    .and_then(|cache| cache.lookup(Some(creds.username()), &uri))
+                    .map(|entry| entry.fetched)
+            });
+            (creds.username().to_owned(), validated, true)
+        } else {
+            let uri = format!("{}/api/whoami", self.config.get_endpoint());
+            let request = self.http.get(&uri);
+            let text = self
+                .send_request_with_credentials(request, &creds)?
+                .text()?;
+            // The server sends the bare name, but a JSON string is fine too:
+            let username = serde_json::from_str(&text).unwrap_or_else(|_| text.trim().to_owned());
+            (username, Some(UtcDateTime::now()), false)
+        };
+
+        let info = WhoAmI {
+            username,
+            role: self.user_role()?,
+            endpoint: self.config.get_endpoint(),
+            credentials: self.config.get_credentials_source(),
+            validated,
+            cached,
+        };
+
+        if self.config.json_output() {
+            return self.print_output(serde_json::to_string_pretty(&info)?);
+        }
+
+        let role = info
+            .role
+            .map_or_else(|| "unknown".to_owned(), |role| role.to_string());
+        let validated = match (&info.validated, info.cached) {
+            (None, _) => "never (offline, nothing cached)".to_owned(),
+            (Some(_), false) => "just now".to_owned(),
+            (Some(time), true) => format!("{} (cached; offline)", time),
+        };
+
+        let mut table = render::Table::new("{:<}  {:<}");
+        for (key, value) in [
+            ("Username:", info.username.as_str()),
+            ("Role:", &role),
+            ("Endpoint:", info.endpoint),
+            ("Credentials:", &info.credentials),
+            ("Last validated:", &validated),
+        ] {
+            table.add_row(render::Row::new().with_cell(key).with_cell(value));
+        }
+
+        self.print_table(&table);
+        Ok(())
+    }
+}
@@ -1,10 +1,14 @@
 use std::{
+    collections::{BTreeMap, HashMap},
     env, fmt, fs,
-    io::{self, BufRead, Write},
+    io::{self, BufRead, IsTerminal, Write},
     path::{Path, PathBuf},
+    time::Duration,
 };
 
+use super::messages::{self, DisplayZone};
 use super::prelude::*;
+use super::retry::RetryPolicy;
 
 use serde_derive::Deserialize;
 use serde_yaml;
@@ -14,43 +18,317 @@ const API_ENDPOINT: &str = "https://cs211.cs.northwestern.edu";
 const AUTHFILE_VAR: &str = "GSC_AUTH_FILE";
 const AUTHFILE_NAME: &str = ".gscauth";
 
+const PROFILE_VAR: &str = "GSC_PROFILE";
+
 const DOTFILE_VAR: &str = "GSC_RC_FILE";
 const DOTFILE_NAME: &str = ".gscrc";
 
-#[derive(Debug)]
+const TRACE_FILE_VAR: &str = "GSC_TRACE";
+
+const API_KEY_VAR: &str = "GSC_API_KEY";
+const USER_VAR: &str = "GSC_USER";
+
+const JSON_VAR: &str = "GSC_JSON";
+
+/// Dotfile settings that may be overridden in the environment, by
+/// `$GSC_` followed by the key in upper case. The `user` and `profile`
+/// keys are left out because `$GSC_USER` and `$GSC_PROFILE` already
+/// mean something else.
+const ENV_SETTINGS: &[&str] = &[
+    "endpoint",
+    "verbosity",
+    "deadline_warning",
+    "retries",
+    "retry_delay",
+    "proxy",
+    "ca_cert",
+    "pool_max_idle",
+    "gzip",
+    "brotli",
+    "http2",
+    "timezone",
+    "default_hw",
+    "overwrite",
+    "unattended",
+    "color",
+    "width",
+    "jobs",
+    "pace",
+    "limit_rate",
+    "timeout",
+    "connect_timeout",
+    "update_check",
+    "reauth",
+    "audit_post",
+    "web_url",
+    "ignore",
+    "upload_guard",
+    "max_upload_size",
+];
+
+/// Operating-system and editor clutter that never belongs in a submission.
+const DEFAULT_IGNORE: &[&str] = &[
+    ".DS_Store",
+    "._*",
+    "__MACOSX",
+    "Thumbs.db",
+    "desktop.ini",
+    ".git",
+    ".svn",
+    "*~",
+    ".*.swp",
+];
+
+const STATE_FILE_VAR: &str = "GSC_STATE_FILE";
+const STATE_FILE_NAME: &str = ".gscstate";
+
+const HISTORY_FILE_VAR: &str = "GSC_HISTORY_FILE";
+const HISTORY_FILE_NAME: &str = "history.log";
+
+const AUDIT_FILE_VAR: &str = "GSC_AUDIT_FILE";
+const AUDIT_FILE_NAME: &str = "audit.log";
+
+const CACHE_DIR_VAR: &str = "GSC_CACHE_DIR";
+const CACHE_DIR_NAME: &str = ".gsccache";
+
+const DEADLINE_WARNING_MINUTES: i64 = 30;
+
+const DEFAULT_RETRIES: u32 = 3;
+const DEFAULT_RETRY_DELAY_MS: u64 = 500;
+
+const DEFAULT_JOBS: usize = 8;
+
+const DEFAULT_MAX_UPLOAD_SIZE: u64 = 5 << 20;
+
+#[derive(Clone, Debug)]
 pub struct Config {
     credentials_file: Option<PathBuf>,
     dotfile: Option<PathBuf>,
+    project_dotfile: Option<PathBuf>,
+    cache_dir: Option<PathBuf>,
+    state_file: Option<PathBuf>,
+    history_file: Option<PathBuf>,
+    audit_file: Option<PathBuf>,
     endpoint: String,
     on_behalf: Option<String>,
     overwrite: OverwritePolicy,
+    unattended: Unattended,
     verbosity: isize,
     json_output: bool,
+    porcelain_output: bool,
+    deadline_warning: i64,
+    retry_policy: RetryPolicy,
+    proxy: Option<String>,
+    ca_cert: Option<PathBuf>,
+    pool_max_idle: Option<usize>,
+    gzip: bool,
+    brotli: bool,
+    http2: bool,
+    time_zone: DisplayZone,
+    insecure: bool,
+    offline: bool,
+    force: bool,
+    strict: bool,
+    trace_file: Option<PathBuf>,
+    output_file: Option<PathBuf>,
+    api_key_file: Option<PathBuf>,
+    profile: Option<String>,
+    default_profile: Option<String>,
+    profiles: HashMap<String, Profile>,
+    endpoint_override: Option<String>,
+    server: Option<String>,
+    endpoints: HashMap<String, String>,
+    default_hw: Option<usize>,
+    color: ColorMode,
+    width: Option<usize>,
+    jobs: usize,
+    pace: Option<Duration>,
+    limit_rate: Option<u64>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    update_check: bool,
+    reauth: bool,
+    audit_post: bool,
+    web_url: Option<String>,
+    ignore: Vec<String>,
+    upload_guard: UploadGuard,
+    max_upload_size: Option<u64>,
+    pre_push_hook: Option<String>,
+    no_verify: bool,
+    upload_name_template: Option<NameTemplate>,
+    media_type: Option<String>,
+    aliases: HashMap<String, String>,
+    assume_yes: bool,
+    no_input: bool,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
 pub enum OverwritePolicy {
     Always,
     Never,
     Ask,
 }
 
-/// This is the format of the dotfile.
+/// How to answer questions when standard input isn’t a terminal.
+#[derive(Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Unattended {
+    Fail,
+    Never,
+    Always,
+}
+
+/// What `gsc cp` does about files that look wrong to upload, such as
+/// executables or huge files.
+#[derive(Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum UploadGuard {
+    Refuse,
+    Warn,
+    Off,
+}
+
+/// Whether to color warnings.
+#[derive(Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+/// A cap on transfer speed in bytes per second, written like `500K` or
+/// `1.5M`, where `K`, `M`, and `G` are powers of 1024. Zero means no cap.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RateLimit(pub u64);
+
+/// A number of bytes, written like `500K` or `1.5M`, where `K`, `M`, and
+/// `G` are powers of 1024.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ByteSize(pub u64);
+
+/// A media type to give uploaded files, from `cp --media-type`, like
+/// `text/x-racket`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MediaType(pub String);
+
+/// How to name uploaded files, from `cp --into`, like `jd123_{stem}{ext}`.
+/// In a file’s base name, `{stem}` is everything before the last dot, and
+/// `{ext}` is that dot and the rest (or nothing, if there’s no dot).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NameTemplate(Vec<TemplatePiece>);
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum TemplatePiece {
+    Text(String),
+    Stem,
+    Ext,
+}
+
+/// This is the format of the dotfile. Unknown keys are collected in
+/// `unknown` so that we can warn about them rather than fail.
 #[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
 pub struct Dotfile {
     #[serde(default)]
     pub endpoint: String,
     #[serde(default)]
     pub verbosity: Option<isize>,
+    #[serde(default)]
+    pub deadline_warning: Option<i64>,
+    #[serde(default)]
+    pub retries: Option<u32>,
+    #[serde(default)]
+    pub retry_delay: Option<u64>,
+    #[serde(default)]
+    pub proxy: Option<String>,
+    #[serde(default)]
+    pub ca_cert: Option<PathBuf>,
+    #[serde(default)]
+    pub pool_max_idle: Option<usize>,
+    #[serde(default)]
+    pub gzip: Option<bool>,
+    #[serde(default)]
+    pub brotli: Option<bool>,
+    #[serde(default)]
+    pub http2: Option<bool>,
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub profiles: Option<HashMap<String, Profile>>,
+    #[serde(default)]
+    pub endpoints: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub default_hw: Option<usize>,
+    #[serde(default)]
+    pub overwrite: Option<OverwritePolicy>,
+    #[serde(default)]
+    pub unattended: Option<Unattended>,
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub color: Option<ColorMode>,
+    #[serde(default)]
+    pub width: Option<usize>,
+    #[serde(default)]
+    pub jobs: Option<usize>,
+    #[serde(default)]
+    pub pace: Option<u64>,
+    #[serde(default)]
+    pub limit_rate: Option<String>,
+    #[serde(default)]
+    pub upload_guard: Option<UploadGuard>,
+    #[serde(default)]
+    pub max_upload_size: Option<String>,
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub connect_timeout: Option<u64>,
+    #[serde(default)]
+    pub update_check: Option<bool>,
+    #[serde(default)]
+    pub reauth: Option<bool>,
+    #[serde(default)]
+    pub audit_post: Option<bool>,
+    #[serde(default)]
+    pub web_url: Option<String>,
+    #[serde(default)]
+    pub ignore: Option<Vec<String>>,
+    #[serde(default)]
+    pub hooks: Option<Hooks>,
+    #[serde(default)]
+    pub aliases: Option<HashMap<String, String>>,
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, serde_yaml::Value>,
+}
+
+/// Commands to run at certain points, like git hooks.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    #[serde(default)]
+    pub pre_push: Option<String>,
+}
+
+/// A named set of account settings in the dotfile, selected with
+/// `--profile`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub credentials: Option<PathBuf>,
 }
 
 fn find_dotfile(env_var: &str, filename: &str) -> Option<PathBuf> {
     match env::var_os(env_var) {
         Some(file) => Some(PathBuf::from(file)),
-        None => match env::var_os("HOME") {
+        None => match home_dir() {
             Some(home) => {
-                let mut buf = PathBuf::from(home);
+                let mut buf = home;
                 buf.push(filename);
                 Some(buf)
             }
@@ -59,19 +337,327 @@ fn find_dotfile(env_var: &str, filename: &str) -> Option<PathBuf> {
     }
 }
 
+pub(crate) fn read_dotfile_at(dotfile_name: &Path) -> Result<Option<Dotfile>> {
+    let contents = match fs::read_to_string(dotfile_name) {
+        Ok(contents) => contents,
+        Err(error) => match error.kind() {
+            io::ErrorKind::NotFound => return Ok(None),
+            _ => {
+                let message = format!("Could not read dotfile: {}", dotfile_name.display());
+                return Err(Error::with_chain(error, message));
+            }
+        },
+    };
+
+    let parsed = serde_yaml::from_str(&contents)
+        .chain_err(|| format!("Could not parse dotfile: {}", dotfile_name.display()))?;
+
+    Ok(Some(parsed))
+}
+
+/// Interprets an environment variable’s `value` for dotfile setting
+/// `key`: as YAML if that fits, and otherwise as a string or a boolean.
+fn parse_env_setting(key: &str, value: &str) -> Result<serde_yaml::Value> {
+    use serde_yaml::{Mapping, Value};
+
+    let mut candidates = vec![Value::from(value)];
+    if let Ok(parsed) = serde_yaml::from_str(value) {
+        candidates.insert(0, parsed);
+    }
+    if let Some(b) = parse_env_bool(value) {
+        candidates.push(Value::from(b));
+    }
+
+    let mut error = None;
+
+    for candidate in candidates {
+        let mut probe = Mapping::new();
+        probe.insert(key.into(), candidate.clone());
+
+        match serde_yaml::from_value::<Dotfile>(Value::Mapping(probe)) {
+            Ok(_) => return Ok(candidate),
+            Err(e) => error = Some(e),
+        }
+    }
+
+    Err(error.unwrap().into())
+}
+
+fn parse_env_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+impl std::str::FromStr for RateLimit {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        parse_bytes(s, "transfer rate").map(RateLimit)
+    }
+}
+
+impl std::str::FromStr for ByteSize {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        parse_bytes(s, "size").map(ByteSize)
+    }
+}
+
+/// Parses a number of bytes with an optional `K`, `M`, or `G` suffix,
+/// calling it a `what` in the error if it isn’t one.
+fn parse_bytes(s: &str, what: &str) -> Result<u64> {
+    let trimmed = s.trim();
+    let (number, unit) = match trimmed.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (&trimmed[..i], c),
+        _ => (trimmed, 'b'),
+    };
+
+    let scale = match unit.to_ascii_lowercase() {
+        'b' => 1,
+        'k' => 1 << 10,
+        'm' => 1 << 20,
+        'g' => 1 << 30,
+        _ => Err(Error::syntax(what, s))?,
+    };
+
+    match number.trim().parse::<f64>() {
+        Ok(n) if n >= 0.0 && n.is_finite() => Ok((n * scale as f64) as u64),
+        _ => Err(Error::syntax(what, s))?,
+    }
+}
+
+impl std::str::FromStr for MediaType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let well_formed = match s.split_once('/') {
+            Some((kind, subtype)) => {
+                let token = |t: &str| {
+                    !t.is_empty()
+                        && t.chars()
+                            .all(|c| c.is_ascii_alphanumeric() || "!#$&^_.+-".contains(c))
+                };
+                token(kind) && token(subtype)
+            }
+            None => false,
+        };
+
+        if well_formed {
+            Ok(MediaType(s.to_ascii_lowercase()))
+        } else {
+            Err(Error::syntax("media type", s))
+        }
+    }
+}
+
+impl NameTemplate {
+    /// The name for a file whose base name is `name`.
+    pub fn apply(&self, name: &str) -> String {
+        let (stem, ext) = match name.rfind('.') {
+            Some(i) if i > 0 => name.split_at(i),
+            _ => (name, ""),
+        };
+
+        self.0
+            .iter()
+            .map(|piece| match piece {
+                TemplatePiece::Text(text) => text.as_str(),
+                TemplatePiece::Stem => stem,
+                TemplatePiece::Ext => ext,
+            })
+            .collect()
+    }
+}
+
+impl std::str::FromStr for NameTemplate {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut pieces = Vec::new();
+        let mut rest = s;
+
+        while let Some(open) = rest.find('{') {
+            if open > 0 {
+                pieces.push(TemplatePiece::Text(rest[..open].to_owned()));
+            }
+
+            let close = rest[open..]
+                .find('}')
+                .map(|i| open + i)
+                .ok_or_else(|| Error::syntax("name template", s))?;
+            pieces.push(match &rest[open + 1..close] {
+                "stem" => TemplatePiece::Stem,
+                "ext" => TemplatePiece::Ext,
+                _ => Err(Error::syntax("name template", s))?,
+            });
+
+            rest = &rest[close + 1..];
+        }
+
+        if !rest.is_empty() {
+            pieces.push(TemplatePiece::Text(rest.to_owned()));
+        }
+
+        Ok(NameTemplate(pieces))
+    }
+}
+
+/// A filename-safe name for the server at `endpoint`, such as
+/// `gsc-test.example.edu` or `localhost_8080`.
+fn endpoint_key(endpoint: &str) -> Result<String> {
+    let url = reqwest::Url::parse(endpoint).map_err(|_| Error::syntax("endpoint URL", endpoint))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| Error::syntax("endpoint URL", endpoint))?;
+
+    let mut key: String = host
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if let Some(port) = url.port() {
+        key += &format!("_{}", port);
+    }
+
+    Ok(key)
+}
+
+/// The user’s home directory: `$HOME` if set, or on Windows,
+/// `%USERPROFILE%` or else `%HOMEDRIVE%%HOMEPATH%`.
+fn home_dir() -> Option<PathBuf> {
+    if let Some(home) = env::var_os("HOME") {
+        return Some(PathBuf::from(home));
+    }
+
+    if cfg!(windows) {
+        if let Some(profile) = env::var_os("USERPROFILE") {
+            return Some(PathBuf::from(profile));
+        }
+
+        if let (Some(drive), Some(path)) = (env::var_os("HOMEDRIVE"), env::var_os("HOMEPATH")) {
+            let mut buf = PathBuf::from(drive);
+            buf.push(path);
+            return Some(buf);
+        }
+    }
+
+    None
+}
+
+/// Like `find_dotfile`, but defaults to `FILENAME` in the `gsc` directory
+/// under `$XDG_DATA_HOME` or `$HOME/.local/share`.
+fn find_data_file(env_var: &str, filename: &str) -> Option<PathBuf> {
+    if let Some(file) = env::var_os(env_var) {
+        return Some(PathBuf::from(file));
+    }
+
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".local").join("share")))?;
+
+    Some(data_home.join("gsc").join(filename))
+}
+
 impl Config {
     pub fn new() -> Self {
         let credentials_file = find_dotfile(AUTHFILE_VAR, AUTHFILE_NAME);
         let dotfile = find_dotfile(DOTFILE_VAR, DOTFILE_NAME);
+        let cache_dir = find_dotfile(CACHE_DIR_VAR, CACHE_DIR_NAME);
+        let state_file = find_dotfile(STATE_FILE_VAR, STATE_FILE_NAME);
+        let history_file = find_data_file(HISTORY_FILE_VAR, HISTORY_FILE_NAME);
+        let audit_file = find_data_file(AUDIT_FILE_VAR, AUDIT_FILE_NAME);
 
         Config {
             credentials_file,
             dotfile,
+            project_dotfile: None,
+            cache_dir,
+            state_file,
+            history_file,
+            audit_file,
             endpoint: API_ENDPOINT.to_owned(),
             on_behalf: None,
             overwrite: OverwritePolicy::Ask,
+            unattended: Unattended::Fail,
             verbosity: 1,
             json_output: false,
+            porcelain_output: false,
+            deadline_warning: DEADLINE_WARNING_MINUTES,
+            retry_policy: RetryPolicy {
+                retries: DEFAULT_RETRIES,
+                delay: Duration::from_millis(DEFAULT_RETRY_DELAY_MS),
+            },
+            proxy: None,
+            ca_cert: None,
+            pool_max_idle: None,
+            gzip: true,
+            brotli: true,
+            http2: false,
+            time_zone: DisplayZone::Local,
+            insecure: false,
+            offline: false,
+            force: false,
+            strict: false,
+            trace_file: env::var_os(TRACE_FILE_VAR).map(PathBuf::from),
+            output_file: None,
+            api_key_file: None,
+            profile: env::var(PROFILE_VAR).ok().filter(|name| !name.is_empty()),
+            default_profile: None,
+            profiles: HashMap::new(),
+            endpoint_override: None,
+            server: None,
+            endpoints: HashMap::new(),
+            default_hw: None,
+            color: ColorMode::Auto,
+            width: None,
+            jobs: DEFAULT_JOBS,
+            pace: None,
+            limit_rate: None,
+            timeout: None,
+            connect_timeout: None,
+            update_check: true,
+            reauth: true,
+            audit_post: false,
+            web_url: None,
+            ignore: Vec::new(),
+            upload_guard: UploadGuard::Refuse,
+            max_upload_size: Some(DEFAULT_MAX_UPLOAD_SIZE),
+            pre_push_hook: None,
+            aliases: HashMap::new(),
+            assume_yes: false,
+            no_input: false,
+            no_verify: false,
+            upload_name_template: None,
+            media_type: None,
+        }
+    }
+
+    /// A configuration for talking to the server at `endpoint` that keeps
+    /// its files in `dir`, leaving the user’s own files and settings alone.
+    #[cfg(feature = "test-support")]
+    pub fn isolated(endpoint: &str, dir: &Path) -> Self {
+        Config {
+            credentials_file: Some(dir.join(AUTHFILE_NAME)),
+            dotfile: None,
+            cache_dir: None,
+            state_file: Some(dir.join(STATE_FILE_NAME)),
+            history_file: Some(dir.join(HISTORY_FILE_NAME)),
+            audit_file: Some(dir.join(AUDIT_FILE_NAME)),
+            endpoint: endpoint.to_owned(),
+            profile: None,
+            update_check: false,
+            ..Config::new()
         }
     }
 
@@ -83,14 +669,132 @@ impl Config {
         self.on_behalf = Some(username);
     }
 
+    /// The overwrite policy, except that when there’s no terminal to ask,
+    /// `ask` gives way to the `unattended` setting.
     pub fn get_overwrite_policy(&self) -> OverwritePolicy {
-        self.overwrite
+        match (self.overwrite, self.get_unattended()) {
+            (OverwritePolicy::Ask, Some(Unattended::Never)) => OverwritePolicy::Never,
+            (OverwritePolicy::Ask, Some(Unattended::Always)) => OverwritePolicy::Always,
+            (policy, _) => policy,
+        }
     }
 
     pub fn set_overwrite_policy(&mut self, op: OverwritePolicy) {
         self.overwrite = op;
     }
 
+    /// How to answer questions without asking: `never` with `--no-input`,
+    /// or the `unattended` setting if standard input isn’t a terminal. `None`
+    /// means questions can be asked.
+    pub fn get_unattended(&self) -> Option<Unattended> {
+        if self.no_input {
+            Some(Unattended::Never)
+        } else if io::stdin().is_terminal() {
+            None
+        } else {
+            Some(self.unattended)
+        }
+    }
+
+    /// The error for a `question` that can’t be asked and has no answer
+    /// short of asking.
+    pub fn cannot_ask(&self, question: String) -> Error {
+        if self.no_input {
+            ErrorKind::NoInput(question)
+        } else {
+            ErrorKind::NoTerminal(question)
+        }
+    }
+
+    /// Asks a yes-or-no question, where just pressing return means no. If
+    /// there’s no terminal to ask, answers according to the `unattended`
+    /// setting.
+    pub fn confirm(&self, question: &str) -> Result<bool> {
+        match self.get_unattended() {
+            Some(Unattended::Never) => Ok(false),
+            Some(Unattended::Always) => Ok(true),
+            _ => confirm(question),
+        }
+    }
+
+    pub fn get_color(&self) -> ColorMode {
+        self.color
+    }
+
+    pub fn set_color(&mut self, color: ColorMode) {
+        self.color = color;
+    }
+
+    /// Whether to color output on stderr, taking `NO_COLOR` and whether
+    /// stderr is a terminal into account when the mode is `auto`.
+    pub fn use_color(&self) -> bool {
+        self.use_color_on(&io::stderr())
+    }
+
+    /// Whether to color output written to `stream`.
+    pub fn use_color_on(&self, stream: &impl IsTerminal) -> bool {
+        match self.color {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => env::var_os("NO_COLOR").is_none() && stream.is_terminal(),
+        }
+    }
+
+    /// How many columns output should fit in: the `width` setting, or else
+    /// the terminal’s width if standard output is a terminal.
+    pub fn get_width(&self) -> Option<usize> {
+        self.width.or_else(crate::util::stdout_width)
+    }
+
+    pub fn set_width(&mut self, width: usize) {
+        self.width = Some(width);
+    }
+
+    /// How many requests to send at once when fetching in parallel.
+    pub fn get_jobs(&self) -> usize {
+        self.jobs
+    }
+
+    pub fn set_jobs(&mut self, jobs: usize) {
+        self.jobs = jobs.max(1);
+    }
+
+    /// The least time to leave between starting requests when fetching
+    /// in parallel, if any.
+    pub fn get_pace(&self) -> Option<Duration> {
+        self.pace
+    }
+
+    pub fn set_pace(&mut self, pace: Duration) {
+        self.pace = Some(pace).filter(|pace| !pace.is_zero());
+    }
+
+    /// The most bytes per second to upload or download a file at, if any.
+    pub fn get_limit_rate(&self) -> Option<u64> {
+        self.limit_rate
+    }
+
+    pub fn set_limit_rate(&mut self, RateLimit(rate): RateLimit) {
+        self.limit_rate = Some(rate).filter(|&rate| rate > 0);
+    }
+
+    pub fn get_upload_guard(&self) -> UploadGuard {
+        self.upload_guard
+    }
+
+    pub fn set_upload_guard(&mut self, guard: UploadGuard) {
+        self.upload_guard = guard;
+    }
+
+    /// The largest file that `gsc cp` uploads without complaint, if any.
+    pub fn get_max_upload_size(&self) -> Option<u64> {
+        self.max_upload_size
+    }
+
+    pub fn set_max_upload_size(&mut self, ByteSize(size): ByteSize) {
+        self.max_upload_size = Some(size).filter(|&size| size > 0);
+    }
+
     pub fn get_verbosity(&self) -> isize {
         self.verbosity
     }
@@ -105,6 +809,18 @@ impl Config {
         vlog::set_verbosity_level(verbosity);
     }
 
+    pub fn get_time_zone(&self) -> DisplayZone {
+        self.time_zone
+    }
+
+    pub fn set_time_zone(&mut self, zone: DisplayZone) {
+        self.time_zone = zone;
+    }
+
+    pub fn activate_time_zone(&self) {
+        messages::set_display_zone(self.time_zone);
+    }
+
     pub fn set_verbosity(&mut self, verbosity: isize) {
         self.verbosity = verbosity;
     }
@@ -117,10 +833,341 @@ impl Config {
         self.json_output = json_output;
     }
 
+    pub fn porcelain_output(&self) -> bool {
+        self.porcelain_output
+    }
+
+    pub fn set_porcelain_output(&mut self, porcelain_output: bool) {
+        self.porcelain_output = porcelain_output;
+    }
+
+    /// How close to a deadline (in minutes) uploading should warn.
+    pub fn get_deadline_warning(&self) -> i64 {
+        self.deadline_warning
+    }
+
+    pub fn get_retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    pub fn set_retries(&mut self, retries: u32) {
+        self.retry_policy.retries = retries;
+    }
+
+    pub fn set_retry_delay(&mut self, delay: Duration) {
+        self.retry_policy.delay = delay;
+    }
+
+    pub fn get_proxy(&self) -> Option<&str> {
+        self.proxy.as_deref()
+    }
+
+    pub fn get_ca_cert(&self) -> Option<&Path> {
+        self.ca_cert.as_deref()
+    }
+
+    pub fn set_ca_cert(&mut self, path: PathBuf) {
+        self.ca_cert = Some(path);
+    }
+
+    /// The maximum number of idle keep-alive connections to hold open,
+    /// or `None` for no limit.
+    pub fn get_pool_max_idle(&self) -> Option<usize> {
+        self.pool_max_idle
+    }
+
+    pub fn set_pool_max_idle(&mut self, max: Option<usize>) {
+        self.pool_max_idle = max;
+    }
+
+    pub fn gzip(&self) -> bool {
+        self.gzip
+    }
+
+    pub fn set_gzip(&mut self, enable: bool) {
+        self.gzip = enable;
+    }
+
+    pub fn brotli(&self) -> bool {
+        self.brotli
+    }
+
+    pub fn set_brotli(&mut self, enable: bool) {
+        self.brotli = enable;
+    }
+
+    /// Whether to speak HTTP/2 without first negotiating it.
+    pub fn http2(&self) -> bool {
+        self.http2
+    }
+
+    pub fn set_http2(&mut self, enable: bool) {
+        self.http2 = enable;
+    }
+
+    pub fn insecure(&self) -> bool {
+        self.insecure
+    }
+
+    pub fn set_insecure(&mut self, insecure: bool) {
+        self.insecure = insecure;
+    }
+
+    pub fn offline(&self) -> bool {
+        self.offline
+    }
+
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    /// Whether to go ahead despite an incompatible server version, a
+    /// suspicious upload, or a self eval outside its window.
+    pub fn force(&self) -> bool {
+        self.force
+    }
+
+    pub fn set_force(&mut self, force: bool) {
+        self.force = force;
+    }
+
+    /// Whether to stop at the first failure rather than warning about it
+    /// and going on.
+    pub fn strict(&self) -> bool {
+        self.strict
+    }
+
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// The command to run before uploading, unless `--no-verify` was
+    /// given.
+    pub fn get_pre_push_hook(&self) -> Option<&str> {
+        if self.no_verify {
+            None
+        } else {
+            self.pre_push_hook.as_deref()
+        }
+    }
+
+    pub fn set_no_verify(&mut self, no_verify: bool) {
+        self.no_verify = no_verify;
+    }
+
+    /// How `cp` names the files it uploads to a whole homework, if not by
+    /// their base names.
+    pub fn get_upload_name_template(&self) -> Option<&NameTemplate> {
+        self.upload_name_template.as_ref()
+    }
+
+    pub fn set_upload_name_template(&mut self, template: NameTemplate) {
+        self.upload_name_template = Some(template);
+    }
+
+    /// The media type to give every uploaded file, rather than letting
+    /// the server decide.
+    pub fn get_media_type(&self) -> Option<&str> {
+        self.media_type.as_deref()
+    }
+
+    pub fn set_media_type(&mut self, media_type: MediaType) {
+        self.media_type = Some(media_type.0);
+    }
+
+    /// Whether to delete things without asking first.
+    pub fn assume_yes(&self) -> bool {
+        self.assume_yes
+    }
+
+    pub fn set_assume_yes(&mut self, assume_yes: bool) {
+        self.assume_yes = assume_yes;
+    }
+
+    /// Whether `--no-input` forbids asking any questions.
+    pub fn no_input(&self) -> bool {
+        self.no_input
+    }
+
+    pub fn set_no_input(&mut self, no_input: bool) {
+        self.no_input = no_input;
+    }
+
+    /// The command line that alias `name` stands for, if any.
+    pub fn get_alias(&self, name: &str) -> Option<&str> {
+        self.aliases.get(name).map(String::as_str)
+    }
+
+    pub fn get_alias_names(&self) -> impl Iterator<Item = &str> {
+        self.aliases.keys().map(String::as_str)
+    }
+
+    pub fn get_trace_file(&self) -> Option<&Path> {
+        self.trace_file.as_deref()
+    }
+
+    pub fn set_trace_file(&mut self, path: PathBuf) {
+        self.trace_file = Some(path);
+    }
+
+    /// The file given by `-o`, where command output goes instead of
+    /// standard output.
+    pub fn get_output_file(&self) -> Option<&Path> {
+        self.output_file.as_deref()
+    }
+
+    pub fn set_output_file(&mut self, path: PathBuf) {
+        self.output_file = Some(path);
+    }
+
+    /// Removes the `-o` file, for commands that use it themselves.
+    pub fn take_output_file(&mut self) -> Option<PathBuf> {
+        self.output_file.take()
+    }
+
+    /// The profile given by `--profile` or `$GSC_PROFILE`, or else the
+    /// dotfile’s default.
+    pub fn get_profile(&self) -> Option<&str> {
+        self.profile.as_deref().or(self.default_profile.as_deref())
+    }
+
+    pub fn set_profile(&mut self, name: String) {
+        self.profile = Some(name);
+    }
+
+    /// Applies the selected profile, if any: its endpoint, its credentials
+    /// file (by default `$HOME/.gscauth-NAME`), and a cache of its own.
+    pub fn activate_profile(&mut self) -> Result<()> {
+        let name = match self.get_profile() {
+            Some(name) => name.to_owned(),
+            None => return Ok(()),
+        };
+
+        let profile = self
+            .profiles
+            .get(&name)
+            .cloned()
+            .ok_or_else(|| ErrorKind::UnknownProfile(name.clone()))?;
+
+        if let Some(endpoint) = profile.endpoint {
+            self.endpoint = endpoint;
+        }
+
+        self.credentials_file = profile.credentials.or_else(|| {
+            home_dir().map(|mut buf| {
+                buf.push(format!("{}-{}", AUTHFILE_NAME, name));
+                buf
+            })
+        });
+
+        self.cache_dir = self.cache_dir.take().map(|dir| dir.join(&name));
+
+        Ok(())
+    }
+
+    pub fn set_endpoint(&mut self, url: String) {
+        self.endpoint_override = Some(url);
+    }
+
+    pub fn set_server(&mut self, name: String) {
+        self.server = Some(name);
+    }
+
+    /// Applies `--endpoint` or `--server`, if given. The credentials and
+    /// cache belong to the usual endpoint, so a different endpoint gets
+    /// its own, keyed by host, so that keys don’t leak across servers.
+    pub fn activate_endpoint(&mut self) -> Result<()> {
+        let endpoint = match (self.endpoint_override.take(), &self.server) {
+            (Some(url), _) => url,
+            (None, Some(name)) => self
+                .endpoints
+                .get(name)
+                .cloned()
+                .ok_or_else(|| ErrorKind::UnknownServer(name.clone()))?,
+            (None, None) => return Ok(()),
+        };
+
+        if endpoint.trim_end_matches('/') == self.endpoint.trim_end_matches('/') {
+            return Ok(());
+        }
+
+        let key = endpoint_key(&endpoint)?;
+
+        self.credentials_file = self.credentials_file.take().map(|path| {
+            let mut buf = path.into_os_string();
+            buf.push("@");
+            buf.push(&key);
+            buf.into()
+        });
+
+        self.cache_dir = self
+            .cache_dir
+            .take()
+            .map(|dir| dir.join(format!("@{}", key)));
+        self.endpoint = endpoint;
+
+        Ok(())
+    }
+
+    pub fn set_api_key_file(&mut self, path: PathBuf) {
+        self.api_key_file = Some(path);
+    }
+
+    /// The username and API key to use in place of the credentials file,
+    /// if an API key was given with `--api-key-file` or `$GSC_API_KEY`.
+    /// Either way, the username comes from `$GSC_USER`.
+    pub fn get_api_key(&self) -> Result<Option<(String, String)>> {
+        let api_key = if let Some(path) = &self.api_key_file {
+            fs::read_to_string(path)
+                .chain_err(|| format!("Could not read API key file: {}", path.display()))?
+        } else if let Some(api_key) = env::var_os(API_KEY_VAR) {
+            api_key.to_string_lossy().into_owned()
+        } else {
+            return Ok(None);
+        };
+
+        match env::var(USER_VAR) {
+            Ok(username) if !username.is_empty() => Ok(Some((username.to_lowercase(), api_key))),
+            _ => Err(ErrorKind::ApiKeyWithoutUser),
+        }
+    }
+
+    /// Where the credentials come from, for showing the user: the API key
+    /// file, `$GSC_API_KEY`, or the credentials file.
+    pub fn get_credentials_source(&self) -> String {
+        if let Some(path) = &self.api_key_file {
+            path.display().to_string()
+        } else if env::var_os(API_KEY_VAR).is_some() {
+            format!("${}", API_KEY_VAR)
+        } else if let Some(path) = &self.credentials_file {
+            path.display().to_string()
+        } else {
+            "none".to_owned()
+        }
+    }
+
     pub fn get_endpoint(&self) -> &str {
         &self.endpoint
     }
 
+    /// The base URL of the web UI, by default `/gsc` on the endpoint’s
+    /// server.
+    pub fn get_web_url(&self) -> String {
+        match &self.web_url {
+            Some(url) => url.clone(),
+            None => format!("{}/gsc", self.endpoint.trim_end_matches('/')),
+        }
+    }
+
+    /// Patterns for files to leave out when uploading an archive, in
+    /// addition to `DEFAULT_IGNORE`.
+    pub fn get_ignore_patterns(&self) -> impl Iterator<Item = &str> {
+        DEFAULT_IGNORE
+            .iter()
+            .copied()
+            .chain(self.ignore.iter().map(String::as_str))
+    }
+
     pub fn get_credentials_file(&self) -> Result<&Path> {
         match &self.credentials_file {
             Some(filename) => Ok(&filename),
@@ -128,50 +1175,381 @@ impl Config {
         }
     }
 
+    pub fn get_cache_dir(&self) -> Option<&Path> {
+        self.cache_dir.as_deref()
+    }
+
+    pub fn set_cache_dir(&mut self, dir: PathBuf) {
+        self.cache_dir = Some(dir);
+    }
+
+    /// Where to remember things between runs, such as when we last
+    /// checked for a newer release.
+    pub fn get_state_file(&self) -> Option<&Path> {
+        self.state_file.as_deref()
+    }
+
+    /// The local journal of changes made to submissions.
+    pub fn get_history_file(&self) -> Option<&Path> {
+        self.history_file.as_deref()
+    }
+
+    /// The local log of changes made with `gsc-admin`.
+    pub fn get_audit_file(&self) -> Option<&Path> {
+        self.audit_file.as_deref()
+    }
+
+    /// Whether to send each entry in the audit log to the server, too.
+    pub fn audit_post(&self) -> bool {
+        self.audit_post
+    }
+
+    pub fn set_audit_post(&mut self, enable: bool) {
+        self.audit_post = enable;
+    }
+
+    /// Whether to check now and then for a newer release.
+    pub fn update_check(&self) -> bool {
+        self.update_check
+    }
+
+    /// Whether to offer, at a terminal, to enter a new API key when the
+    /// server rejects the saved one.
+    pub fn reauth(&self) -> bool {
+        self.reauth
+    }
+
     pub fn get_dotfile(&self) -> Option<&Path> {
         self.dotfile.as_ref().map(PathBuf::as_path)
     }
 
-    pub fn read_dotfile(&self) -> Result<Option<Dotfile>> {
-        let dotfile_name = match self.get_dotfile() {
-            None => return Ok(None),
-            Some(filename) => filename,
-        };
+    /// The project dotfile that was loaded, if any.
+    pub fn get_project_dotfile(&self) -> Option<&Path> {
+        self.project_dotfile.as_deref()
+    }
 
-        let contents = match fs::read_to_string(dotfile_name) {
-            Ok(contents) => contents,
-            Err(error) => match error.kind() {
-                io::ErrorKind::NotFound => return Ok(None),
-                _ => {
-                    let message = format!("Could not read dotfile: {}", dotfile_name.display());
-                    return Err(Error::with_chain(error, message));
-                }
-            },
-        };
+    /// The homework for commands like `ls` to use when none is given.
+    pub fn get_default_hw(&self) -> Option<usize> {
+        self.default_hw
+    }
+
+    /// Looks for a project `.gscrc` in the current directory and its
+    /// parents, stopping at the global dotfile or the home directory’s.
+    pub fn find_project_dotfile(&self) -> Option<PathBuf> {
+        let stops: Vec<PathBuf> = self
+            .get_dotfile()
+            .map(Path::to_owned)
+            .into_iter()
+            .chain(home_dir().map(|home| home.join(DOTFILE_NAME)))
+            .filter_map(|path| fs::canonicalize(path).ok())
+            .collect();
 
-        let parsed = serde_yaml::from_str(&contents)
-            .chain_err(|| format!("Could not parse dotfile: {}", dotfile_name.display()))?;
+        let cwd = env::current_dir().ok()?;
+
+        for dir in cwd.ancestors() {
+            let candidate = dir.join(DOTFILE_NAME);
+            if !candidate.is_file() {
+                continue;
+            }
 
-        Ok(Some(parsed))
+            match fs::canonicalize(&candidate) {
+                Ok(path) if stops.contains(&path) => return None,
+                Ok(path) => return Some(path),
+                Err(_) => continue,
+            }
+        }
+
+        None
+    }
+
+    pub fn read_dotfile(&self) -> Result<Option<Dotfile>> {
+        match self.get_dotfile() {
+            None => Ok(None),
+            Some(filename) => read_dotfile_at(filename),
+        }
     }
 
+    /// Loads the global dotfile, the project dotfile, if any, and then
+    /// the environment, each taking precedence over the one before.
     pub fn load_dotfile(&mut self) -> Result<()> {
-        if let Some(Dotfile {
+        if let Some(dotfile) = self.read_dotfile()? {
+            let path = self.dotfile.clone().unwrap_or_default();
+            self.apply_dotfile(dotfile, &path)?;
+        }
+
+        if let Some(path) = self.find_project_dotfile() {
+            if let Some(dotfile) = read_dotfile_at(&path)? {
+                self.apply_dotfile(dotfile, &path)?;
+                self.project_dotfile = Some(path);
+            }
+        }
+
+        self.apply_env()
+    }
+
+    /// Applies settings from `ENV_SETTINGS` and `$GSC_JSON`. Relative
+    /// paths are taken relative to the current directory.
+    fn apply_env(&mut self) -> Result<()> {
+        let mut mapping = serde_yaml::Mapping::new();
+
+        for &key in ENV_SETTINGS {
+            let var = format!("GSC_{}", key.to_uppercase());
+            if let Some(value) = env::var(&var).ok().filter(|value| !value.is_empty()) {
+                let parsed = parse_env_setting(key, &value)
+                    .chain_err(|| format!("Invalid value for ${}: ‘{}’", var, value))?;
+                mapping.insert(key.into(), parsed);
+            }
+        }
+
+        let dotfile = serde_yaml::from_value(serde_yaml::Value::Mapping(mapping))?;
+        self.apply_dotfile(dotfile, Path::new(""))?;
+
+        if let Some(value) = env::var(JSON_VAR).ok().filter(|value| !value.is_empty()) {
+            self.json_output = parse_env_bool(&value)
+                .ok_or_else(|| format!("Invalid value for ${}: ‘{}’", JSON_VAR, value))?;
+        }
+
+        Ok(())
+    }
+
+    /// Applies the settings from the dotfile at `path`. Relative paths in
+    /// the dotfile are taken relative to its directory.
+    fn apply_dotfile(&mut self, dotfile: Dotfile, path: &Path) -> Result<()> {
+        let dir = path.parent();
+        let resolve = |path: PathBuf| match dir {
+            Some(dir) => dir.join(path),
+            None => path,
+        };
+
+        let Dotfile {
             endpoint,
             verbosity,
-        }) = self.read_dotfile()?
-        {
-            if !endpoint.is_empty() {
-                self.endpoint = endpoint;
-            }
+            deadline_warning,
+            retries,
+            retry_delay,
+            proxy,
+            ca_cert,
+            pool_max_idle,
+            gzip,
+            brotli,
+            http2,
+            timezone,
+            profile,
+            profiles,
+            endpoints,
+            default_hw,
+            overwrite,
+            unattended,
+            user,
+            color,
+            width,
+            jobs,
+            pace,
+            limit_rate,
+            upload_guard,
+            max_upload_size,
+            timeout,
+            connect_timeout,
+            update_check,
+            reauth,
+            audit_post,
+            web_url,
+            ignore,
+            hooks,
+            aliases,
+            unknown,
+        } = dotfile;
+
+        for key in unknown.keys() {
+            ve1!(
+                "Warning: ignoring unknown setting ‘{}’ in {}",
+                key,
+                path.display()
+            );
+        }
+
+        if !endpoint.is_empty() {
+            self.endpoint = endpoint;
+        }
+
+        if let Some(i) = verbosity {
+            self.verbosity = i;
+        }
+
+        if let Some(minutes) = deadline_warning {
+            self.deadline_warning = minutes;
+        }
+
+        if let Some(n) = retries {
+            self.retry_policy.retries = n;
+        }
 
-            if let Some(i) = verbosity {
-                self.verbosity = i;
+        if let Some(ms) = retry_delay {
+            self.retry_policy.delay = Duration::from_millis(ms);
+        }
+
+        if proxy.is_some() {
+            self.proxy = proxy;
+        }
+
+        if let Some(path) = ca_cert {
+            self.ca_cert = Some(resolve(path));
+        }
+
+        if pool_max_idle.is_some() {
+            self.pool_max_idle = pool_max_idle;
+        }
+
+        if let Some(b) = gzip {
+            self.gzip = b;
+        }
+
+        if let Some(b) = brotli {
+            self.brotli = b;
+        }
+
+        if let Some(b) = http2 {
+            self.http2 = b;
+        }
+
+        if let Some(zone) = timezone {
+            self.time_zone = zone.parse()?;
+        }
+
+        if profile.is_some() {
+            self.default_profile = profile;
+        }
+
+        for (name, mut settings) in profiles.unwrap_or_default() {
+            settings.credentials = settings.credentials.map(resolve);
+            self.profiles.insert(name, settings);
+        }
+
+        self.endpoints.extend(endpoints.unwrap_or_default());
+
+        if default_hw.is_some() {
+            self.default_hw = default_hw;
+        }
+
+        if let Some(policy) = overwrite {
+            self.overwrite = policy;
+        }
+
+        if let Some(unattended) = unattended {
+            self.unattended = unattended;
+        }
+
+        if user.is_some() {
+            self.on_behalf = user;
+        }
+
+        if let Some(mode) = color {
+            self.color = mode;
+        }
+
+        if width.is_some() {
+            self.width = width;
+        }
+
+        if let Some(n) = jobs {
+            self.jobs = n.max(1);
+        }
+
+        if let Some(ms) = pace {
+            self.set_pace(Duration::from_millis(ms));
+        }
+
+        if let Some(rate) = limit_rate {
+            self.set_limit_rate(rate.parse()?);
+        }
+
+        if let Some(guard) = upload_guard {
+            self.upload_guard = guard;
+        }
+
+        if let Some(size) = max_upload_size {
+            self.set_max_upload_size(size.parse()?);
+        }
+
+        if let Some(secs) = timeout {
+            self.timeout = Some(Duration::from_secs(secs));
+        }
+
+        if let Some(secs) = connect_timeout {
+            self.connect_timeout = Some(Duration::from_secs(secs));
+        }
+
+        if let Some(b) = update_check {
+            self.update_check = b;
+        }
+
+        if let Some(b) = reauth {
+            self.reauth = b;
+        }
+
+        if let Some(b) = audit_post {
+            self.audit_post = b;
+        }
+
+        if web_url.is_some() {
+            self.web_url = web_url;
+        }
+
+        self.ignore.extend(ignore.unwrap_or_default());
+
+        if let Some(Hooks { pre_push }) = hooks {
+            if pre_push.is_some() {
+                self.pre_push_hook = pre_push;
             }
         }
 
+        self.aliases.extend(aliases.unwrap_or_default());
+
         Ok(())
     }
+
+    /// Builds an HTTP client according to the network settings. Unless a
+    /// proxy is configured explicitly, the standard `HTTP_PROXY`,
+    /// `HTTPS_PROXY`, and `NO_PROXY` environment variables are honored.
+    pub fn build_http_client(&self) -> Result<reqwest::blocking::Client> {
+        let mut builder = reqwest::blocking::Client::builder()
+            .gzip(self.gzip)
+            .brotli(self.brotli);
+
+        if let Some(max) = self.pool_max_idle {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+
+        if self.http2 {
+            builder = builder.http2_prior_knowledge();
+        }
+
+        // A timeout of 0 means to wait forever:
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(Some(timeout).filter(|t| !t.is_zero()));
+        }
+
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+
+        if let Some(proxy) = self.get_proxy() {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+
+        if let Some(path) = self.get_ca_cert() {
+            let pem = fs::read(path)
+                .chain_err(|| format!("Could not read CA certificate: {}", path.display()))?;
+            builder = builder.add_root_certificate(reqwest::Certificate::from_pem(&pem)?);
+        }
+
+        if self.insecure {
+            ve1!("Warning: not verifying the server’s TLS certificate (--insecure).");
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        Ok(builder.build()?)
+    }
 }
 
 impl OverwritePolicy {
@@ -185,45 +1563,91 @@ impl OverwritePolicy {
             Always => Ok(true),
             Never => Err(ErrorKind::DestinationFileExists(dst_thunk().to_string()))?,
             Ask => {
-                let stdin = io::stdin();
-                let mut input = stdin.lock();
-                let mut buf = String::with_capacity(2);
                 let dst = dst_thunk();
+                let prompt = format!("File ‘{}’ already exists.\nOverwrite [Y/N/A/C]? ", dst);
+                let options = [
+                    "[Y]es, overwrite just this file",
+                    "[N]o, do not overwrite this file",
+                    "overwrite [A]ll files",
+                    "[C]ancel operation and exit",
+                ];
 
-                loop {
-                    print!("File ‘{}’ already exists.\nOverwrite [Y/N/A/C]? ", dst);
-                    io::stdout().flush()?;
-
-                    input.read_line(&mut buf)?;
+                let answer = prompt_letter(&prompt, &options, |c| match c {
+                    'y' | 'n' | 'a' | 'c' => Some(c),
+                    _ => None,
+                })?;
 
-                    if buf.is_empty() {
-                        std::process::exit(1);
+                match answer {
+                    'y' => Ok(true),
+                    'n' => {
+                        v2!("Skipping ‘{}’.", dst);
+                        Ok(false)
                     }
-
-                    match buf.chars().flat_map(char::to_lowercase).next() {
-                        Some('y') => return Ok(true),
-                        Some('n') => {
-                            v2!("Skipping ‘{}’.", dst);
-                            return Ok(false);
-                        }
-                        Some('a') => {
-                            *self = Always;
-                            return Ok(true);
-                        }
-                        Some('c') => std::process::exit(0),
-                        _ => {
-                            ve1!("");
-                            ve1!("Did not understand response. Options are:");
-                            ve1!("   [Y]es, overwrite just this file");
-                            ve1!("   [N]o, do not overwrite this file");
-                            ve1!("   overwrite [A]ll files");
-                            ve1!("   [C]ancel operation and exit");
-                            ve1!("");
-                            buf.clear();
-                        }
+                    'a' => {
+                        *self = Always;
+                        Ok(true)
                     }
+                    _ => std::process::exit(0),
                 }
             }
         }
     }
 }
+
+/// Asks a yes-or-no question, where just pressing return means no.
+fn confirm(question: &str) -> Result<bool> {
+    let prompt = format!("{} [y/N] ", question);
+    let options = ["[Y]es", "[N]o (the default)"];
+
+    prompt_letter(&prompt, &options, |c| match c {
+        'y' => Some(true),
+        'n' | '\n' | '\r' => Some(false),
+        _ => None,
+    })
+}
+
+/// Prompts until the first letter of the user’s response is accepted by
+/// `interpret`, listing `options` after each response it doesn’t accept.
+/// Exits if standard input is closed, and fails if it isn’t a terminal.
+pub fn prompt_letter<T, F>(prompt: &str, options: &[&str], mut interpret: F) -> Result<T>
+where
+    F: FnMut(char) -> Option<T>,
+{
+    let stdin = io::stdin();
+
+    if !stdin.is_terminal() {
+        let question = prompt.lines().next().unwrap_or_default().trim_end();
+        Err(ErrorKind::NoTerminal(question.to_owned()))?;
+    }
+
+    let mut input = stdin.lock();
+    let mut buf = String::with_capacity(2);
+
+    loop {
+        print!("{}", prompt);
+        io::stdout().flush()?;
+
+        buf.clear();
+        input.read_line(&mut buf)?;
+
+        if buf.is_empty() {
+            std::process::exit(1);
+        }
+
+        if let Some(result) = buf
+            .chars()
+            .flat_map(char::to_lowercase)
+            .next()
+            .and_then(&mut interpret)
+        {
+            return Ok(result);
+        }
+
+        ve1!("");
+        ve1!("Did not understand response. Options are:");
+        for option in options {
+            ve1!("   {}", option);
+        }
+        ve1!("");
+    }
+}
@@ -1,7 +1,9 @@
 #[cfg(feature = "file_locking")]
 use fs2::FileExt;
 
+use chrono::{DateTime, Utc};
 use reqwest::header::HeaderValue;
+use vlog::*;
 
 use std::default::Default;
 use std::fs;
@@ -12,11 +14,17 @@ use super::errors::ErrorKind;
 
 type Result<T> = super::errors::Result<T>;
 
+/// The cookie that stands for the user, and, on the lines after it in
+/// the credentials file, when the key was saved and when the server last
+/// accepted it. Older versions read only the first line, so they don’t
+/// mind the others.
 #[derive(Clone, Debug, Default)]
 pub struct Credentials {
     username_:     String,
     cookie_key_:   String,
     cookie_value_: String,
+    saved_:        Option<DateTime<Utc>>,
+    validated_:    Option<DateTime<Utc>>,
 }
 
 impl Credentials {
@@ -28,6 +36,8 @@ impl Credentials {
             username_:     username.into(),
             cookie_key_:   cookie_key.into(),
             cookie_value_: cookie_value.into(),
+            saved_:        None,
+            validated_:    None,
         }
     }
 
@@ -36,35 +46,89 @@ impl Credentials {
             .map_err(|_| ErrorKind::LoginPlease)?;
 
         #[cfg(feature = "file_locking")]
-        file.lock_shared()?;
+        FileExt::lock_shared(&file)?;
+
+        warn_if_readable_by_others(path, &file);
 
         let mut buf_reader = BufReader::new(file);
         let mut buf = String::new();
         let _ = buf_reader.read_line(&mut buf);
 
+        // Tolerate CRLF line endings and the byte-order mark that some
+        // Windows editors add:
+        let line = buf.trim_start_matches('\u{feff}').trim();
         let (username, key, value) =
-            parse_cookie_file(buf.trim_end()).ok_or(ErrorKind::LoginPlease)?;
+            parse_cookie_file(line).ok_or(ErrorKind::LoginPlease)?;
+
+        let mut creds = Self::new(username, key, value);
+
+        for line in buf_reader.lines().map_while(|line| line.ok()) {
+            let (name, time) = match line.trim().split_once(' ') {
+                Some(pair) => pair,
+                None => continue,
+            };
+            let time = match DateTime::parse_from_rfc3339(time) {
+                Ok(time) => Some(time.with_timezone(&Utc)),
+                Err(_) => continue,
+            };
+            match name {
+                "saved"     => creds.saved_ = time,
+                "validated" => creds.validated_ = time,
+                _           => {}
+            }
+        }
 
-        Ok(Self {
-            username_:     username.to_owned(),
-            cookie_key_:   key.to_owned(),
-            cookie_value_: value.to_owned(),
-        })
+        Ok(creds)
     }
 
+    /// Writes the credentials to `filename` by way of a temporary file
+    /// that then replaces it, so that anyone reading it at the same time
+    /// sees either the old file or the new one, never a partial one.
     pub fn write(&self, filename: &Path) -> Result<()> {
-        let file = fs::OpenOptions::new()
-            .create(true)
-            .truncate(true)
-            .write(true)
-            .open(filename)?;
+        let mut temp = filename.as_os_str().to_owned();
+        temp.push(format!(".{}.tmp", std::process::id()));
+        let temp = Path::new(&temp);
 
-        #[cfg(feature = "file_locking")]
-        file.lock_exclusive()?;
+        let result = self.write_new(temp)
+            .and_then(|()| Ok(fs::rename(temp, filename)?));
+
+        if result.is_err() {
+            let _ = fs::remove_file(temp);
+        }
+
+        result
+    }
+
+    fn write_new(&self, filename: &Path) -> Result<()> {
+        let mut options = fs::OpenOptions::new();
+        options.create(true).truncate(true).write(true);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let file = options.open(filename)?;
+
+        // The mode above only applies to new files, and a crashed run may
+        // have left this one behind:
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
 
         let mut w = BufWriter::new(file);
         writeln!(w, "{}:{}={}", self.username_, self.cookie_key_, self.cookie_value_)?;
+        if let Some(saved) = self.saved_ {
+            writeln!(w, "saved {}", saved.to_rfc3339())?;
+        }
+        if let Some(validated) = self.validated_ {
+            writeln!(w, "validated {}", validated.to_rfc3339())?;
+        }
 
+        w.into_inner().map_err(|e| e.into_error())?.sync_all()?;
         Ok(())
     }
 
@@ -72,12 +136,61 @@ impl Credentials {
         &self.username_
     }
 
+    /// When the key was saved, by `gsc auth` or when the server first
+    /// accepted it after an upgrade from a version that didn’t say.
+    pub fn saved(&self) -> Option<DateTime<Utc>> {
+        self.saved_
+    }
+
+    /// When the server last accepted the key.
+    pub fn validated(&self) -> Option<DateTime<Utc>> {
+        self.validated_
+    }
+
+    /// Records that the server accepted the key just now.
+    pub fn mark_validated(&mut self) {
+        let now = Utc::now();
+        self.validated_ = Some(now);
+        self.saved_.get_or_insert(now);
+    }
+
+    /// Whether `self` and `other` are the same login, whatever their
+    /// timestamps.
+    pub fn same_key(&self, other: &Credentials) -> bool {
+        self.username_ == other.username_
+            && self.cookie_key_ == other.cookie_key_
+            && self.cookie_value_ == other.cookie_value_
+    }
+
     pub fn to_header(&self) -> Result<HeaderValue> {
         let s = format!("{}={}", self.cookie_key_, self.cookie_value_);
         Ok(HeaderValue::from_str(&s)?)
     }
 }
 
+/// Warns, like ssh does for private keys, when other users can read the
+/// credentials file.
+#[cfg(unix)]
+fn warn_if_readable_by_others(path: &Path, file: &fs::File) {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Ok(metadata) = file.metadata() {
+        let mode = metadata.permissions().mode();
+        if mode & 0o077 != 0 {
+            ve1!(
+                "Warning: credentials file ‘{}’ is accessible by other users \
+                 (mode {:o}); fix with:\n  chmod 600 {}",
+                path.display(),
+                mode & 0o777,
+                path.display()
+            );
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn warn_if_readable_by_others(_path: &Path, _file: &fs::File) {}
+
 fn parse_cookie_file(contents: &str) -> Option<(&str, &str, &str)> {
     let colon = contents.find(':')?;
     let equals = contents.find('=')?;
@@ -0,0 +1,100 @@
+//! Date specifications for `admin extend`.
+//!
+//! Besides absolute dates (anything `UtcDateTime::from_str` accepts), a
+//! spec may be an offset such as `+3d`, `+48h`, or `-1d12h`, or a weekday
+//! such as `next friday 23:59`. Those two forms are resolved against a
+//! base time, normally the existing due date.
+
+use crate::messages::UtcDateTime;
+use crate::prelude::*;
+
+use chrono::{Datelike, Duration, NaiveTime, Weekday};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+#[derive(Clone, Debug)]
+pub enum DateSpec {
+    Absolute(UtcDateTime),
+    Offset(Duration),
+    Weekday(Weekday, NaiveTime),
+}
+
+impl DateSpec {
+    /// Whether `resolve` actually uses its base time.
+    pub fn is_relative(&self) -> bool {
+        !matches!(self, DateSpec::Absolute(_))
+    }
+
+    pub fn resolve(&self, base: &UtcDateTime) -> UtcDateTime {
+        match self {
+            DateSpec::Absolute(date) => date.clone(),
+            DateSpec::Offset(offset) => (base.clone().into_local() + *offset).into(),
+            DateSpec::Weekday(weekday, time) => {
+                let mut date = base.clone().into_local().naive_local().date().succ();
+                while date.weekday() != *weekday {
+                    date = date.succ();
+                }
+                UtcDateTime::from_naive_local(date.and_time(*time))
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for DateSpec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        lazy_static! {
+            static ref OFFSET: Regex = Regex::new(r"^([+-])\s*((?:\d+\s*[wdhm]\s*)+)$").unwrap();
+            static ref OFFSET_PART: Regex = Regex::new(r"(\d+)\s*([wdhm])").unwrap();
+            static ref WEEKDAY: Regex =
+                Regex::new(r"^(?i:next\s+)?([[:alpha:]]+)(?:\s+(\d{1,2}:\d{2}(?::\d{2})?))?$")
+                    .unwrap();
+        }
+
+        let s = s.trim();
+        let syntax_error = || Error::syntax("date specification", s);
+
+        if let Some(captures) = OFFSET.captures(s) {
+            let mut offset = Duration::zero();
+
+            for part in OFFSET_PART.captures_iter(&captures[2]) {
+                let n: i64 = part[1].parse().map_err(|_| syntax_error())?;
+                offset = offset
+                    + match &part[2] {
+                        "w" => Duration::weeks(n),
+                        "d" => Duration::days(n),
+                        "h" => Duration::hours(n),
+                        _ => Duration::minutes(n),
+                    };
+            }
+
+            if &captures[1] == "-" {
+                offset = -offset;
+            }
+
+            return Ok(DateSpec::Offset(offset));
+        }
+
+        if let Some(captures) = WEEKDAY.captures(s) {
+            if let Ok(weekday) = captures[1].parse::<Weekday>() {
+                let time = match captures.get(2) {
+                    Some(time) => parse_time(time.as_str()).ok_or_else(syntax_error)?,
+                    None => NaiveTime::from_hms(23, 59, 59),
+                };
+
+                return Ok(DateSpec::Weekday(weekday, time));
+            }
+        }
+
+        s.parse()
+            .map(DateSpec::Absolute)
+            .map_err(|_| syntax_error())
+    }
+}
+
+fn parse_time(s: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(s, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M"))
+        .ok()
+}
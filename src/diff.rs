@@ -0,0 +1,64 @@
+//! Line-based diffs in unified format.
+
+use similar::{ChangeTag, TextDiff};
+
+const CONTEXT: usize = 3;
+
+/// Compares `old` and `new` line by line, returning a unified diff with
+/// headers labeled `old_label` and `new_label`, or `None` if they’re the
+/// same. Given `color`, the diff is colored with ANSI escapes.
+pub fn unified(
+    old_label: &str,
+    new_label: &str,
+    old: &str,
+    new: &str,
+    color: bool,
+) -> Option<String> {
+    if old == new {
+        return None;
+    }
+
+    let paint = |code: &str, text: &str| {
+        if color {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text.to_owned()
+        }
+    };
+
+    let mut out = String::new();
+    out += &paint("1", &format!("--- {}", old_label));
+    out += "\n";
+    out += &paint("1", &format!("+++ {}", new_label));
+    out += "\n";
+
+    let diff = TextDiff::from_lines(old, new);
+
+    for hunk in diff.unified_diff().context_radius(CONTEXT).iter_hunks() {
+        out += &paint("36", &hunk.header().to_string());
+        out += "\n";
+
+        for change in hunk.iter_changes() {
+            let (prefix, code) = match change.tag() {
+                ChangeTag::Equal => (" ", ""),
+                ChangeTag::Delete => ("-", "31"),
+                ChangeTag::Insert => ("+", "32"),
+            };
+
+            let line = change.value();
+            let text = format!("{}{}", prefix, line.strip_suffix('\n').unwrap_or(line));
+            out += &if code.is_empty() {
+                text
+            } else {
+                paint(code, &text)
+            };
+            out += "\n";
+
+            if change.missing_newline() {
+                out += "\\ No newline at end of file\n";
+            }
+        }
+    }
+
+    Some(out)
+}
@@ -1,11 +1,13 @@
+use super::messages::{EvalType, UserRole, UtcDateTime};
+use super::suggest;
 use super::RemotePattern;
 
-use error_chain::*;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde_derive::{Deserialize, Serialize};
 
 use std::borrow::Borrow;
+use std::error::Error as StdError;
 use std::fmt;
 use std::path::PathBuf;
 
@@ -43,10 +45,10 @@ impl<'a> ApiKeyExplanation<&'a str> {
     }
 
     fn into_err<T>(self) -> self::Result<T> {
-        Err(Error::from(ErrorKind::NotAnApiKey(ApiKeyExplanation {
+        Err(ErrorKind::NotAnApiKey(ApiKeyExplanation {
             reasons: self.reasons,
             bad_key: self.bad_key.map(str::to_owned),
-        })))
+        }))
     }
 
     pub fn add(&mut self, reason: impl Into<String>) {
@@ -102,137 +104,565 @@ impl<S: Borrow<str>> fmt::Display for ApiKeyExplanation<S> {
     }
 }
 
-error_chain! {
-    foreign_links {
-        Clap(clap::Error);
-        Globset(globset::Error);
-        InvalidHeaderValue(reqwest::header::InvalidHeaderValue);
-        Io(std::io::Error);
-        ParseInt(std::num::ParseIntError);
-        ParseFloat(std::num::ParseFloatError);
-        ParseDateTime(chrono::format::ParseError);
-        Reqwest(reqwest::Error);
-        SerdeYaml(serde_yaml::Error);
-    }
+/// Everything that can go wrong. Errors from other libraries are wrapped
+/// transparently, and `chain_err` adds context to an error while keeping
+/// the original as its source.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("{0}")]
+    Msg(String),
+
+    #[error("{context}")]
+    Context {
+        context: Box<Error>,
+        #[source]
+        source: Box<dyn StdError + Send + 'static>,
+    },
+
+    #[error(transparent)]
+    Clap(#[from] clap::Error),
+    #[error(transparent)]
+    Globset(#[from] globset::Error),
+    #[error(transparent)]
+    InvalidHeaderValue(#[from] reqwest::header::InvalidHeaderValue),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    ParseInt(#[from] std::num::ParseIntError),
+    #[error(transparent)]
+    ParseFloat(#[from] std::num::ParseFloatError),
+    #[error(transparent)]
+    ParseDateTime(#[from] chrono::format::ParseError),
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    SerdeYaml(#[from] serde_yaml::Error),
+
+    #[error("Error response from server to {} {}:\n  {} {}\n  {}",
+            .method, url_path(.url), .contents.status, .contents.title, .contents.message)]
+    ServerError {
+        contents: JsonStatus,
+        method: String,
+        url: String,
+    },
+
+    /// An error from working on one of several files or homeworks.
+    #[error("{item}: {error}")]
+    Processing { item: String, error: Box<Error> },
+
+    #[error("The server is receiving too many requests; please try again{}.",
+            .0.map(|secs| format!(" in {} seconds", secs)).unwrap_or_default())]
+    RateLimited(Option<u64>),
+
+    #[error("Cannot send {0} request while offline (--offline).")]
+    Offline(String),
+
+    #[error(
+        "The server speaks API version {0}, which is incompatible with gsc’s {1}; \
+             pass --force to try anyway."
+    )]
+    IncompatibleServer(String, String),
+
+    #[error("Could not reach {0}: {1}")]
+    ServerUnreachable(String, String),
+
+    #[error("{0} is down (HTTP {1}).")]
+    ServerUnavailable(String, String),
+
+    #[error("No cached copy of {0} is available offline (--offline).")]
+    NotCached(String),
 
-    errors {
-        ServerError(contents: JsonStatus) {
-            description("error from server")
-            display("Error response from server:\n  {} {}\n  {}",
-                    contents.status, contents.title, contents.message)
-        }
+    #[error("{0}")]
+    NotAnApiKey(ApiKeyExplanation<String>),
 
-        NotAnApiKey(explanation: ApiKeyExplanation<String>) {
-            description("doesn't look like an API key")
-            display("{}", explanation)
-        }
+    #[error("Homework hw{0} does not exist.")]
+    UnknownHomework(usize),
+
+    #[error("Syntax error: could not parse ‘{1}’ as {0}.")]
+    SyntaxError(String, String),
+
+    #[error("Syntax error: could not parse ‘{1}’ as {0}.\nDid you mean ‘{2}’?")]
+    SyntaxErrorSuggest(String, String, String),
 
-        UnknownHomework(number: usize) {
-            description("unknown homework")
-            display("Homework hw{} does not exist.", number)
-        }
+    #[error("‘{0}’ is not a gsc command.\nDid you mean ‘{1}’?")]
+    UnknownCommand(String, String),
 
-        SyntaxError(class: String, thing: String) {
-            description("syntax error")
-            display("Syntax error: could not parse ‘{}’ as {}.", thing, class)
-        }
+    #[error("Administrative commands are in their own program now; use ‘gsc-admin’ instead.")]
+    AdminCommandsMoved,
 
-        NoCommandGiven {
-            description("no subcommand given")
-            display("No subcommand given; pass -h for help.")
-        }
+    #[error("No homework given, and no default_hw is set in a .gscrc file.")]
+    NoHomeworkGiven,
 
-        LoginPlease {
-            description("login please")
-            display("You are not logged in; use the ‘gsc auth’ command to authenticate.")
-        }
+    #[error(
+        "No homework is open with an upcoming due date; set default_hw in .gscrc to pick one."
+    )]
+    NoCurrentHomework,
 
-        NoCookieFileGiven {
-            description("no cookie file given")
-            display("Please specify a cookie file.")
-        }
+    #[error("No subcommand given; pass -h for help.")]
+    NoCommandGiven,
 
-        NoSuchRemoteFile(rpat: RemotePattern) {
-            description("no such remote file")
-            display("No remote files matching pattern ‘{}’.", rpat)
-        }
+    #[error("You are not logged in; use the ‘gsc auth’ command to authenticate.")]
+    LoginPlease,
 
-        CannotCopyLocalToLocal(src: PathBuf, dst: PathBuf) {
-            description("cannot copy local to local")
-            display("Cannot copy local file ({}) to local destination ({}).",
-                    src.display(), dst.display())
-        }
+    #[error("The server rejected the API key for ‘{0}’. Use ‘gsc auth {0}’ to enter a new one.")]
+    ApiKeyRejected(String),
 
-        CannotCopyLocalToLocalExtra(src: PathBuf, dst: PathBuf, extra: String) {
-            description("cannot copy local to local")
-            display("Cannot copy local file ({}) to local destination ({}).\n{}",
-                    src.display(), dst.display(), extra)
-        }
+    #[error(
+        "The server rejected the API key for ‘{0}’, which likely expired on {1}. \
+             Get a new one at {2} and enter it with ‘gsc auth {0}’."
+    )]
+    ApiKeyExpired(String, UtcDateTime, String),
 
-        CannotCopyRemoteToRemote(src: RemotePattern, dst: RemotePattern) {
-            description("cannot copy remote to remote")
-            display("Cannot copy remote file ({}) to remote destination ({}).", src, dst)
-        }
+    #[error("‘gsc-admin’ requires the grader or admin role, but ‘{0}’ is a {1}.")]
+    RequiresStaffRole(String, UserRole),
 
-        BadLocalPath(filename: PathBuf) {
-            description("bad local path")
-            display("Not a well-formed local file path: ‘{}’.", filename.display())
-        }
+    #[error(
+        "To use an API key from $GSC_API_KEY or --api-key-file, \
+             set $GSC_USER to your username."
+    )]
+    ApiKeyWithoutUser,
 
-        FilenameNotUtf8(filename: PathBuf) {
-            description("filename not UTF-8")
-            display("Filename not proper UTF-8: ‘{}’.", filename.display())
-        }
+    #[error("Profile ‘{0}’ is not defined in the dotfile.")]
+    UnknownProfile(String),
 
-        MultipleSourcesOneDestination {
-            description("multiple sources one destination")
-            display("Multiple source files cannot be copied to one destination file.")
-        }
+    #[error("Server ‘{0}’ is not defined under ‘endpoints’ in the dotfile.")]
+    UnknownServer(String),
+
+    #[error("Unknown setting: ‘{0}’.")]
+    UnknownSetting(String),
+
+    #[error("Could not find the history file; please set $GSC_HISTORY_FILE.")]
+    NoHistoryFile,
+
+    #[error("Could not find the audit file; please set $GSC_AUDIT_FILE.")]
+    NoAuditFile,
+
+    #[error("Could not find the dotfile; please set $GSC_RC_FILE.")]
+    NoDotfile,
+
+    #[error("Please specify a cookie file.")]
+    NoCookieFileGiven,
+
+    #[error("No remote files matching pattern ‘{0}’.")]
+    NoSuchRemoteFile(RemotePattern),
+
+    #[error("Cannot copy local file ({}) to local destination ({}).",
+            .0.display(), .1.display())]
+    CannotCopyLocalToLocal(PathBuf, PathBuf),
+
+    #[error("Cannot copy local file ({}) to local destination ({}).\n{}",
+            .0.display(), .1.display(), .2)]
+    CannotCopyLocalToLocalExtra(PathBuf, PathBuf, String),
+
+    #[error("Cannot copy remote file ({0}) to remote destination ({1}).")]
+    CannotCopyRemoteToRemote(RemotePattern, RemotePattern),
+
+    #[error("Not a well-formed local file path: ‘{}’.", .0.display())]
+    BadLocalPath(PathBuf),
+
+    #[error("Filename not proper UTF-8: ‘{}’.", .0.display())]
+    FilenameNotUtf8(PathBuf),
+
+    #[error("Not a valid remote file name: ‘{0}’.\nNames can’t be empty, ‘.’, or ‘..’, or contain slashes or control characters.")]
+    BadRemoteFilename(String),
+
+    #[error("‘{0}’ matches more than one file, so it can only be compared with a directory.")]
+    DiffNeedsDirectory(String),
+
+    #[error("Multiple source files cannot be copied to one destination file.")]
+    MultipleSourcesOneDestination,
+
+    #[error("Destination pattern ‘{0}’ resolves to multiple remote files:\n{1}")]
+    DestinationPatternIsMultiple(RemotePattern, RemoteFiles),
+
+    #[error("Cannot copy whole source homework ‘hw{}’ over file destination ‘{}’.",
+            .0, .1.display())]
+    SourceHwToDestinationFile(usize, PathBuf),
+
+    #[error("To ‘{0}’ a whole homework, you must provide the ‘-a’ flag.")]
+    CommandRequiresFlag(String),
+
+    #[error("Could not find informational eval item to add score to.")]
+    NoInformationalEvalItem,
+
+    #[error("Homework {0} does not have item {1}.")]
+    EvalItemDoesNotExist(usize, usize),
+
+    #[error("Score ‘{0}’ doesn’t fit hw{1} item {2}: {}", eval_score_hint(*.3))]
+    EvalScoreMismatch(String, usize, usize, EvalType),
+
+    #[error("Self evaluation for hw{0} closed on {1}. Use ‘--force’ to try anyway.")]
+    SelfEvalClosed(usize, UtcDateTime),
+
+    #[error("Self evaluation for hw{0} opens once it’s due, on {1}. Use ‘--force’ to try anyway.")]
+    SelfEvalNotOpen(usize, UtcDateTime),
+
+    #[error("Not setting hw{0} item {1} to {2} without an explanation; give one, or pass ‘--allow-empty’.")]
+    EmptyExplanation(usize, usize, String),
+
+    #[error("Homework {0} item {1} hasn’t been graded yet, so there’s nothing to regrade.")]
+    NotGradedYet(usize, usize),
+
+    #[error("Not overwriting destination file ‘{0}’ (-n).")]
+    DestinationFileExists(String),
+
+    #[error(
+        "Pre-push hook ‘{0}’ failed ({1}), so nothing was uploaded. Use ‘--no-verify’ to skip it."
+    )]
+    PrePushHookFailed(String, String),
+
+    #[error("‘{0}’ failed:\n  {1}")]
+    GitFailed(String, String),
+
+    #[error("Both ‘{1}’ and ‘{2}’ would be uploaded as ‘{0}’.")]
+    DuplicateFileName(String, String, String),
+
+    #[error("Not uploading ‘{0}’: {1}. Use ‘--force’ to upload it anyway.")]
+    SuspiciousUpload(String, String),
+
+    #[error("Cannot extract into ‘{0}’; the destination must be a homework, e.g. ‘hw3’.")]
+    ExtractNeedsHomework(String),
+
+    #[error(
+        "Cannot rename into ‘{0}’ with ‘--into’; the destination must be a homework, e.g. ‘hw3:’."
+    )]
+    IntoNeedsHomework(String),
+
+    #[error("Cannot tell archive format from ‘{}’; use .zip, .tar, .tar.gz, or .tgz.",
+            .0.display())]
+    UnknownArchiveFormat(PathBuf),
+
+    #[error("Username did not match, so ‘{0}’ was not deleted.")]
+    DeletionNotConfirmed(String),
+
+    #[error("Cannot ask without a terminal: {0}\nSee ‘unattended’ in gsc(1) for how to answer in advance.")]
+    NoTerminal(String),
+
+    #[error("Not asking, because of ‘--no-input’: {0}")]
+    NoInput(String),
+
+    #[error("Alias ‘{0}’ expands to itself.")]
+    AliasLoop(String),
+
+    #[error("Alias ‘{0}’ has no command.")]
+    EmptyAlias(String),
+
+    #[error("Could not set modification time for file ‘{}’.\n  reason: {}",
+            .0.display(), .1)]
+    SetModTimeFailed(PathBuf, String),
+}
+
+/// What most of the code calls `Error` when constructing one, from back
+/// when errors and their kinds were separate types.
+pub type ErrorKind = Error;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Broad classes of errors, which determine `gsc`’s exit status.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    /// The command line doesn’t make sense.
+    Usage,
+    /// The dotfile or environment is wrong.
+    Config,
+    /// Not logged in, or the server doesn’t accept our credentials.
+    Auth,
+    /// Something the user asked for doesn’t exist.
+    NotFound,
+    /// Couldn’t get through to the server.
+    Network,
+    /// The server refused or failed, or we don’t understand it.
+    Server,
+    /// Reading or writing local files failed.
+    Local,
+    Other,
+}
 
-        DestinationPatternIsMultiple(rpat: RemotePattern, rfiles: RemoteFiles) {
-            description("destination pattern is multiple")
-            display("Destination pattern ‘{}’ resolves to multiple remote files:\n{}", rpat, rfiles)
+impl Category {
+    /// Exit statuses follow BSD’s `sysexits.h` where there’s a match.
+    /// (Status 2 is taken: `gsc diff` uses it to mean “files differ.”)
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Category::Usage => 64,
+            Category::NotFound => 66,
+            Category::Network => 69,
+            Category::Local => 74,
+            Category::Server => 76,
+            Category::Auth => 77,
+            Category::Config => 78,
+            Category::Other => 1,
         }
+    }
+}
+
+/// Adds context to the error in a `Result`, or makes an error from a
+/// `None`.
+pub trait ResultExt<T> {
+    fn chain_err<F, C>(self, context: F) -> Result<T>
+    where
+        F: FnOnce() -> C,
+        C: Into<Error>;
+}
+
+impl<T, E: StdError + Send + 'static> ResultExt<T> for std::result::Result<T, E> {
+    fn chain_err<F, C>(self, context: F) -> Result<T>
+    where
+        F: FnOnce() -> C,
+        C: Into<Error>,
+    {
+        self.map_err(|error| Error::with_chain(error, context()))
+    }
+}
+
+impl<T> ResultExt<T> for Option<T> {
+    fn chain_err<F, C>(self, context: F) -> Result<T>
+    where
+        F: FnOnce() -> C,
+        C: Into<Error>,
+    {
+        self.ok_or_else(|| context().into())
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Msg(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Msg(message.to_owned())
+    }
+}
 
-        SourceHwToDestinationFile(src: usize, dst: PathBuf) {
-            description("source homework to destination file")
-            display("Cannot copy whole source homework ‘hw{}’ over file destination ‘{}’.",
-                    src, dst.display())
+impl Error {
+    /// Wraps `error` in `context`, which is what gets displayed.
+    pub fn with_chain(error: impl StdError + Send + 'static, context: impl Into<Error>) -> Self {
+        Error::Context {
+            context: Box::new(context.into()),
+            source: Box::new(error),
         }
+    }
 
-        CommandRequiresFlag(command: String) {
-            description("command requires ‘-a’ flag")
-            display("To ‘{}’ a whole homework, you must provide the ‘-a’ flag.", command)
+    /// Notes that this error happened while working on `item`, such as
+    /// a remote file, so that a warning about it says which.
+    pub fn processing(self, item: impl fmt::Display) -> Self {
+        Error::Processing {
+            item: item.to_string(),
+            error: Box::new(self),
         }
+    }
 
-        NoInformationalEvalItem {
-            description("no informational eval item")
-            display("Could not find informational eval item to add score to.")
+    /// The error without any context that `chain_err` or `processing`
+    /// added.
+    pub fn kind(&self) -> &ErrorKind {
+        match self {
+            Error::Context { context, .. } => context.kind(),
+            Error::Processing { error, .. } => error.kind(),
+            _ => self,
         }
+    }
 
-        EvalItemDoesNotExist(hw: usize, number: usize) {
-            description("requested eval item does not exist")
-            display("Homework {} does not have item {}.", hw, number)
+    /// The HTTP status that the server responded with, if this error came
+    /// from a response.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Error::Context { context, source } => context
+                .status()
+                .or_else(|| as_error(source.as_ref())?.status()),
+            Error::Processing { error, .. } => error.status(),
+            Error::ServerError { contents, .. } => Some(contents.status),
+            Error::RateLimited(_) => Some(429),
+            Error::Reqwest(error) => error.status().map(|status| status.as_u16()),
+            _ => None,
         }
+    }
 
-        DestinationFileExists(filename: String) {
-            description("destination file exists, and flag ‘-n’ was given")
-            display("Not overwriting destination file ‘{}’ (-n).", filename)
+    /// The URL of the request that this error came from, if any.
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            Error::Context { context, source } => {
+                context.url().or_else(|| as_error(source.as_ref())?.url())
+            }
+            Error::Processing { error, .. } => error.url(),
+            Error::ServerError { url, .. } => Some(url),
+            Error::Reqwest(error) => error.url().map(|url| url.as_str()),
+            _ => None,
         }
+    }
 
-        SetModTimeFailed(file: PathBuf, reason: String) {
-            description("could not set file modification time to match server")
-            display("Could not set modification time for file ‘{}’.\n  reason: {}",
-                    file.display(), reason)
+    pub fn category(&self) -> Category {
+        use self::Category::*;
+
+        match self {
+            Error::Context { context, source } => match context.category() {
+                Other => match as_error(source.as_ref()) {
+                    Some(error) => error.category(),
+                    None if source.is::<std::io::Error>() => Local,
+                    None if source.is::<serde_yaml::Error>() => Config,
+                    None => Other,
+                },
+                category => category,
+            },
+
+            Error::Processing { error, .. } => error.category(),
+            Error::Msg(_) | Error::InvalidHeaderValue(_) => Other,
+            Error::Clap(_) | Error::Globset(_) => Usage,
+            Error::ParseInt(_) | Error::ParseFloat(_) | Error::ParseDateTime(_) => Usage,
+            Error::Io(_) => Local,
+            Error::Reqwest(error) if error.status().is_some() => Server,
+            Error::Reqwest(error) if error.is_decode() => Server,
+            Error::Reqwest(_) => Network,
+            Error::SerdeJson(_) => Server,
+            Error::SerdeYaml(_) => Config,
+
+            Error::ServerError { contents, .. } => match contents.status {
+                401 | 403 => Auth,
+                404 => NotFound,
+                _ => Server,
+            },
+            Error::IncompatibleServer(..) => Server,
+
+            Error::RateLimited(_)
+            | Error::Offline(_)
+            | Error::ServerUnreachable(..)
+            | Error::ServerUnavailable(..)
+            | Error::NotCached(_) => Network,
+
+            Error::NotAnApiKey(_)
+            | Error::LoginPlease
+            | Error::ApiKeyRejected(_)
+            | Error::ApiKeyExpired(..)
+            | Error::RequiresStaffRole(..) => Auth,
+
+            Error::ApiKeyWithoutUser
+            | Error::UnknownProfile(_)
+            | Error::UnknownServer(_)
+            | Error::UnknownSetting(_)
+            | Error::NoHistoryFile
+            | Error::NoAuditFile
+            | Error::NoDotfile
+            | Error::AliasLoop(_)
+            | Error::EmptyAlias(_) => Config,
+
+            Error::SyntaxError(..)
+            | Error::SyntaxErrorSuggest(..)
+            | Error::UnknownCommand(..)
+            | Error::AdminCommandsMoved
+            | Error::NoHomeworkGiven
+            | Error::NoCommandGiven
+            | Error::NoCookieFileGiven
+            | Error::CannotCopyLocalToLocal(..)
+            | Error::CannotCopyLocalToLocalExtra(..)
+            | Error::CannotCopyRemoteToRemote(..)
+            | Error::BadLocalPath(_)
+            | Error::BadRemoteFilename(_)
+            | Error::DiffNeedsDirectory(_)
+            | Error::MultipleSourcesOneDestination
+            | Error::DestinationPatternIsMultiple(..)
+            | Error::SourceHwToDestinationFile(..)
+            | Error::CommandRequiresFlag(_)
+            | Error::ExtractNeedsHomework(_)
+            | Error::IntoNeedsHomework(_)
+            | Error::UnknownArchiveFormat(_)
+            | Error::EvalScoreMismatch(..)
+            | Error::EmptyExplanation(..)
+            | Error::NoTerminal(_)
+            | Error::NoInput(_) => Usage,
+
+            Error::UnknownHomework(_)
+            | Error::NoCurrentHomework
+            | Error::NoSuchRemoteFile(_)
+            | Error::NoInformationalEvalItem
+            | Error::EvalItemDoesNotExist(..)
+            | Error::NotGradedYet(..) => NotFound,
+
+            Error::FilenameNotUtf8(_)
+            | Error::DestinationFileExists(_)
+            | Error::SetModTimeFailed(..) => Local,
+
+            Error::PrePushHookFailed(..)
+            | Error::GitFailed(..)
+            | Error::DuplicateFileName(..)
+            | Error::SuspiciousUpload(..)
+            | Error::SelfEvalClosed(..)
+            | Error::SelfEvalNotOpen(..)
+            | Error::DeletionNotConfirmed(_) => Other,
         }
     }
 }
 
+/// What kind of score an item of type `eval_type` takes.
+fn eval_score_hint(eval_type: EvalType) -> &'static str {
+    match eval_type {
+        EvalType::Boolean => "it’s a yes/no item, so answer ‘yes’ or ‘no’.",
+        _ => "give a fraction like ‘4/5’ or a percentage from 0 to 100.",
+    }
+}
+
+/// The path part of `url`, for showing which request failed.
+fn url_path(url: &str) -> &str {
+    let after_scheme = url.find("://").map_or(0, |i| i + 3);
+    url[after_scheme..]
+        .find('/')
+        .map_or(url, |i| &url[after_scheme + i..])
+}
+
+fn as_error<'a>(error: &'a (dyn StdError + Send + 'static)) -> Option<&'a Error> {
+    error.downcast_ref()
+}
+
 impl ErrorKind {
     pub fn syntax(class: impl Into<String>, thing: impl Into<String>) -> Self {
         Self::SyntaxError(class.into(), thing.into())
     }
 
+    /// A syntax error for homework spec `spec`, suggesting the spec
+    /// that it looks like a typo of, e.g., ‘hw3’ for ‘h3’ or ‘HW 3’.
+    pub fn hw_syntax(class: impl Into<String>, spec: impl Into<String>) -> Self {
+        lazy_static! {
+            static ref NEAR_HW: Regex = Regex::new(r"^([[:alpha:]]*)[-_ .#]*(\d+)(:.*)?$").unwrap();
+        }
+
+        let class = class.into();
+        let spec = spec.into();
+
+        let suggestion = NEAR_HW.captures(&spec).and_then(|captures| {
+            let prefix = captures[1].to_lowercase();
+            let number: usize = captures[2].parse().ok()?;
+            let rest = captures.get(3).map_or("", |m| m.as_str());
+
+            if prefix.is_empty() || suggest::closest(&prefix, vec!["hw", "homework"]).is_some() {
+                Some(format!("hw{}{}", number, rest))
+            } else {
+                None
+            }
+        });
+
+        match suggestion {
+            Some(suggestion) if suggestion != spec => {
+                Self::SyntaxErrorSuggest(class, spec, suggestion)
+            }
+            _ => Self::SyntaxError(class, spec),
+        }
+    }
+
+    /// An error for unknown subcommand `command`, if it is close enough to
+    /// one of `commands` to suggest it.
+    pub fn unknown_command<'a>(
+        command: &str,
+        commands: impl IntoIterator<Item = &'a str>,
+    ) -> Option<Self> {
+        let suggestion = suggest::closest(command, commands)?;
+        Some(Self::UnknownCommand(
+            command.to_owned(),
+            suggestion.to_owned(),
+        ))
+    }
+
     pub fn dest_pat_is_multiple(
         rpat: &RemotePattern,
         rfile_metas: &[super::messages::FileMeta],
@@ -243,7 +673,7 @@ impl ErrorKind {
 
     pub fn cannot_copy_local_to_local(src: impl Into<PathBuf>, dst: impl Into<PathBuf>) -> Self {
         lazy_static! {
-            pub static ref HW_NUM: Regex = Regex::new(r"^hw\d+$").unwrap();
+            pub static ref HW_NUM: Regex = Regex::new(r"^(?i:hw\d+|current)$").unwrap();
         }
 
         let src = src.into();
@@ -260,19 +690,6 @@ impl ErrorKind {
     }
 }
 
-impl Error {
-    pub fn syntax(class: impl Into<String>, thing: impl Into<String>) -> Self {
-        ErrorKind::syntax(class, thing).into()
-    }
-
-    pub fn dest_pat_is_multiple(
-        rpat: &RemotePattern,
-        rfile_metas: &[super::messages::FileMeta],
-    ) -> Self {
-        ErrorKind::dest_pat_is_multiple(rpat, rfile_metas).into()
-    }
-}
-
 impl std::fmt::Display for RemoteFiles {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         for filename in &self.0 {
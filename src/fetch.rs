@@ -0,0 +1,100 @@
+use crate::cache::HttpCache;
+use crate::config::Config;
+use crate::credentials::Credentials;
+use crate::prelude::*;
+use crate::scheduler::Scheduler;
+use crate::trace::Tracer;
+
+use reqwest::{blocking, header, Method};
+
+/// Sends authenticated GET requests from worker threads, which can’t
+/// borrow the (non-`Sync`) `GscClient` itself.
+pub(crate) struct Fetcher<'a> {
+    http: &'a blocking::Client,
+    config: &'a Config,
+    cache: Option<&'a HttpCache>,
+    tracer: Option<&'a Tracer>,
+    user: String,
+    cookie: header::HeaderValue,
+}
+
+impl<'a> Fetcher<'a> {
+    pub fn get(&self, uri: &str) -> Result<blocking::Response> {
+        let request = self
+            .http
+            .get(uri)
+            .header(header::COOKIE, self.cookie.clone())
+            .build()?;
+        let response = crate::execute_request(
+            self.http,
+            self.config,
+            self.cache,
+            self.tracer,
+            Some(&self.user),
+            request,
+        )?;
+        crate::check_response(&Method::GET, response)
+    }
+}
+
+impl GscClient {
+    /// Runs `fetch` on each of `uris` concurrently, as the `Scheduler`
+    /// allows, returning the results in the same order, or the first
+    /// error.
+    ///
+    /// The workers can’t prompt for a new API key, so if the server
+    /// rejects the key, the first rejected URI is fetched again here, the
+    /// usual way, which asks for a new key (or explains the rejection),
+    /// and then whatever was rejected is fetched again with the new key.
+    pub(crate) fn fetch_in_parallel<T, F>(&self, uris: &[String], fetch: F) -> Result<Vec<T>>
+    where
+        T: Send,
+        F: Fn(&Fetcher, &str) -> Result<T> + Sync,
+    {
+        self.check_server_version()?;
+        let creds = self.current_credentials()?;
+        let mut results = self.fetch_each(uris, &creds, &fetch)?;
+
+        let rejected: Vec<usize> = (0..uris.len())
+            .filter(|&i| matches!(&results[i], Err(e) if e.status() == Some(401)))
+            .collect();
+
+        if let Some(&first) = rejected.first() {
+            self.send_request_with_credentials(self.http.get(&uris[first]), &creds)?;
+            let creds = self.current_credentials()?;
+            let uris: Vec<String> = rejected.iter().map(|&i| uris[i].clone()).collect();
+            for (i, result) in rejected
+                .into_iter()
+                .zip(self.fetch_each(&uris, &creds, &fetch)?)
+            {
+                results[i] = result;
+            }
+        } else if !uris.is_empty() {
+            self.note_key_accepted(&creds);
+        }
+
+        results.into_iter().collect()
+    }
+
+    fn fetch_each<T, F>(
+        &self,
+        uris: &[String],
+        creds: &Credentials,
+        fetch: &F,
+    ) -> Result<Vec<Result<T>>>
+    where
+        T: Send,
+        F: Fn(&Fetcher, &str) -> Result<T> + Sync,
+    {
+        let fetcher = Fetcher {
+            http: &self.http,
+            config: &self.config,
+            cache: self.cache.as_ref(),
+            tracer: self.tracer.as_ref(),
+            user: creds.username().to_owned(),
+            cookie: creds.to_header()?,
+        };
+
+        Ok(Scheduler::new(&self.config).run(uris, |uri| fetch(&fetcher, uri)))
+    }
+}
@@ -0,0 +1,72 @@
+//! Remote file names. A remote name is a single path component: the
+//! server stores each homework’s files flat, and we put them in local
+//! directories by name, so a name that could be read as a path (or that
+//! would garble a terminal) is refused both on the way up and on the way
+//! down. Names go into request URIs percent-encoded, and the same
+//! encoding comes back out of URIs with `decode`.
+
+use crate::errors::{ErrorKind, Result};
+
+use percent_encoding as enc;
+
+use std::borrow::Cow;
+use std::fmt;
+
+const ENCODE_SET: &enc::AsciiSet = &enc::CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'<')
+    .add(b'>')
+    .add(b'`')
+    .add(b'?')
+    .add(b'{')
+    .add(b'}')
+    .add(b'%')
+    .add(b'/')
+    .add(b'+');
+
+/// Checks that `name` is safe to use as a remote file name, and as the
+/// last component of a local path: it must be non-empty, not ‘.’ or
+/// ‘..’, and free of slashes, backslashes, and control characters.
+pub fn check(name: &str) -> Result<&str> {
+    let bad = name.is_empty()
+        || name == "."
+        || name == ".."
+        || name.contains(|c: char| c == '/' || c == '\\' || c.is_control());
+
+    if bad {
+        Err(ErrorKind::BadRemoteFilename(display(name).into_owned()))
+    } else {
+        Ok(name)
+    }
+}
+
+/// Percent-encodes `name` as one segment of a URI path.
+pub fn encode(name: &str) -> impl fmt::Display + '_ {
+    enc::utf8_percent_encode(name, ENCODE_SET)
+}
+
+/// Undoes `encode`, or any other percent-encoding. Invalid UTF-8 is
+/// replaced rather than refused, since we only show or compare the result.
+pub fn decode(segment: &str) -> Cow<'_, str> {
+    enc::percent_decode_str(segment).decode_utf8_lossy()
+}
+
+/// Makes `name` safe to print, by escaping any control characters in it.
+pub fn display(name: &str) -> Cow<'_, str> {
+    if name.contains(char::is_control) {
+        name.chars()
+            .map(|c| {
+                if c.is_control() {
+                    c.escape_default().to_string()
+                } else {
+                    c.to_string()
+                }
+            })
+            .collect::<String>()
+            .into()
+    } else {
+        name.into()
+    }
+}
@@ -0,0 +1,124 @@
+//! Checking that the server speaks a version of the API that we
+//! understand.
+
+use crate::messages::ServerInfo;
+use crate::prelude::*;
+
+use reqwest::{Method, StatusCode};
+
+/// The API version that this client speaks. A server with a different
+/// major version is incompatible; one with an older minor version may
+/// lack features that we use.
+const API_VERSION: (u32, u32) = (1, 0);
+
+/// How long to trust a cached `/api/version` response before asking the
+/// server again.
+const SERVER_INFO_TTL_HOURS: i64 = 24;
+
+impl GscClient {
+    /// Compares the server’s API version with ours, the first time it’s
+    /// called. An incompatible server is an error unless `--force` was
+    /// given.
+    pub(crate) fn check_server_version(&self) -> Result<()> {
+        if self.server_info.get().is_some() {
+            return Ok(());
+        }
+
+        let info = self.fetch_server_info().unwrap_or_else(|e| {
+            ve3!("Could not check the server’s API version: {}", e);
+            None
+        });
+
+        let result = match &info {
+            Some(info) => self.compare_server_version(info),
+            None => Ok(()),
+        };
+
+        let _ = self.server_info.set(info);
+        result
+    }
+
+    /// What the server reported about itself, if we’ve asked.
+    pub fn server_info(&self) -> Option<&ServerInfo> {
+        self.server_info.get().and_then(Option::as_ref)
+    }
+
+    fn compare_server_version(&self, info: &ServerInfo) -> Result<()> {
+        let (major, minor) = match info.api_version() {
+            Some(version) => version,
+            None => {
+                ve2!(
+                    "Server reported an unknown API version: {}",
+                    info.api_version
+                );
+                return Ok(());
+            }
+        };
+
+        let ours = format!("{}.{}", API_VERSION.0, API_VERSION.1);
+
+        if major != API_VERSION.0 {
+            if !self.config.force() {
+                Err(ErrorKind::IncompatibleServer(
+                    info.api_version.clone(),
+                    ours.clone(),
+                ))?;
+            }
+
+            self.warn(format!(
+                "Warning: the server speaks API version {}, but gsc speaks {}; \
+                 continuing anyway (--force).",
+                info.api_version, ours
+            ));
+        } else if minor < API_VERSION.1 {
+            ve1!(
+                "Warning: the server speaks API version {}, older than gsc’s {}; \
+                 some commands may not work.",
+                info.api_version,
+                ours
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Asks the server for its version, unless the cache has a recent
+    /// enough answer. A server too old to have `/api/version` gives `None`.
+    fn fetch_server_info(&self) -> Result<Option<ServerInfo>> {
+        let uri = format!("{}/api/version", self.config.get_endpoint());
+
+        if let Some(entry) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.lookup(None, &uri))
+        {
+            let age = -entry.fetched.time_until();
+            if self.config.offline() || age < chrono::Duration::hours(SERVER_INFO_TTL_HOURS) {
+                ve3!("Using server version cached {}", entry.fetched);
+                return Ok(Some(crate::messages::from_slice(entry.body.as_bytes())?));
+            }
+        }
+
+        if self.config.offline() {
+            return Ok(None);
+        }
+
+        let request = self.http.get(&uri).build()?;
+        let response = crate::execute_request(
+            &self.http,
+            &self.config,
+            self.cache.as_ref(),
+            self.tracer.as_ref(),
+            None,
+            request,
+        )?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            ve3!("Server does not report its API version");
+            return Ok(None);
+        }
+
+        let response = crate::check_response(&Method::GET, response)?;
+        Ok(Some(response.message()?))
+    }
+}
@@ -0,0 +1,152 @@
+//! A local journal of the changes made to submissions.
+//!
+//! Each upload, deletion, move, type change, and self evaluation appends
+//! one JSON line to the history file, so that students have their own
+//! record of what they did and when, independent of the server.
+
+use crate::messages::{FileMeta, UtcDateTime};
+use crate::prelude::*;
+
+use serde_derive::{Deserialize, Serialize};
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Upload,
+    Rm,
+    Mv,
+    Chtype,
+    EvalSet,
+}
+
+impl Action {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Action::Upload => "upload",
+            Action::Rm => "rm",
+            Action::Mv => "mv",
+            Action::Chtype => "chtype",
+            Action::EvalSet => "eval set",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Entry {
+    pub time: UtcDateTime,
+    pub user: String,
+    pub action: Action,
+    pub hw: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<u64>,
+    /// The upload time that the server reported, if it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_time: Option<UtcDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl Entry {
+    pub fn new(action: Action, hw: usize) -> Self {
+        Entry {
+            time: UtcDateTime::now(),
+            user: String::new(),
+            action,
+            hw,
+            file: None,
+            bytes: None,
+            server_time: None,
+            detail: None,
+        }
+    }
+
+    pub fn file(mut self, name: impl Into<String>) -> Self {
+        self.file = Some(name.into());
+        self
+    }
+
+    pub fn bytes(mut self, bytes: u64) -> Self {
+        self.bytes = Some(bytes);
+        self
+    }
+
+    pub fn detail(mut self, detail: impl ToString) -> Self {
+        self.detail = Some(detail.to_string());
+        self
+    }
+
+    /// Fills in what the server said about the file, if anything.
+    pub fn server_meta(mut self, meta: Option<&FileMeta>) -> Self {
+        if let Some(meta) = meta {
+            self.bytes = Some(meta.byte_count as u64);
+            self.server_time = Some(meta.upload_time.clone());
+        }
+        self
+    }
+}
+
+impl GscClient {
+    /// Appends `entry` to the history file. Failing to is only worth a
+    /// warning, since the change itself has already been made.
+    pub(crate) fn record(&self, mut entry: Entry) {
+        let path = match self.config.get_history_file() {
+            Some(path) => path,
+            None => return,
+        };
+
+        if let Ok((user, _)) = self.load_effective_credentials() {
+            entry.user = user;
+        }
+
+        if let Err(e) = append_entry(path, &entry) {
+            ve1!("Could not write history file {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Appends `entry` to the JSON-lines file at `path`, creating it (and its
+/// directory) if need be.
+pub(crate) fn append_entry(path: &Path, entry: &impl serde::Serialize) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+
+    fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)?
+        .write_all(line.as_bytes())?;
+
+    Ok(())
+}
+
+/// The entries in the JSON-lines file at `path`, such as the history
+/// file, oldest first, skipping any lines that don’t parse.
+pub fn read_entries<T: serde::de::DeserializeOwned>(path: &Path) -> Result<Vec<T>> {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => Err(e).chain_err(|| format!("Could not read {}", path.display()))?,
+    };
+
+    let mut entries = Vec::new();
+
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => ve2!("Skipping line {} of {}: {}", i + 1, path.display(), e),
+        }
+    }
+
+    Ok(entries)
+}
@@ -0,0 +1,46 @@
+//! User-configured commands run around uploads, like git hooks.
+
+use crate::prelude::*;
+
+use std::process::Command;
+
+impl GscClient {
+    /// Runs the `hooks.pre_push` command, if any, before uploading to
+    /// homework `hw`, failing if it does.
+    pub(crate) fn run_pre_push_hook(&self, hw: usize) -> Result<()> {
+        let hook = match self.config.get_pre_push_hook() {
+            Some(hook) => hook,
+            None => return Ok(()),
+        };
+
+        v2!("Running pre-push hook: {}", hook);
+
+        let status = shell(hook)
+            .env("GSC_HW", hw.to_string())
+            .status()
+            .chain_err(|| format!("Could not run pre-push hook: {}", hook))?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(ErrorKind::PrePushHookFailed(
+                hook.to_owned(),
+                status.to_string(),
+            ))
+        }
+    }
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
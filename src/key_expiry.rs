@@ -0,0 +1,77 @@
+//! Telling an expired API key from a bad one.
+//!
+//! The credentials file records when its key was saved and when the
+//! server last accepted it. When the server rejects the key and says how
+//! long its keys last (as `key_rotation_days` in `/api/version`), a key
+//! older than that has most likely just expired, and the user needs to
+//! hear where to get a new one rather than that something is wrong.
+
+use crate::credentials::Credentials;
+use crate::messages::UtcDateTime;
+use crate::prelude::*;
+
+/// How stale the record of when the key was last accepted may get before
+/// it’s worth rewriting the credentials file.
+const REVALIDATE_AFTER_HOURS: i64 = 24;
+
+impl GscClient {
+    /// Records, once per run, that the server accepted `creds`, if they
+    /// came from the credentials file and the last record of it is over a
+    /// day old. Failing to is only worth a note at `-vvv`, since the
+    /// request itself went fine.
+    pub(crate) fn note_key_accepted(&self, creds: &Credentials) {
+        if self.key_accepted.replace(true)
+            || self.config.offline()
+            || !matches!(self.config.get_api_key(), Ok(None))
+            || recently_validated(creds)
+        {
+            return;
+        }
+
+        let result = self.config.get_credentials_file().and_then(|path| {
+            // Don’t overwrite a key that another `gsc auth` just saved, or
+            // a record that another run just made:
+            let mut current = Credentials::read(path)?;
+            if current.same_key(creds) && !recently_validated(&current) {
+                current.mark_validated();
+                current.write(path)?;
+            }
+            Ok(())
+        });
+
+        if let Err(e) = result {
+            ve3!("Could not record when the API key was last accepted: {}", e);
+        }
+    }
+
+    /// When `creds` most likely expired, if the server rotates keys and
+    /// they are older than it keeps them.
+    pub(crate) fn key_expiry(&self, creds: &Credentials) -> Option<UtcDateTime> {
+        let days = self.server_info()?.key_rotation_days?;
+        let expiry = creds.saved()? + chrono::Duration::days(days.into());
+
+        if expiry <= chrono::Utc::now() {
+            Some(expiry.into())
+        } else {
+            None
+        }
+    }
+
+    /// The error for the server rejecting `creds`: that they expired, if
+    /// that seems likely, or just that they were rejected.
+    pub(crate) fn key_rejected(&self, creds: &Credentials) -> ErrorKind {
+        let username = creds.username().to_owned();
+
+        match self.key_expiry(creds) {
+            Some(expiry) => ErrorKind::ApiKeyExpired(username, expiry, self.config.get_web_url()),
+            None => ErrorKind::ApiKeyRejected(username),
+        }
+    }
+}
+
+fn recently_validated(creds: &Credentials) -> bool {
+    let threshold = chrono::Duration::hours(REVALIDATE_AFTER_HOURS);
+    creds
+        .validated()
+        .is_some_and(|validated| chrono::Utc::now() - validated < threshold)
+}
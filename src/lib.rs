@@ -1,29 +1,60 @@
 #![recursion_limit = "256"]
 
-use percent_encoding as enc;
-
 use reqwest::blocking;
+use serde::de::DeserializeOwned;
 
-use std::cell::{Cell, RefCell};
+use std::cell::{Cell, OnceCell, RefCell};
 use std::collections::{hash_map, HashMap};
 use std::fs;
-use std::io::{self, BufRead, BufReader};
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
 use std::iter;
 use std::ops::Deref;
 use std::path::Path;
 use std::process::Command;
 
+pub mod archive;
+pub mod args;
 pub mod config;
 pub mod credentials;
+pub mod datespec;
 pub mod errors;
+pub mod filename;
 pub mod messages;
+pub mod render;
 
-mod args;
+mod audit;
+mod cache;
 mod cmd;
+mod diff;
+mod fetch;
+mod handshake;
+mod history;
+mod hooks;
+mod key_expiry;
+mod media_type;
+mod memo;
+mod output;
+mod paging;
+mod porcelain;
+mod retry;
+mod role;
+mod scheduler;
+mod suggest;
+mod throttle;
+mod trace;
+mod update;
+mod upload_guard;
 mod util;
 
+#[cfg(feature = "test-support")]
+pub mod test_support;
+
 const API_KEY_COOKIE: &str = "gsc_api_key";
 
+/// Self evals scoring at least this much on yes/no and scale items should
+/// say why; graders see too many blank ones.
+const EXPLAIN_SCORES_FROM: f64 = 0.5;
+
 pub mod prelude {
     pub use thousands::Separable;
     pub use vlog::*;
@@ -31,9 +62,10 @@ pub mod prelude {
     pub use crate::{
         args::{
             traits::{Qualified, RemotePath, Unqualified},
-            types::{CpArg, HwOptQual, HwQual, RemoteDestination, RemotePattern},
+            types::{CpArg, EvalScore, HwOptQual, HwQual, RemoteDestination, RemotePattern},
         },
         errors::{Error, ErrorKind, JsonStatus, RemoteFiles, ResultExt},
+        messages::ReadMessage,
         GscClient,
     };
 
@@ -43,27 +75,64 @@ pub mod prelude {
 pub use prelude::*;
 
 use self::credentials::*;
-use self::util::{hanging, Percentage};
+use self::throttle::Throttled;
+use self::util::{HumanDuration, Percentage, Summary};
 use crate::errors::ApiKeyExplanation;
 use std::cmp::Ordering;
 
 pub struct GscClient {
     http: blocking::Client,
+    cache: Option<cache::HttpCache>,
+    memo: memo::ResponseMemo,
+    tracer: Option<trace::Tracer>,
     config: config::Config,
     submission_uris: RefCell<HashMap<String, Vec<Option<String>>>>,
+    server_info: OnceCell<Option<messages::ServerInfo>>,
+    user_role: OnceCell<messages::UserRole>,
     had_warning: Cell<bool>,
+    attempts: Cell<usize>,
+    failures: RefCell<Vec<String>>,
+    renewed_credentials: RefCell<Option<Credentials>>,
+    api_key_credentials: OnceCell<Credentials>,
+    key_accepted: Cell<bool>,
+    output: RefCell<Option<output::OutputFile>>,
 }
 
 impl GscClient {
     pub fn new() -> Result<Self> {
         let mut config = config::Config::new();
         config.load_dotfile()?;
+        Self::with_config(config)
+    }
+
+    /// Creates a client from a fully processed configuration.
+    pub fn with_config(config: config::Config) -> Result<Self> {
+        let output = config
+            .get_output_file()
+            .map(output::OutputFile::create)
+            .transpose()?;
 
         Ok(GscClient {
-            http: blocking::Client::new(),
+            http: config.build_http_client()?,
+            cache: config
+                .get_cache_dir()
+                .map(|dir| cache::HttpCache::new(dir, config.offline())),
+            memo: memo::ResponseMemo::default(),
+            tracer: config
+                .get_trace_file()
+                .map(trace::Tracer::new)
+                .transpose()?,
             config,
             submission_uris: RefCell::new(HashMap::new()),
+            server_info: OnceCell::new(),
+            user_role: OnceCell::new(),
             had_warning: Cell::new(false),
+            attempts: Cell::new(0),
+            failures: RefCell::new(Vec::new()),
+            renewed_credentials: RefCell::new(None),
+            api_key_credentials: OnceCell::new(),
+            key_accepted: Cell::new(false),
+            output: RefCell::new(output),
         })
     }
 
@@ -79,12 +148,34 @@ impl GscClient {
         self.had_warning.get()
     }
 
-    pub fn admin_csv(&self) -> Result<()> {
-        let uri = format!("{}/api/grades.csv", self.config.get_endpoint());
-        let request = self.http.get(&uri);
-        let mut response = self.send_request(request)?;
-        response.copy_to(&mut io::stdout())?;
-        Ok(())
+    /// Says prominently, on stderr, whom `--user` has us acting as, so that
+    /// nobody changes a student’s files thinking they’re their own.
+    pub fn announce_on_behalf(&self) {
+        if let Some(user) = self.config.get_on_behalf() {
+            let banner = format!("*** Acting as {} (--user) ***", user);
+            if self.config.use_color() {
+                ve1!("\x1b[1;7m{}\x1b[0m", banner);
+            } else {
+                ve1!("{}", banner);
+            }
+        }
+    }
+
+    /// Summarizes the failures that were warned about along the way, if
+    /// there were any among several operations.
+    pub fn print_failure_summary(&self) {
+        let failures = self.failures.borrow();
+        let attempts = self.attempts.get().max(failures.len());
+
+        if failures.is_empty() || attempts < 2 {
+            return;
+        }
+
+        ve1!("");
+        ve1!("{} of {} operations failed:", failures.len(), attempts);
+        for failure in failures.iter() {
+            ve1!(" - {}", failure.lines().next().unwrap_or(""));
+        }
     }
 
     pub fn admin_divorce(&self, username: &str, hw: usize) -> Result<()> {
@@ -95,6 +186,7 @@ impl GscClient {
         let uri = self.get_uri_for_submission(username, hw, &creds)?;
         let request = self.http.patch(&uri).json(&message);
         self.send_request(request)?;
+        self.audit(audit::Entry::new(audit::Action::Divorce, username).hw(hw));
 
         v1!("Okay");
         Ok(())
@@ -106,11 +198,15 @@ impl GscClient {
         let request = self.http.post(&uri).json(&message);
         v2!("Creating user {} with role {}...", name, role);
         let response = self.send_request(request)?;
+        self.audit(
+            audit::Entry::new(audit::Action::AddUser, name)
+                .payload(serde_json::json!({ "role": role })),
+        );
 
         if self.config.json_output() {
-            v1!("{}", response.text()?);
+            self.print_output(response.text()?)?;
         } else {
-            let result: messages::UserShort = response.json()?;
+            let result: messages::UserShort = response.message()?;
             v1!("Created user {}.", result.name);
         }
 
@@ -118,10 +214,29 @@ impl GscClient {
     }
 
     pub fn admin_del_user(&self, name: &str) -> Result<()> {
+        if !self.config.assume_yes() {
+            if self.config.get_unattended().is_some() {
+                let question = format!("Really delete user ‘{}’?", name);
+                Err(self.config.cannot_ask(question))?;
+            }
+
+            print!(
+                "This deletes user ‘{}’ and all of their submissions.\n\
+                 Type the username to confirm: ",
+                name
+            );
+            io::stdout().flush()?;
+
+            if read_stdin_line()?.trim() != name {
+                Err(ErrorKind::DeletionNotConfirmed(name.to_owned()))?;
+            }
+        }
+
         let uri = self.user_uri(name);
         let request = self.http.delete(&uri);
         v2!("Deleting user {}...", name);
         self.send_request(request)?;
+        self.audit(audit::Entry::new(audit::Action::DelUser, name));
         Ok(())
     }
 
@@ -131,19 +246,49 @@ impl GscClient {
         hw: usize,
         datetime: &str,
         eval: bool,
+        from_now: bool,
     ) -> Result<()> {
+        let spec: datespec::DateSpec = datetime.parse().chain_err(|| {
+            format!(
+                "Could not understand date ‘{}’ (try e.g. ‘2024-05-01 23:59’, ‘+3d’, \
+                 or ‘next friday 23:59’)",
+                datetime
+            )
+        })?;
+
+        let creds = self.load_credentials()?;
+        let uri = self.get_uri_for_submission(username, hw, &creds)?;
+
+        let date = if from_now || !spec.is_relative() {
+            spec.resolve(&messages::UtcDateTime::now())
+        } else {
+            let request = self.http.get(&uri);
+            let current: messages::Submission = self.send_request(request)?.message()?;
+            spec.resolve(if eval { &current.eval_date } else { &current.due_date })
+        };
+        v1!("Interpreting ‘{}’ as {}", datetime, date);
+
         let mut message = messages::SubmissionChange::default();
         if eval {
-            message.eval_date = Some(datetime.parse()?);
+            message.eval_date = Some(date);
         } else {
-            message.due_date = Some(datetime.parse()?);
+            message.due_date = Some(date);
         }
 
-        let creds = self.load_credentials()?;
-        let uri = self.get_uri_for_submission(username, hw, &creds)?;
         let request = self.http.patch(&uri).json(&message);
         let response = self.send_request(request)?;
-        let submission: messages::Submission = response.json()?;
+        let submission: messages::Submission = response.message()?;
+
+        let payload = if eval {
+            serde_json::json!({ "eval_date": submission.eval_date })
+        } else {
+            serde_json::json!({ "due_date": submission.due_date })
+        };
+        self.audit(
+            audit::Entry::new(audit::Action::Extend, username)
+                .hw(hw)
+                .payload(payload),
+        );
 
         if eval {
             v2!("Set eval date set to {}", submission.eval_date);
@@ -154,11 +299,17 @@ impl GscClient {
         Ok(())
     }
 
-    pub fn admin_permalink(&self, username: &str, hw: usize, number: usize) -> Result<()> {
+    pub fn admin_permalink(
+        &self,
+        username: &str,
+        hw: usize,
+        number: usize,
+        open: bool,
+    ) -> Result<()> {
         let creds = self.load_credentials()?;
         let uri = self.get_uri_for_submission(username, hw, &creds)?;
         let request = self.http.get(&uri);
-        let submission: messages::Submission = self.send_request(request)?.json()?;
+        let submission: messages::Submission = self.send_request(request)?.message()?;
 
         let uri = format!(
             "{}{}/{}/self",
@@ -167,11 +318,14 @@ impl GscClient {
             number
         );
         let request = self.http.get(&uri);
-        let self_eval: messages::SelfEval = self.send_request(request)?.json()?;
+        let self_eval: messages::SelfEval = self.send_request(request)?.message()?;
 
-        v1!("{}", self_eval.permalink);
+        if !open {
+            v1!("{}", self_eval.permalink);
+            return Ok(());
+        }
 
-        Ok(())
+        self.show_url(&self.permalink_url(&self_eval.permalink), false)
     }
 
     pub fn admin_partners(&self, username: &str, hw: usize) -> Result<()> {
@@ -179,7 +333,7 @@ impl GscClient {
         let uri = self.get_uri_for_submission(username, hw, &creds)?;
         let request = self.http.get(&uri);
         let response = self.send_request(request)?;
-        let submission: messages::Submission = response.json()?;
+        let submission: messages::Submission = response.message()?;
 
         let mut buf = submission.owner1.name.clone();
         if let Some(owner2) = &submission.owner2 {
@@ -197,16 +351,17 @@ impl GscClient {
         let uri = self.get_uri_for_submission(username, hw, &creds)?;
         let request = self.http.get(&uri);
         let response = self.send_request(request)?;
-        let submission: messages::Submission = response.json()?;
+        let submission: messages::Submission = response.message()?;
 
         let uri = format!("{}{}", self.config.get_endpoint(), submission.evals_uri);
         let request = self.http.get(&uri);
         let response = self.send_request(request)?;
-        Ok(response.json()?)
+        response.message()
     }
 
     fn set_grade(
         &self,
+        action: audit::Action,
         username: &str,
         hw: usize,
         eval: &messages::EvalShort,
@@ -221,10 +376,20 @@ impl GscClient {
             score,
             explanation: comment.to_owned(),
             status: messages::GraderEvalStatus::Ready,
+            regrade_reason: None,
         };
         request = request.json(&message);
         let response = self.send_request(request)?;
-        let result: messages::GraderEval = response.json()?;
+        let result: messages::GraderEval = response.message()?;
+        self.audit(
+            audit::Entry::new(action, username)
+                .hw(hw)
+                .payload(serde_json::json!({
+                    "sequence": eval.sequence,
+                    "score": result.score,
+                    "comment": comment,
+                })),
+        );
 
         v2!(
             "Set user {}’s hw{}, item {} to {}",
@@ -250,7 +415,7 @@ impl GscClient {
             .filter(|eval| eval.sequence == number)
             .next()
             .ok_or_else(|| ErrorKind::EvalItemDoesNotExist(hw, number))?;
-        self.set_grade(username, hw, &eval, score, comment)
+        self.set_grade(audit::Action::SetGrade, username, hw, &eval, score, comment)
     }
 
     pub fn admin_set_auto(
@@ -266,7 +431,7 @@ impl GscClient {
             .filter(|eval| eval.eval_type == messages::EvalType::Informational)
             .last()
             .chain_err(|| ErrorKind::NoInformationalEvalItem)?;
-        self.set_grade(username, hw, &eval, score, comment)
+        self.set_grade(audit::Action::SetAuto, username, hw, &eval, score, comment)
     }
 
     pub fn admin_set_exam(
@@ -285,46 +450,40 @@ impl GscClient {
         }];
         let request = self.http.patch(&uri).json(&message);
         let response = self.send_request(request)?;
+        self.audit(audit::Entry::new(audit::Action::SetExam, username).payload(
+            serde_json::json!({
+                "exam": number,
+                "points": points,
+                "possible": possible,
+            }),
+        ));
         self.print_results(response)
     }
 
-    pub fn admin_submissions(&self, hw: usize) -> Result<()> {
-        let uri = format!("{}/api/submissions/hw{}", self.config.get_endpoint(), hw);
-        let request = self.http.get(&uri);
-        let result = self.send_request(request)?;
-        let submissions: Vec<messages::SubmissionShort> = result.json()?;
-
-        let mut table = tabular::Table::new(" {:>}  {:<}  {:<}");
+    /// Authenticates, prompting for the API key until the server accepts
+    /// one, or if `stdin` is set or standard input isn’t a terminal,
+    /// reading a single key from stdin.
+    pub fn auth(&mut self, username: &str, stdin: bool) -> Result<()> {
+        let username = &username.to_lowercase();
 
-        for submission in &submissions {
-            table.add_row(
-                tabular::Row::new()
-                    .with_cell(submission.id)
-                    .with_cell(&submission.owner1.name)
-                    .with_cell(
-                        submission
-                            .owner2
-                            .as_ref()
-                            .map(|o| o.name.as_str())
-                            .unwrap_or(""),
-                    ),
-            );
+        if self.config.no_input() && !stdin {
+            let question = format!("Enter API key for {}", username);
+            Err(self.config.cannot_ask(question))?;
         }
 
-        v1!("{}", table);
-
-        Ok(())
-    }
-
-    pub fn auth(&mut self, username: &str) -> Result<()> {
-        let username = &username.to_lowercase();
+        let stdin = stdin || !io::stdin().is_terminal();
         let uri = self.user_uri(username);
+        self.check_server_version()?;
 
         loop {
-            let api_key = prompt_secret("Enter API key", username)?;
+            let api_key = if stdin {
+                read_stdin_line()?
+            } else {
+                prompt_secret("Enter API key", username)?
+            };
             let api_key = check_api_key(&api_key, self.config())?;
 
-            let creds = Credentials::new(username, API_KEY_COOKIE, api_key);
+            let mut creds = Credentials::new(username, API_KEY_COOKIE, api_key);
             ve3!("> Sending request to {}", uri);
             let response = self
                 .http
@@ -332,16 +491,16 @@ impl GscClient {
                 .header(reqwest::header::COOKIE, creds.to_header()?)
                 .send()?;
 
-            match self.handle_response(response) {
-                Ok(_) => {
-                    v2!("Authenticated as {}", username);
+            match self.handle_response(&reqwest::Method::GET, response) {
+                Ok(response) => {
+                    creds.mark_validated();
                     self.save_credentials(&creds)?;
+                    let user: messages::User = response.message()?;
+                    v1!("{}", render::auth_summary(&user, self.config.get_endpoint()));
                     return Ok(());
                 }
-                Err(e @ Error(ErrorKind::ServerError(JsonStatus { status: 401, .. }), _)) => {
-                    eprintln!("{}", e)
-                }
-                Err(e) => return Err(e.into()),
+                Err(e) if e.status() == Some(401) && !stdin => eprintln!("{}", e),
+                Err(e) => return Err(e),
             }
         }
     }
@@ -386,6 +545,7 @@ impl GscClient {
         };
 
         let policy = &mut self.config.get_overwrite_policy();
+        let mut summary = Summary::transfer("Copied");
 
         match dst_type {
             DstType::File => {
@@ -403,7 +563,9 @@ impl GscClient {
                 } else {
                     let src_file = self.fetch_one_matching_filename(src_rpat)?;
                     if policy.confirm_overwrite(|| dst.display())? {
-                        self.download_file(src_rpat.hw, &src_file, dst)?;
+                        summary.add(self.download_file(src_rpat.hw, &src_file, dst)?);
+                    } else {
+                        summary.skip();
                     }
                 }
             }
@@ -417,10 +579,10 @@ impl GscClient {
 
                 if src_rpat.is_whole_hw() {
                     soft_create_dir(dst)?;
-                    self.download_hw(policy, src_rpat.hw, dst)?;
+                    self.download_hw(policy, &mut summary, src_rpat.hw, dst)?;
                 } else {
                     let src_file = self.fetch_one_matching_filename(src_rpat)?;
-                    self.download_file(src_rpat.hw, &src_file, dst)?;
+                    summary.add(self.download_file(src_rpat.hw, &src_file, dst)?);
                 }
             }
 
@@ -428,7 +590,7 @@ impl GscClient {
                 for src_rpat in src_rpats {
                     self.try_warn(|| {
                         if src_rpat.is_whole_hw() {
-                            self.download_hw(policy, src_rpat.hw, dst)?;
+                            self.download_hw(policy, &mut summary, src_rpat.hw, dst)?;
                         } else {
                             let src_metas = self.fetch_nonempty_matching_file_list(src_rpat)?;
 
@@ -436,22 +598,30 @@ impl GscClient {
                                 let mut file_dst = dst.to_owned();
                                 file_dst.push(&src_meta.name);
                                 if self.is_okay_to_write_local(policy, &file_dst)? {
-                                    self.download_file(src_rpat.hw, &src_meta, &file_dst)?;
+                                    let bytes = self
+                                        .download_file(src_rpat.hw, &src_meta, &file_dst)
+                                        .map_err(|error| error.processing(&src_meta))?;
+                                    summary.add(bytes);
+                                } else {
+                                    summary.skip();
                                 }
                             }
                         }
 
                         Ok(())
-                    });
+                    })?;
                 }
             }
         }
 
-        v2!("Done.");
+        self.finish_summary(&summary);
         Ok(())
     }
 
-    fn download_file(&self, hw: usize, meta: &messages::FileMeta, dst: &Path) -> Result<()> {
+    /// Downloads one file, returning how many bytes it had.
+    fn download_file(&self, hw: usize, meta: &messages::FileMeta, dst: &Path) -> Result<u64> {
+        filename::check(&meta.name)?;
+
         let mut file = fs::OpenOptions::new()
             .write(true)
             .create(true)
@@ -466,25 +636,25 @@ impl GscClient {
             meta.name,
             dst.display()
         );
-        let mut response = self.send_request(request)?;
-        response.copy_to(&mut file)?;
+        let response = self.stream_request(request)?;
+        let rate = self.config.get_limit_rate();
+        let bytes = io::copy(&mut Throttled::new(response, rate), &mut file)?;
 
-        if cfg!(unix) {
-            let mtime = &meta.upload_time;
-            ve2!(
-                "Setting modification time of ‘{}’ to {}",
-                dst.display(),
-                mtime.touch_t_fmt()
-            );
-            set_file_mtime(dst, mtime)?;
-        }
+        let mtime = &meta.upload_time;
+        ve2!(
+            "Setting modification time of ‘{}’ to {}",
+            dst.display(),
+            mtime
+        );
+        set_file_mtime(dst, mtime)?;
 
-        Ok(())
+        Ok(bytes)
     }
 
     fn download_hw(
         &self,
         policy: &mut config::OverwritePolicy,
+        summary: &mut Summary,
         hw: usize,
         dst: &Path,
     ) -> Result<()> {
@@ -501,7 +671,12 @@ impl GscClient {
             soft_create_dir(&file_dst)?;
             file_dst.push(&src_meta.name);
             if self.is_okay_to_write_local(policy, &file_dst)? {
-                self.download_file(hw, &src_meta, &file_dst)?;
+                let bytes = self
+                    .download_file(hw, &src_meta, &file_dst)
+                    .map_err(|error| error.processing(&src_meta))?;
+                summary.add(bytes);
+            } else {
+                summary.skip();
             }
         }
 
@@ -521,16 +696,50 @@ impl GscClient {
             }
         }
 
+        for src in &srcs {
+            self.check_upload(src)?;
+        }
+
+        self.warn_if_late(dst.hw)?;
+        self.run_pre_push_hook(dst.hw)?;
+
+        let mut summary = Summary::transfer("Copied");
+
         if dst.is_whole_hw() {
+            let template = self.config.get_upload_name_template();
+            let mut uploads = Vec::new();
+            let mut names = HashMap::new();
+
             for src in srcs {
-                let filename = match self.get_base_filename(&src) {
-                    Ok(s) => s,
-                    Err(e) => {
-                        self.warn(e);
-                        continue;
-                    }
+                let filename = match self.try_warn(|| self.get_base_filename(&src).map(Some))? {
+                    Some(filename) => filename,
+                    None => continue,
                 };
-                self.upload_file(src, &dst.with_name(filename))?;
+
+                let filename = match template {
+                    Some(template) => template.apply(filename),
+                    None => filename.to_owned(),
+                };
+
+                // Renaming mustn’t send two files to the same name:
+                if template.is_some() {
+                    if let Some(other) = names.insert(filename.clone(), src) {
+                        Err(ErrorKind::DuplicateFileName(
+                            filename.clone(),
+                            other.display().to_string(),
+                            src.display().to_string(),
+                        ))?;
+                    }
+                }
+
+                uploads.push((src, filename));
+            }
+
+            for (src, filename) in uploads {
+                let bytes = self
+                    .upload_file(src, &dst.with_name(filename))
+                    .map_err(|error| error.processing(src.display()))?;
+                summary.add(bytes);
             }
         } else {
             let src = if srcs.len() == 1 {
@@ -546,30 +755,182 @@ impl GscClient {
                 _ => Err(Error::dest_pat_is_multiple(dst, &dsts))?,
             };
 
-            self.upload_file(src, &dst.with_name(filename))?;
+            summary.add(self.upload_file(src, &dst.with_name(filename))?);
         }
 
-        v2!("Done.");
+        self.finish_summary(&summary);
         Ok(())
     }
 
-    fn upload_file(&self, src: &Path, dst: &RemotePattern) -> Result<()> {
+    /// Uploads one file, returning how many bytes it had.
+    fn upload_file(&self, src: &Path, dst: &RemotePattern) -> Result<u64> {
         let src_file = fs::File::open(&src)?;
-        let encoded_dst = enc::utf8_percent_encode(&dst.name, ENCODE_SET);
+        let bytes = src_file.metadata()?.len();
+        let rate = self.config.get_limit_rate();
+        let body = blocking::Body::sized(Throttled::new(src_file, rate), bytes);
+        v2!("Uploading ‘{}’ -> ‘{}’...", src.display(), dst);
+        self.upload_body(&src.display().to_string(), body, bytes, dst)
+    }
+
+    /// Checks whether local file `src` looks wrong to upload, and if so,
+    /// refuses or warns according to the `upload_guard` setting.
+    fn check_upload(&self, src: &Path) -> Result<()> {
+        if self.config.get_upload_guard() == config::UploadGuard::Off {
+            return Ok(());
+        }
+
+        // A file that can’t be read fails later, with a better message:
+        let max_size = self.config.get_max_upload_size();
+        match upload_guard::inspect(src, max_size) {
+            Ok(reason) => self.guard_upload(&src.display().to_string(), reason),
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// Like [`GscClient::check_upload`], but for contents that aren’t in a
+    /// local file: `src` names them, and `start` is their first few bytes.
+    fn check_upload_contents(&self, src: &str, bytes: u64, start: &[u8]) -> Result<()> {
+        if self.config.get_upload_guard() == config::UploadGuard::Off {
+            return Ok(());
+        }
+
+        let max_size = self.config.get_max_upload_size();
+        let reason = upload_guard::inspect_contents(Path::new(src), bytes, start, max_size);
+        self.guard_upload(src, reason)
+    }
+
+    fn guard_upload(&self, src: &str, reason: Option<String>) -> Result<()> {
+        if let Some(reason) = reason {
+            if self.refuses_uploads() {
+                Err(ErrorKind::SuspiciousUpload(src.to_owned(), reason))?;
+            } else {
+                self.warn(format!("Warning: uploading ‘{}’ anyway: {}.", src, reason));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn refuses_uploads(&self) -> bool {
+        self.config.get_upload_guard() == config::UploadGuard::Refuse && !self.config.force()
+    }
+
+    /// Uploads `body`, which is `bytes` long and comes from `src`, to
+    /// `dst`, returning `bytes`. Every upload goes through here, so that
+    /// the server’s verdict on forbidden files is always heeded; callers
+    /// check the contents themselves first, before anything is uploaded.
+    fn upload_body(
+        &self,
+        src: &str,
+        body: impl Into<blocking::Body>,
+        bytes: u64,
+        dst: &RemotePattern,
+    ) -> Result<u64> {
+        let encoded_dst = filename::encode(filename::check(&dst.name)?);
         let base_uri = self.get_uri_for_submission_files(dst.hw)?;
         let uri = format! {"{}/{}", base_uri, encoded_dst};
-        let request = self.http.put(&uri).body(src_file);
-        v2!("Uploading ‘{}’ -> ‘{}’...", src.display(), dst);
-        self.send_request(request)?;
+        let request = self.http.put(&uri).body(body);
+        let response = self.send_request(request)?;
+        let meta: Option<messages::FileMeta> = response.message().ok();
+
+        // The file is uploaded either way, so this is only worth a warning:
+        if let Some(Err(error)) = meta.as_ref().map(|meta| self.fix_media_type(meta)) {
+            self.fail(error)?;
+        }
+
+        let entry = history::Entry::new(history::Action::Upload, dst.hw)
+            .file(dst.name.as_str())
+            .bytes(bytes)
+            .server_meta(meta.as_ref());
+        self.record(entry);
+
+        // Only the server knows which files it won’t take, so those have
+        // to be taken back:
+        if let Some(meta) = meta.filter(|meta| meta.purpose == messages::FilePurpose::Forbidden) {
+            let reason = "the server marks it as forbidden";
+
+            match self.config.get_upload_guard() {
+                config::UploadGuard::Off => (),
+                _ if self.refuses_uploads() => {
+                    let uri = format!("{}{}", self.config.get_endpoint(), meta.uri);
+                    self.send_request(self.http.delete(&uri))?;
+                    Err(ErrorKind::SuspiciousUpload(
+                        src.to_owned(),
+                        format!("{}, so it was deleted again", reason),
+                    ))?;
+                }
+                _ => self.warn(format!(
+                    "Warning: uploaded ‘{}’ anyway, but {}.",
+                    src, reason
+                )),
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    /// Changes the media type of just-uploaded file `meta` to the one
+    /// given with `--media-type`, or else to the one for its extension if
+    /// the server didn’t know it.
+    fn fix_media_type(&self, meta: &messages::FileMeta) -> Result<()> {
+        let media_type = match self.config.get_media_type() {
+            Some(media_type) if media_type != meta.media_type => media_type,
+            Some(_) => return Ok(()),
+            None => match media_type::correct(&meta.name, &meta.media_type) {
+                Some(media_type) => media_type,
+                None => return Ok(()),
+            },
+        };
+
+        let message = messages::FileMetaChange {
+            media_type: Some(media_type.to_owned()),
+            ..messages::FileMetaChange::default()
+        };
+
+        let uri = format!("{}{}", self.config.get_endpoint(), meta.uri);
+        let request = self.http.patch(&uri).json(&message);
+        v2!("Setting media type of remote file ‘{}’ to {}...", meta, media_type);
+        self.send_request(request)
+            .map_err(|error| error.processing(meta))?;
+
+        Ok(())
+    }
+
+    fn fetch_submission(&self, hw: usize) -> Result<messages::Submission> {
+        let (who, creds) = self.load_effective_credentials()?;
+        let uri = self.get_uri_for_submission(&who, hw, &creds)?;
+        let request = self.http.get(&uri);
+        self.send_request(request)?.message()
+    }
+
+    fn warn_if_late(&self, hw: usize) -> Result<()> {
+        let submission = self.fetch_submission(hw)?;
+
+        let status = submission.status;
+        let time_left = submission.due_date.time_until();
+        let window = chrono::Duration::minutes(self.config.get_deadline_warning());
+
+        if status.is_self_eval()
+            || status == messages::SubmissionStatus::Closed
+            || time_left <= chrono::Duration::zero()
+        {
+            self.warn(format!("*** Warning: hw{} is past due ({}). ***", hw, status));
+        } else if time_left < window {
+            self.warn(format!(
+                "*** Warning: hw{} is due in {}. ***",
+                hw,
+                HumanDuration(time_left)
+            ));
+        }
 
         Ok(())
     }
 
     fn get_base_filename<'a>(&self, path: &'a Path) -> Result<&'a str> {
         match path.file_name() {
-            None => Err(ErrorKind::BadLocalPath(path.to_owned()).into()),
+            None => Err(ErrorKind::BadLocalPath(path.to_owned())),
             Some(os_str) => match os_str.to_str() {
-                None => Err(ErrorKind::FilenameNotUtf8(path.to_owned()).into()),
+                None => Err(ErrorKind::FilenameNotUtf8(path.to_owned())),
                 Some(s) => Ok(s),
             },
         }
@@ -633,6 +994,10 @@ impl GscClient {
 
         self.clear_credentials()?;
 
+        if let Some(cache) = &self.cache {
+            cache.clear()?;
+        }
+
         Ok(())
     }
 
@@ -675,18 +1040,18 @@ impl GscClient {
                         table.add_heading(String::new());
                     }
 
-                    print!("{}", table);
+                    self.with_output(|out| write!(out, "{}", table))?;
                 } else {
                     for file in files {
                         let uri = format!("{}{}", self.config.get_endpoint(), file.uri);
                         let request = self.http.get(&uri);
                         let mut response = self.send_request(request)?;
-                        response.copy_to(&mut io::stdout())?;
+                        self.with_output(|out| io::copy(&mut response, out).map(drop))?;
                     }
                 }
 
                 Ok(())
-            })
+            })?
         }
 
         Ok(())
@@ -697,7 +1062,7 @@ impl GscClient {
         let uri = self.get_uri_for_submission(&who, hw, &creds)?;
         let request = self.http.get(&uri);
         let response = self.send_request(request)?;
-        let submission: messages::Submission = response.json()?;
+        let submission: messages::Submission = response.message()?;
 
         let uri = format!(
             "{}{}/{}",
@@ -707,43 +1072,88 @@ impl GscClient {
         );
         let request = self.http.get(&uri);
         let response = self.send_request(request)?;
-        let eval: messages::Eval = response.json()?;
-
-        v1!(
-            "Homework {} item {} ({:?}, {})",
-            hw,
-            number,
-            eval.eval_type,
-            Percentage(eval.value)
-        );
-        v1!("{}", hanging(&eval.prompt));
-
-        if let Some(ref self_eval) = eval.self_eval {
-            v1!("Self evaluation:   {}", Percentage(self_eval.score));
-            v1!("{}", hanging(&self_eval.explanation));
-        }
-
-        if let Some(ref grader_eval) = eval.grader_eval {
-            v1!("Grader evaluation: {}", Percentage(grader_eval.score));
-            v1!("{}", hanging(&grader_eval.explanation));
-        }
+        let eval: messages::Eval = response.message()?;
 
+        v1!("{}", render::eval(hw, number, &eval, self.config.get_width()));
         Ok(())
     }
 
-    pub fn set_eval(&self, hw: usize, number: usize, score: f64, explanation: &str) -> Result<()> {
+    /// Sets the self evaluation of item `number` of homework `hw`, after
+    /// checking that `score` fits the item’s type and, unless forced, that
+    /// self evaluation is open. Claiming a high score with no explanation
+    /// needs confirmation, or `allow_empty`.
+    pub fn set_eval(
+        &self,
+        hw: usize,
+        number: usize,
+        score: EvalScore,
+        explanation: &str,
+        allow_empty: bool,
+    ) -> Result<()> {
         let (who, creds) = self.load_effective_credentials()?;
         let uri = self.get_uri_for_submission(&who, hw, &creds)?;
         let request = self.http.get(&uri);
         let response = self.send_request(request)?;
-        let submission: messages::Submission = response.json()?;
+        let submission: messages::Submission = response.message()?;
+
+        if !submission.status.is_self_eval() && !self.config.force() {
+            Err(match submission.status {
+                messages::SubmissionStatus::Closed => {
+                    ErrorKind::SelfEvalClosed(hw, submission.eval_date)
+                }
+                _ => ErrorKind::SelfEvalNotOpen(hw, submission.due_date),
+            })?;
+        }
 
         let uri = format!(
-            "{}{}/{}/self",
+            "{}{}/{}",
             self.config.get_endpoint(),
             submission.evals_uri,
             number
         );
+        let request = self.http.get(&uri);
+        let eval: messages::Eval = self.send_request(request)?.message()?;
+        let score = score.for_type(eval.eval_type).ok_or_else(|| {
+            ErrorKind::EvalScoreMismatch(score.to_string(), hw, number, eval.eval_type)
+        })?;
+
+        let needs_explanation = matches!(
+            eval.eval_type,
+            messages::EvalType::Boolean | messages::EvalType::Scale
+        ) && score >= EXPLAIN_SCORES_FROM;
+
+        if needs_explanation && explanation.trim().is_empty() && !allow_empty {
+            if self.config.get_unattended().is_some() {
+                Err(ErrorKind::EmptyExplanation(hw, number, Percentage(score).to_string()))?;
+            }
+
+            let question = format!(
+                "Set hw{} item {} to {} with no explanation?",
+                hw,
+                number,
+                Percentage(score)
+            );
+            if !self.config.confirm(&question)? {
+                v1!("Nothing was set.");
+                return Ok(());
+            }
+        }
+
+        if let Some(user) = self.config.get_on_behalf() {
+            let question = format!(
+                "Really set {}’s hw{} item {} self eval to {}?",
+                user,
+                hw,
+                number,
+                Percentage(score)
+            );
+            if !self.config.confirm(&question)? {
+                v1!("Nothing was set.");
+                return Ok(());
+            }
+        }
+
+        let uri = format!("{}/self", uri);
         let mut request = self.http.put(&uri);
         let message = messages::SelfEval {
             uri,
@@ -753,7 +1163,7 @@ impl GscClient {
         };
         request = request.json(&message);
         let response = self.send_request(request)?;
-        let result: messages::SelfEval = response.json()?;
+        let result: messages::SelfEval = response.message()?;
 
         v2!(
             "Set hw{} item {} self eval to {}",
@@ -762,6 +1172,10 @@ impl GscClient {
             Percentage(result.score)
         );
 
+        let entry = history::Entry::new(history::Action::EvalSet, hw)
+            .detail(format!("item {}: {}", number, Percentage(result.score)));
+        self.record(entry);
+
         Ok(())
     }
 
@@ -770,9 +1184,14 @@ impl GscClient {
         let uri = self.user_uri(&who);
         let request = self.http.get(&uri);
         let response = self.send_request_with_credentials(request, &creds)?;
-        let user: messages::User = response.json()?;
-        self.print_partner_status(&user, "");
-        Ok(())
+        let user: messages::User = response.message()?;
+
+        if self.config.porcelain_output() {
+            return self.print_porcelain_partners(&user);
+        }
+
+        self.print_partner_status(&user);
+        self.resolve_incoming_partner_requests(&user)
     }
 
     pub fn partner_request(&self, hw: usize, them: &str) -> Result<()> {
@@ -800,6 +1219,8 @@ impl GscClient {
             assignment_number: hw,
             user: them.to_owned(),
             status: op,
+            direction: None,
+            timestamp: None,
         }];
 
         let request = self.http.patch(&uri).json(&message);
@@ -808,22 +1229,68 @@ impl GscClient {
     }
 
     pub fn rm(&self, pats: &[RemotePattern]) -> Result<()> {
+        let mut files = Vec::new();
+
         for rpat in pats {
             self.try_warn(|| {
-                let files = self.fetch_nonempty_matching_file_list(&rpat)?;
+                files.extend(self.fetch_nonempty_matching_file_list(&rpat)?);
+                Ok(())
+            })?;
+        }
 
-                for file in files {
-                    let uri = format!("{}{}", self.config.get_endpoint(), file.uri);
-                    let request = self.http.delete(&uri);
-                    v2!("Deleting remote file ‘hw{}:{}’...", rpat.hw, file.name);
-                    self.send_request(request)?;
+        if files.is_empty() {
+            return Ok(());
+        }
+
+        // Acting for someone else, ask even with ‘--yes’:
+        let on_behalf = self.config.get_on_behalf();
+        if !self.config.assume_yes() || on_behalf.is_some() {
+            let whose = on_behalf.map(|user| format!("{}’s ", user));
+            let question = match files.as_slice() {
+                [file] => format!(
+                    "Really delete {}‘hw{}:{}’?",
+                    whose.unwrap_or_default(),
+                    file.hw,
+                    file.name
+                ),
+                _ => {
+                    for file in &files {
+                        v1!("  hw{}:{}", file.hw, file.name);
+                    }
+                    format!(
+                        "Really delete {}{} files?",
+                        whose.unwrap_or_else(|| "these ".to_owned()),
+                        files.len()
+                    )
                 }
+            };
 
+            if !self.config.confirm(&question)? {
+                v1!("Nothing was deleted.");
+                return Ok(());
+            }
+        }
+
+        let mut summary = Summary::new("Deleted");
+
+        for file in files {
+            self.try_warn(|| {
+                let uri = format!("{}{}", self.config.get_endpoint(), file.uri);
+                let request = self.http.delete(&uri);
+                v2!("Deleting remote file ‘hw{}:{}’...", file.hw, file.name);
+                self.send_request(request)
+                    .map_err(|error| error.processing(&file))?;
+
+                summary.add(file.byte_count as u64);
+                let entry = history::Entry::new(history::Action::Rm, file.hw)
+                    .file(file.name)
+                    .bytes(file.byte_count as u64);
+                self.record(entry);
                 Ok(())
-            });
+            })?;
         }
 
-        v2!("Done.");
+        self.finish_summary(&summary);
         Ok(())
     }
 
@@ -832,66 +1299,59 @@ impl GscClient {
         let uri = self.get_uri_for_submission(&who, number, &creds)?;
         let request = self.http.get(&uri);
         let response = self.send_request(request)?;
+        let incoming = self.fetch_incoming_partner_requests(&who, &creds, number)?;
 
-        let submission: messages::Submission = response.json()?;
-        let in_evaluation = submission.status.is_self_eval();
-        let quota_remaining = submission.quota_remaining();
+        if self.config.json_output() {
+            let submission: serde_json::Value = response.json()?;
+            let json = serde_json::json!({
+                "submission": submission,
+                "incoming_partner_requests": incoming,
+            });
+            self.print_output(serde_json::to_string_pretty(&json)?)?;
+            return Ok(());
+        }
 
-        let mut table = tabular::Table::new("  {:<}  {:<}");
-        table.add_row(
-            tabular::Row::new()
-                .with_cell("Submission status:")
-                .with_cell(submission.status),
-        );
+        let submission: messages::Submission = response.message()?;
+
+        if self.config.porcelain_output() {
+            let key = |key| porcelain::Record::new().field(key);
+            let owners = iter::once(&submission.owner1).chain(&submission.owner2);
+
+            let mut records = vec![key("hw").field(number)];
+            records.extend(owners.map(|owner| key("owner").field(&owner.name)));
+            records.extend(vec![
+                key("status").name(&submission.status),
+                key("eval_status").name(&submission.eval_status),
+                key("open_date").date(&submission.open_date),
+                key("due_date").date(&submission.due_date),
+                key("eval_date").date(&submission.eval_date),
+                key("last_modified").date(&submission.last_modified),
+                key("bytes_used").field(submission.bytes_used),
+                key("bytes_quota").field(submission.bytes_quota),
+            ]);
+            records.extend(incoming.iter().map(porcelain::partner_request));
+
+            for record in records {
+                self.print_output(record)?;
+            }
 
-        if in_evaluation {
-            table.add_row(
-                tabular::Row::new()
-                    .with_cell("Evaluation status:")
-                    .with_cell(submission.eval_status),
-            );
+            return Ok(());
         }
 
-        table
-            .add_row(
-                tabular::Row::new()
-                    .with_cell("Open date:")
-                    .with_cell(submission.open_date),
-            )
-            .add_row(
-                tabular::Row::new()
-                    .with_cell("Submission due date:")
-                    .with_cell(submission.due_date),
-            )
-            .add_row(
-                tabular::Row::new()
-                    .with_cell("Self-eval due date:")
-                    .with_cell(submission.eval_date),
-            )
-            .add_row(
-                tabular::Row::new()
-                    .with_cell("Last modified:")
-                    .with_cell(submission.last_modified),
-            )
-            .add_row(
-                tabular::Row::new()
-                    .with_cell("Quota remaining:")
-                    .with_cell(format!(
-                        "{:.1}% ({} of {} bytes used)",
-                        quota_remaining,
-                        submission.bytes_used.separate_with_commas(),
-                        submission.bytes_quota.separate_with_commas()
-                    )),
-            );
+        let now = messages::UtcDateTime::now();
+        let width = self.config.get_width();
+        v1!("{}", render::submission(number, &submission, &now, width));
 
-        let mut owners = submission.owner1.name.clone();
-        if let Some(owner2) = &submission.owner2 {
-            owners += " and ";
-            owners += &owner2.name;
+        let files = self.fetch_matching_file_list(&RemotePattern::just_hw(number))?;
+        let late = render::late_files(&files, &submission.due_date);
+        if !late.is_empty() {
+            v1!("{}", late);
         }
 
-        v1!("hw{} ({})", number, owners);
-        v1!("{}", table);
+        for p in &incoming {
+            ve1!("*** {} has requested to partner with you on hw{}. ***", p.user, number);
+            ve1!("Use ‘gsc partner accept hw{} {}’ to accept.", number, p.user);
+        }
 
         Ok(())
     }
@@ -902,99 +1362,81 @@ impl GscClient {
         let request = self.http.get(&uri);
         let response = self.send_request_with_credentials(request, &creds)?;
 
-        let user: messages::User = response.json()?;
-
-        v1!("Status for {}:\n", user.name);
-
-        if user
-            .submissions
-            .iter()
-            .any(|s| s.status != messages::SubmissionStatus::Future)
-        {
-            let mut table = tabular::Table::new("    hw{:<}: {:>}    {:<}");
+        let user: messages::User = response.message()?;
 
+        if self.config.porcelain_output() {
             for s in &user.submissions {
-                let grade = match s.status {
-                    messages::SubmissionStatus::Future => continue,
-                    messages::SubmissionStatus::Closed => format!("{:.1}%", 100.0 * s.grade),
-                    _ => String::new(),
-                };
-
-                table.add_row(
-                    tabular::Row::new()
-                        .with_cell(s.assignment_number)
-                        .with_cell(grade)
-                        .with_cell(s.status),
-                );
+                if s.status != messages::SubmissionStatus::Future {
+                    let record = porcelain::submission(s.assignment_number, s.status, s.grade);
+                    self.print_output(record)?;
+                }
             }
 
-            v1!("  Submissions:\n{}", table);
-        }
-
-        if !user.exam_grades.is_empty() {
-            let mut table = tabular::Table::new("    ex{:<}: {:>}%    ({:<} / {:<})");
-
             for e in &user.exam_grades {
-                let grade = format!("{:.1}", 100.0 * e.points as f64 / e.possible as f64);
-                table.add_row(
-                    tabular::Row::new()
-                        .with_cell(e.number)
-                        .with_cell(grade)
-                        .with_cell(e.points)
-                        .with_cell(e.possible),
-                );
+                let record = porcelain::Record::new()
+                    .field("exam")
+                    .field(e.number)
+                    .field(e.points)
+                    .field(e.possible);
+                self.print_output(record)?;
             }
 
-            v1!("  Exam grades:\n{}", table);
+            return self.print_porcelain_partners(&user);
         }
 
+        v1!("Status for {}:\n", user.name);
+        self.print_user_record(&user, false);
+
         if !user.partner_requests.is_empty() {
-            self.print_partner_status(&user, "  ");
             v1!("Partner requests can be managed with the ‘gsc partner’ command.");
         }
 
         Ok(())
     }
 
-    pub fn whoami(&self) -> Result<()> {
-        let uri = format!("{}/api/whoami", self.config.get_endpoint());
-        let request = self.http.get(&uri);
-        let response = self.send_request(request)?;
-        let text = response.text()?;
-        v1!("{}", text);
-        Ok(())
-    }
-
     // Helper methods
 
-    fn fetch_raw_file_list(&self, hw: usize) -> Result<blocking::Response> {
+    /// The files in homework `hw`, or just the first `limit` of them, as
+    /// `FileMeta`s or as whatever else `T` the caller wants them as.
+    fn fetch_file_list<T: DeserializeOwned>(&self, hw: usize, limit: Option<usize>) -> Result<Vec<T>> {
         let uri = self.get_uri_for_submission_files(hw)?;
-        let request = self.http.get(&uri);
-        self.send_request(request)
+        let creds = self.load_credentials()?;
+        self.fetch_list(&uri, &creds, limit)
     }
 
     fn fetch_exact_file_name(&self, hw: usize, name: &str) -> Result<messages::FileMeta> {
-        let response = self.fetch_raw_file_list(hw)?;
-
-        let files: Vec<messages::FileMeta> = response.json()?;
+        let files: Vec<messages::FileMeta> = self.fetch_file_list(hw, None)?;
 
         files
             .into_iter()
             .filter(|file| file.name == name)
             .next()
-            .ok_or_else(|| ErrorKind::NoSuchRemoteFile(RemotePattern::hw_name(hw, name)).into())
+            .ok_or_else(|| ErrorKind::NoSuchRemoteFile(RemotePattern::hw_name(hw, name)))
     }
 
     fn fetch_matching_file_list(&self, rpat: &RemotePattern) -> Result<Vec<messages::FileMeta>> {
+        self.fetch_first_matching_files(rpat, None)
+    }
+
+    /// Like `fetch_matching_file_list`, but stops after `limit` matches.
+    fn fetch_first_matching_files(
+        &self,
+        rpat: &RemotePattern,
+        limit: Option<usize>,
+    ) -> Result<Vec<messages::FileMeta>> {
         let matcher = glob(&rpat.name)?;
-        let response = self.fetch_raw_file_list(rpat.hw)?;
+        let uri = self.get_uri_for_submission_files(rpat.hw)?;
+        let creds = self.load_credentials()?;
 
-        let files: Vec<messages::FileMeta> = response.json()?;
+        let mut files = Vec::new();
+        self.for_each_listed(&uri, &creds, false, |file: messages::FileMeta| {
+            if matcher.is_match(&file.name) {
+                files.push(file);
+            }
+            limit.map_or(true, |limit| files.len() < limit)
+        })?;
 
-        Ok(files
-            .into_iter()
-            .filter(|file| matcher.is_match(&file.name))
-            .collect())
+        Ok(files)
     }
 
     fn fetch_nonempty_matching_file_list(
@@ -1026,13 +1468,46 @@ impl GscClient {
         creds: &Credentials,
     ) -> Result<Vec<messages::SubmissionShort>> {
         let uri = self.user_uri(user) + "/submissions";
-        let request = self.http.get(&uri);
-        let response = self.send_request_with_credentials(request, &creds)?;
-        response
-            .json()
+        self.fetch_list(&uri, creds, None)
             .chain_err(|| "Could not understand response from server")
     }
 
+    /// The homework that `current` refers to: `default_hw` if it is set,
+    /// and otherwise the open homework that is due soonest.
+    pub fn current_hw(&self) -> Result<usize> {
+        if let Some(hw) = self.config.get_default_hw() {
+            return Ok(hw);
+        }
+
+        let (who, creds) = self.load_effective_credentials()?;
+        let endpoint = self.config.get_endpoint();
+
+        let uris: Vec<String> = self
+            .fetch_submissions(&who, &creds)?
+            .into_iter()
+            .filter(|s| {
+                matches!(
+                    s.status,
+                    messages::SubmissionStatus::Open | messages::SubmissionStatus::Extended
+                )
+            })
+            .map(|s| format!("{}{}", endpoint, s.uri))
+            .collect();
+
+        let submissions: Vec<messages::Submission> =
+            self.fetch_in_parallel(&uris, |fetcher, uri| fetcher.get(uri)?.message())?;
+
+        let hw = submissions
+            .into_iter()
+            .filter(|s| s.due_date.time_until() > chrono::Duration::zero())
+            .min_by(|a, b| a.due_date.cmp(&b.due_date))
+            .map(|s| s.assignment_number)
+            .ok_or(ErrorKind::NoCurrentHomework)?;
+
+        ve2!("The current homework is hw{}.", hw);
+        Ok(hw)
+    }
+
     fn get_submission_uris(&self, user: &str, creds: &Credentials) -> Result<Vec<Option<String>>> {
         let submissions = self.fetch_submissions(user, creds)?;
         let mut result = Vec::new();
@@ -1066,7 +1541,7 @@ impl GscClient {
 
         match uris.get(number) {
             Some(Some(uri)) => Ok(uri.to_owned()),
-            _ => Err(ErrorKind::UnknownHomework(number).into()),
+            _ => Err(ErrorKind::UnknownHomework(number)),
         }
     }
 
@@ -1076,17 +1551,41 @@ impl GscClient {
             .map(|uri| uri + "/files")
     }
 
-    fn handle_response(&self, response: blocking::Response) -> Result<blocking::Response> {
-        if response.status().is_success() {
-            Ok(response)
-        } else {
-            let error = response.json()?;
-            Err(ErrorKind::ServerError(error))?
+    fn handle_response(
+        &self,
+        method: &reqwest::Method,
+        response: blocking::Response,
+    ) -> Result<blocking::Response> {
+        check_response(method, response)
+    }
+
+    /// The credentials to send, including a key that the user gave this
+    /// run after the server rejected the saved one.
+    fn current_credentials(&self) -> Result<Credentials> {
+        let creds = self.load_credentials()?;
+        match &*self.renewed_credentials.borrow() {
+            Some(renewed) if renewed.username() == creds.username() => Ok(renewed.clone()),
+            _ => Ok(creds),
         }
     }
 
+    /// The credentials to send: from `$GSC_API_KEY` or `--api-key-file`
+    /// if given, checked once per run, and otherwise read afresh from the
+    /// credentials file, which another `gsc auth` may have changed.
     fn load_credentials(&self) -> Result<Credentials> {
-        Credentials::read(self.config.get_credentials_file()?)
+        if let Some(creds) = self.api_key_credentials.get() {
+            return Ok(creds.clone());
+        }
+
+        match self.config.get_api_key()? {
+            Some((username, api_key)) => {
+                let api_key = check_api_key(&api_key, &self.config)?;
+                let creds = Credentials::new(username, API_KEY_COOKIE, api_key);
+                let _ = self.api_key_credentials.set(creds.clone());
+                Ok(creds)
+            }
+            None => Credentials::read(self.config.get_credentials_file()?),
+        }
     }
 
     fn load_effective_credentials(&self) -> Result<(String, Credentials)> {
@@ -1115,30 +1614,23 @@ impl GscClient {
         Ok(request)
     }
 
-    fn print_partner_status(&self, user: &messages::User, indent: &str) {
+    fn print_partner_status(&self, user: &messages::User) {
         if user.partner_requests.is_empty() {
             ve1!("No outstanding partner requests.");
         } else {
-            let mut table = tabular::Table::new("    {:<} {:<}");
-
-            for p in &user.partner_requests {
-                use self::messages::PartnerRequestStatus::*;
-                let hw = format!("hw{}:", p.assignment_number);
-                let message = match p.status {
-                    Outgoing => format!("sent to {}", p.user),
-                    Incoming => format!("received from {}", p.user),
-                    _ => continue,
-                };
-
-                table.add_row(tabular::Row::new().with_cell(hw).with_cell(message));
-            }
+            v1!("{}", render::partner_requests(&user.partner_requests, ""));
+        }
+    }
 
-            v1!("{}Partner requests:\n{}", indent, table);
+    fn print_user_record(&self, user: &messages::User, show_future: bool) {
+        let record = render::user_record(user, show_future);
+        if !record.is_empty() {
+            v1!("{}", record);
         }
     }
 
     fn print_results(&self, response: blocking::Response) -> Result<()> {
-        let results: Vec<messages::JsonResult> = response.json()?;
+        let results: Vec<messages::JsonResult> = response.message()?;
         self.print_results_helper(&results);
         Ok(())
     }
@@ -1162,31 +1654,228 @@ impl GscClient {
         self.send_request_with_credentials(req_builder, &creds)
     }
 
+    /// Like `send_request`, but for a response read as a stream, such as
+    /// a file download, which the memo and the cache must not read first.
+    fn stream_request(&self, req_builder: blocking::RequestBuilder) -> Result<blocking::Response> {
+        let creds = self.load_credentials()?;
+        self.send_request_via(req_builder, &creds, true)
+    }
+
     fn send_request_with_credentials(
+        &self,
+        req_builder: blocking::RequestBuilder,
+        creds: &Credentials,
+    ) -> Result<blocking::Response> {
+        self.send_request_via(req_builder, creds, false)
+    }
+
+    /// Sends a request with `creds`. If the caller will read the response
+    /// as a stream, `streamed` bypasses the memo and the cache, which would
+    /// read it all first. (Offline, the cache still answers.)
+    fn send_request_via(
         &self,
         mut req_builder: blocking::RequestBuilder,
         creds: &Credentials,
+        streamed: bool,
     ) -> Result<blocking::Response> {
+        // The caller may have loaded the credentials before they were
+        // renewed:
+        let renewed = self.renewed_credentials.borrow().clone();
+        let creds = match &renewed {
+            Some(renewed) if renewed.username() == creds.username() => renewed,
+            _ => creds,
+        };
+
+        self.check_server_version()?;
         req_builder = self.add_credentials(req_builder, &creds)?;
         let request = req_builder.build()?;
-        ve3!("> Sending request to {}", request.url());
-        let response = self.http.execute(request)?;
-        self.handle_response(response)
+        let method = request.method().clone();
+        let retry = request.try_clone();
+        let response = self.execute(request, creds, streamed)?;
+
+        match self.handle_response(&method, response) {
+            Ok(response) => {
+                self.note_key_accepted(creds);
+                Ok(response)
+            }
+            Err(error) if error.status() == Some(401) => {
+                match retry.filter(|_| renewed.is_none() && self.can_reauth()) {
+                    Some(retry) => self.reauth_and_retry(retry, creds, streamed),
+                    None => Err(self.key_rejected(creds)),
+                }
+            }
+            result => result,
+        }
+    }
+
+    fn execute(
+        &self,
+        request: blocking::Request,
+        creds: &Credentials,
+        streamed: bool,
+    ) -> Result<blocking::Response> {
+        if streamed {
+            let cache = self.cache.as_ref().filter(|_| self.config.offline());
+            return execute_request(
+                &self.http,
+                &self.config,
+                cache,
+                self.tracer.as_ref(),
+                Some(creds.username()),
+                request,
+            );
+        }
+
+        self.memo.send(creds.username(), request, |request| {
+            execute_request(
+                &self.http,
+                &self.config,
+                self.cache.as_ref(),
+                self.tracer.as_ref(),
+                Some(creds.username()),
+                request,
+            )
+        })
+    }
+
+    /// Whether, when the server rejects the API key, we can ask the user
+    /// for a new one. Not if the key came from somewhere other than the
+    /// credentials file, since that’s where a new one would go.
+    fn can_reauth(&self) -> bool {
+        self.config.reauth()
+            && self.config.get_unattended().is_none()
+            && io::stderr().is_terminal()
+            && matches!(self.config.get_api_key(), Ok(None))
+    }
+
+    /// Prompts for a new API key and resends `request` with it, saving the
+    /// key if the server accepts it.
+    fn reauth_and_retry(
+        &self,
+        mut request: blocking::Request,
+        creds: &Credentials,
+        streamed: bool,
+    ) -> Result<blocking::Response> {
+        let username = creds.username();
+        match self.key_expiry(creds) {
+            Some(expiry) => ve1!(
+                "The API key for {} likely expired on {}; get a new one at {}.",
+                username,
+                expiry,
+                self.config.get_web_url()
+            ),
+            None => ve1!(
+                "The server rejected the API key for {}; it may have expired or been revoked.",
+                username
+            ),
+        }
+
+        let api_key = prompt_secret("Enter a new API key (or nothing to give up)", username)?;
+        if api_key.trim().is_empty() {
+            Err(self.key_rejected(creds))?;
+        }
+
+        let api_key = check_api_key(&api_key, &self.config)?;
+        let mut new_creds = Credentials::new(username, API_KEY_COOKIE, api_key);
+        request
+            .headers_mut()
+            .insert(reqwest::header::COOKIE, new_creds.to_header()?);
+
+        let method = request.method().clone();
+        let response = self.handle_response(&method, self.execute(request, &new_creds, streamed)?)?;
+
+        new_creds.mark_validated();
+        self.save_credentials(&new_creds)?;
+        v2!("Authenticated as {}", username);
+        *self.renewed_credentials.borrow_mut() = Some(new_creds);
+
+        Ok(response)
     }
 
-    fn try_warn<F, R>(&self, f: F) -> R
+    /// Runs one of several independent operations, warning about its
+    /// failure and going on—unless in strict mode.
+    fn try_warn<F, R>(&self, f: F) -> Result<R>
     where
         F: FnOnce() -> Result<R>,
         R: Default,
     {
-        f().unwrap_or_else(|error| {
-            self.warn(error);
-            R::default()
-        })
+        self.attempts.set(self.attempts.get() + 1);
+
+        match f() {
+            Ok(result) => Ok(result),
+            Err(error) => {
+                self.fail(error)?;
+                Ok(R::default())
+            }
+        }
+    }
+
+    /// Records a failure that leaves the command partly done, which is an
+    /// error in strict mode and a warning otherwise.
+    fn fail(&self, error: impl Into<Error>) -> Result<()> {
+        let error = error.into();
+
+        if self.config.strict() {
+            return Err(error);
+        }
+
+        self.failures.borrow_mut().push(error.to_string());
+        self.warn(error);
+        Ok(())
+    }
+
+    /// Prints `text` as the result of the command: to the `-o` file if
+    /// there is one, or else to standard output.
+    fn print_output(&self, text: impl std::fmt::Display) -> Result<()> {
+        match &mut *self.output.borrow_mut() {
+            Some(file) => writeln!(file, "{}", text)?,
+            None => v1!("{}", text),
+        }
+
+        Ok(())
+    }
+
+    /// Like `print_output`, but for output that `write` writes as is.
+    fn with_output(&self, write: impl FnOnce(&mut dyn Write) -> io::Result<()>) -> Result<()> {
+        match &mut *self.output.borrow_mut() {
+            Some(file) => write(file)?,
+            None => write(&mut io::stdout())?,
+        }
+
+        Ok(())
+    }
+
+    /// Moves the `-o` file, if any, into place. Until this is called, the
+    /// output is kept in a temporary file, so a command that fails leaves
+    /// no partial output behind.
+    pub fn finish_output(&self) -> Result<()> {
+        match self.output.borrow_mut().take() {
+            Some(file) => file.commit(),
+            None => Ok(()),
+        }
+    }
+
+    /// Ends a bulk operation with its summary, or for a single file, just
+    /// “Done.” when verbose.
+    fn finish_summary(&self, summary: &Summary) {
+        if summary.count() > 1 {
+            v1!("{}", summary);
+        } else {
+            v2!("Done.");
+        }
+    }
+
+    /// Prints a table, cutting its flexible column short to fit the output.
+    fn print_table(&self, table: &render::Table) {
+        v1!("{}", render::table(table, self.config.get_width()));
     }
 
     fn warn<T: std::fmt::Display>(&self, msg: T) {
-        ve1!("{}", msg);
+        if self.config.use_color() {
+            ve1!("\x1b[33m{}\x1b[0m", msg);
+        } else {
+            ve1!("{}", msg);
+        }
         self.had_warning.set(true);
     }
 }
@@ -1202,19 +1891,53 @@ impl messages::FilePurpose {
     }
 }
 
-const ENCODE_SET: &'static enc::AsciiSet = &enc::CONTROLS
-    .add(b' ')
-    .add(b'"')
-    .add(b'#')
-    .add(b'<')
-    .add(b'>')
-    .add(b'`')
-    .add(b'?')
-    .add(b'{')
-    .add(b'}')
-    .add(b'%')
-    .add(b'/')
-    .add(b'+');
+/// Sends a request, with the credentials of `user` if any, going through
+/// the cache if it’s a GET, retrying as configured, and tracing if
+/// enabled. The response is returned unchecked.
+fn execute_request(
+    http: &blocking::Client,
+    config: &config::Config,
+    cache: Option<&cache::HttpCache>,
+    tracer: Option<&trace::Tracer>,
+    user: Option<&str>,
+    request: blocking::Request,
+) -> Result<blocking::Response> {
+    let send = |request: blocking::Request| match tracer {
+        None => config.get_retry_policy().execute(http, request),
+        Some(tracer) => {
+            tracer.request(&request)?;
+            let response = config.get_retry_policy().execute(http, request)?;
+            tracer.response(response)
+        }
+    };
+
+    match cache {
+        Some(cache) if request.method() == reqwest::Method::GET => cache.send(user, request, send),
+        _ if config.offline() => Err(ErrorKind::Offline(request.method().to_string()))?,
+        _ => send(request),
+    }
+}
+
+fn check_response(
+    method: &reqwest::Method,
+    response: blocking::Response,
+) -> Result<blocking::Response> {
+    if response.status().is_success() {
+        Ok(response)
+    } else if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let wait = retry::retry_after(&response).map(|d| d.as_secs());
+        Err(ErrorKind::RateLimited(wait))?
+    } else {
+        let method = method.to_string();
+        let url = response.url().to_string();
+        let contents = response.json()?;
+        Err(ErrorKind::ServerError {
+            contents,
+            method,
+            url,
+        })?
+    }
+}
 
 fn glob(pattern: &str) -> Result<globset::GlobMatcher> {
     let real_pattern = if pattern.is_empty() { "*" } else { pattern };
@@ -1227,6 +1950,12 @@ fn prompt_secret(prompt: &str, username: &str) -> Result<String> {
     Ok(secret)
 }
 
+fn read_stdin_line() -> Result<String> {
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+    Ok(line)
+}
+
 fn check_api_key(api_key: &str, config: &config::Config) -> Result<String> {
     const KEY_LEN: usize = 40;
 
@@ -1240,12 +1969,14 @@ fn check_api_key(api_key: &str, config: &config::Config) -> Result<String> {
         return reasons.final_straw("It’s empty!");
     }
 
-    let api_key = api_key.trim_matches(|c: char| c.is_ascii_whitespace());
+    let api_key = unwrap_pasted_api_key(api_key);
+    let api_key = api_key.as_str();
 
     let len = api_key.len();
 
     if len == 0 {
-        return reasons.final_straw("It’s nothing but whitespace.");
+        return reasons
+            .final_straw("Nothing is left once the whitespace, quotes, and labels are removed.");
     }
 
     match len.cmp(&KEY_LEN) {
@@ -1276,6 +2007,70 @@ fn check_api_key(api_key: &str, config: &config::Config) -> Result<String> {
     Ok(result)
 }
 
+/// Peels off what tends to come along when an API key is copied and
+/// pasted: whitespace, quotes, an “API key:” label, the rest of the
+/// `gsc_api_key=…` cookie it came from, and URL encoding. Each correction
+/// is explained at `-v`.
+fn unwrap_pasted_api_key(pasted: &str) -> String {
+    const QUOTES: &[(char, char)] = &[
+        ('"', '"'),
+        ('\'', '\''),
+        ('`', '`'),
+        ('“', '”'),
+        ('‘', '’'),
+        ('<', '>'),
+    ];
+
+    let mut key = pasted
+        .trim_matches(|c: char| c.is_ascii_whitespace())
+        .to_owned();
+
+    loop {
+        let before = key.clone();
+
+        if let Some(start) = key.find(&format!("{}=", API_KEY_COOKIE)) {
+            let value = &key[start + API_KEY_COOKIE.len() + 1..];
+            let value = value.split(';').next().unwrap_or_default();
+            ve2!(
+                "Using just the value of the ‘{}’ cookie that you pasted.",
+                API_KEY_COOKIE
+            );
+            key = value.to_owned();
+        }
+
+        if let Some((label, value)) = key.rsplit_once(':') {
+            if label.to_lowercase().contains("key") {
+                ve2!("Ignoring the label ‘{}:’ before the key.", label.trim());
+                key = value.to_owned();
+            }
+        }
+
+        if let Some(&(open, close)) = QUOTES
+            .iter()
+            .find(|(open, close)| key.len() > 1 && key.starts_with(*open) && key.ends_with(*close))
+        {
+            ve2!("Removing the {}…{} around the key.", open, close);
+            key = key[open.len_utf8()..key.len() - close.len_utf8()].to_owned();
+        }
+
+        if key.contains('%') {
+            let decoded = percent_encoding::percent_decode_str(&key).decode_utf8_lossy();
+            if decoded != key {
+                ve2!("Decoding the URL-encoded characters in the key.");
+                key = decoded.into_owned();
+            }
+        }
+
+        key = key
+            .trim_matches(|c: char| c.is_ascii_whitespace())
+            .to_owned();
+
+        if key == before {
+            return key;
+        }
+    }
+}
+
 fn soft_create_dir(path: &Path) -> Result<()> {
     match fs::create_dir(path) {
         Ok(_) => Ok(()),
@@ -1287,6 +2082,29 @@ fn soft_create_dir(path: &Path) -> Result<()> {
 }
 
 fn set_file_mtime(dst: &Path, mtime: &messages::UtcDateTime) -> Result<()> {
+    let native = fs::OpenOptions::new()
+        .write(true)
+        .open(dst)
+        .and_then(|file| file.set_modified(mtime.to_system_time()));
+
+    match native {
+        Ok(()) => Ok(()),
+        Err(error) if cfg!(unix) => {
+            ve3!(
+                "Could not set modification time directly ({}); trying touch(1)",
+                error
+            );
+            set_file_mtime_with_touch(dst, mtime)
+                .map_err(|_| ErrorKind::SetModTimeFailed(dst.to_owned(), error.to_string()))
+        }
+        Err(error) => Err(ErrorKind::SetModTimeFailed(
+            dst.to_owned(),
+            error.to_string(),
+        )),
+    }
+}
+
+fn set_file_mtime_with_touch(dst: &Path, mtime: &messages::UtcDateTime) -> Result<()> {
     let mtime = mtime.touch_t_fmt().to_string();
     let output = Command::new("touch")
         .arg("-m")
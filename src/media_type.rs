@@ -0,0 +1,38 @@
+//! Media types for the kinds of files that servers tend not to recognize.
+//! When the server gives an uploaded file only a generic type, and its
+//! extension is in `MEDIA_TYPES`, we set the type from there, so that the
+//! web interface can highlight it.
+
+/// What servers fall back on for files that they don’t recognize.
+const GENERIC: &[&str] = &["text/plain", "application/octet-stream"];
+
+const MEDIA_TYPES: &[(&str, &str)] = &[
+    ("rkt", "text/x-racket"),
+    ("rktd", "text/x-racket"),
+    ("rktl", "text/x-racket"),
+    ("scm", "text/x-scheme"),
+    ("sld", "text/x-scheme"),
+    ("sls", "text/x-scheme"),
+    ("ss", "text/x-scheme"),
+    ("lisp", "text/x-common-lisp"),
+    ("clj", "text/x-clojure"),
+    ("hs", "text/x-haskell"),
+    ("ml", "text/x-ocaml"),
+    ("mli", "text/x-ocaml"),
+    ("rs", "text/x-rust"),
+    ("md", "text/markdown"),
+];
+
+/// The media type that a file named `name` should have, if the server
+/// gave it `given`, or `None` if `given` will do.
+pub fn correct(name: &str, given: &str) -> Option<&'static str> {
+    if !GENERIC.contains(&given) {
+        return None;
+    }
+
+    let (_, ext) = name.rsplit_once('.')?;
+    MEDIA_TYPES
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(ext))
+        .map(|&(_, media_type)| media_type)
+}
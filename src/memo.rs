@@ -0,0 +1,116 @@
+//! Remembering the JSON responses to GET requests for the rest of one
+//! command, so that a command that looks at the same submission or file
+//! list more than once (say, to check before copying and then to copy)
+//! asks the server only once.
+//!
+//! Any other request may change what the server would say, so it makes
+//! us forget everything. Other responses, like file downloads and long
+//! listings, are passed through untouched, since they may be large and
+//! are read as streams.
+
+use crate::prelude::*;
+
+use reqwest::{blocking, header, Method, StatusCode};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::Read;
+
+/// The largest response body worth keeping around; anything bigger is
+/// cheaper to stream again than to hold onto.
+const MAX_MEMO_BYTES: u64 = 256 * 1024;
+
+/// Remembered responses, keyed by the user whose credentials the request
+/// was sent with and its URL. (A request on behalf of another user names
+/// that user in its URL.)
+#[derive(Debug, Default)]
+pub(crate) struct ResponseMemo {
+    responses: RefCell<HashMap<(String, String), Memo>>,
+}
+
+#[derive(Debug)]
+struct Memo {
+    status: StatusCode,
+    headers: header::HeaderMap,
+    body: Vec<u8>,
+}
+
+impl ResponseMemo {
+    /// Sends `request` for `user` using `send`, unless it’s a GET that
+    /// already has a remembered response.
+    pub fn send<F>(
+        &self,
+        user: &str,
+        request: blocking::Request,
+        send: F,
+    ) -> Result<blocking::Response>
+    where
+        F: FnOnce(blocking::Request) -> Result<blocking::Response>,
+    {
+        if request.method() != Method::GET {
+            self.clear();
+            return send(request);
+        }
+
+        let key = (user.to_owned(), request.url().to_string());
+
+        if let Some(memo) = self.responses.borrow().get(&key) {
+            ve3!("< Reusing the response from earlier in this command");
+            return Ok(memo.to_response());
+        }
+
+        let mut response = send(request)?;
+
+        let is_json = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|ct| ct.starts_with("application/json"));
+
+        let is_small = response
+            .content_length()
+            .is_some_and(|len| len <= MAX_MEMO_BYTES);
+
+        if !response.status().is_success() || !is_json || !is_small {
+            return Ok(response);
+        }
+
+        let mut body = Vec::new();
+        response.read_to_end(&mut body)?;
+        let memo = Memo {
+            status: response.status(),
+            headers: response.headers().clone(),
+            body,
+        };
+
+        let response = memo.to_response();
+        self.responses.borrow_mut().insert(key, memo);
+        Ok(response)
+    }
+
+    pub fn clear(&self) {
+        self.responses.borrow_mut().clear();
+    }
+}
+
+impl GscClient {
+    /// Forgets the responses remembered so far, for a command that polls
+    /// and so wants to see changes that it didn’t make itself.
+    pub(crate) fn forget_responses(&self) {
+        self.memo.clear();
+    }
+}
+
+impl Memo {
+    fn to_response(&self) -> blocking::Response {
+        let mut builder = http::Response::builder().status(self.status);
+        if let Some(headers) = builder.headers_mut() {
+            *headers = self.headers.clone();
+        }
+
+        builder
+            .body(self.body.clone())
+            .expect("remembered response is well formed")
+            .into()
+    }
+}
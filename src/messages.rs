@@ -1,17 +1,144 @@
+use crate::filename;
+
 use chrono::format::{DelayedFormat, StrftimeItems};
-use chrono::{offset, DateTime};
+use chrono::{offset, DateTime, NaiveDate, NaiveDateTime};
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::blocking;
+use serde::de::{self as serde_de, DeserializeOwned, DeserializeSeed, SeqAccess, Visitor};
 use serde::Serializer;
 use serde_derive::{Deserialize, Serialize};
+use vlog::{ve3, verbose_elog};
+
+use std::fmt;
+use std::io::{self, BufRead};
+use std::marker::PhantomData;
+use std::sync::RwLock;
+
+/// Parses a message from the server. Fields that this version doesn’t
+/// know about are ignored, and logged at `-vvv`, so that an older client
+/// keeps working when the server adds something. (Likewise, each enum has
+/// an `Unknown` variant for values that we don’t recognize.)
+pub fn from_slice<T: DeserializeOwned>(bytes: &[u8]) -> serde_json::Result<T> {
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let value = serde_ignored::deserialize(&mut deserializer, |path| {
+        ve3!("Ignoring unknown field ‘{}’ in server response", path)
+    })?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+/// Parses a JSON array from the server one element at a time, passing
+/// each to `f` as soon as it’s read. Unlike `from_slice`, this never holds
+/// the whole array in memory, either as text or as a `Vec`, which matters
+/// for listings that cover a whole class.
+pub fn for_each_in_array<T, R, F>(reader: R, f: F) -> serde_json::Result<()>
+where
+    T: DeserializeOwned,
+    R: BufRead,
+    F: FnMut(T),
+{
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let mut ignored =
+        |path: serde_ignored::Path| ve3!("Ignoring unknown field ‘{}’ in server response", path);
+    EachElement(f, PhantomData).deserialize(serde_ignored::Deserializer::new(
+        &mut deserializer,
+        &mut ignored,
+    ))?;
+    deserializer.end()
+}
+
+struct EachElement<T, F>(F, PhantomData<fn(T)>);
+
+impl<'de, T, F> DeserializeSeed<'de> for EachElement<T, F>
+where
+    T: DeserializeOwned,
+    F: FnMut(T),
+{
+    type Value = ();
+
+    fn deserialize<D: serde_de::Deserializer<'de>>(self, de: D) -> Result<(), D::Error> {
+        de.deserialize_seq(self)
+    }
+}
+
+impl<'de, T, F> Visitor<'de> for EachElement<T, F>
+where
+    T: DeserializeOwned,
+    F: FnMut(T),
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(element) = seq.next_element()? {
+            (self.0)(element);
+        }
+        Ok(())
+    }
+}
+
+/// Reading a response body as a server message, via `from_slice`, or as
+/// a stream of them, via `for_each_in_array`.
+pub trait ReadMessage {
+    fn message<T: DeserializeOwned>(self) -> crate::errors::Result<T>;
+
+    fn for_each_message<T, F>(self, f: F) -> crate::errors::Result<()>
+    where
+        T: DeserializeOwned,
+        F: FnMut(T);
+}
+
+impl ReadMessage for blocking::Response {
+    fn message<T: DeserializeOwned>(self) -> crate::errors::Result<T> {
+        Ok(from_slice(&self.bytes()?)?)
+    }
+
+    fn for_each_message<T, F>(self, f: F) -> crate::errors::Result<()>
+    where
+        T: DeserializeOwned,
+        F: FnMut(T),
+    {
+        Ok(for_each_in_array(io::BufReader::new(self), f)?)
+    }
+}
 
-#[derive(Clone, Deserialize, Debug)]
+#[derive(Clone, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct UtcDateTime(DateTime<offset::Utc>);
 
+/// The time zone that `UtcDateTime`s are displayed in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplayZone {
+    Local,
+    Fixed(offset::FixedOffset),
+}
+
+lazy_static! {
+    static ref DISPLAY_ZONE: RwLock<DisplayZone> = RwLock::new(DisplayZone::Local);
+}
+
+impl DisplayZone {
+    pub fn utc() -> Self {
+        DisplayZone::Fixed(offset::FixedOffset::east(0))
+    }
+}
+
+/// Sets the time zone for displaying dates for the rest of the run.
+pub fn set_display_zone(zone: DisplayZone) {
+    *DISPLAY_ZONE.write().unwrap() = zone;
+}
+
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "snake_case")]
 pub enum EvalType {
     Boolean,
     Scale,
     Informational,
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Deserialize, Debug)]
@@ -56,6 +183,8 @@ pub enum FilePurpose {
     Resource,
     Log,
     Forbidden,
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -65,6 +194,8 @@ pub enum GraderEvalStatus {
     HeldBack,
     Ready,
     Regrade,
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -74,6 +205,17 @@ pub struct GraderEval {
     pub score: f64,
     pub explanation: String,
     pub status: GraderEvalStatus,
+    /// Why the student asked for a regrade, if they did.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regrade_reason: Option<String>,
+}
+
+/// A student’s request to have a grader eval looked at again.
+#[derive(Serialize, Debug)]
+pub struct RegradeRequest<'a> {
+    pub status: GraderEvalStatus,
+    pub regrade_reason: &'a str,
 }
 
 #[derive(Deserialize, Debug)]
@@ -90,7 +232,7 @@ pub struct FileMeta {
 
 impl std::fmt::Display for FileMeta {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "hw{}:{}", self.hw, self.name)
+        write!(f, "hw{}:{}", self.hw, filename::display(&self.name))
     }
 }
 
@@ -109,6 +251,17 @@ pub enum PartnerRequestStatus {
     Incoming,
     Accepted,
     Canceled,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Which way a partner request went. For a pending request the status
+/// says, but once it’s accepted or canceled, only this does.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PartnerRequestDirection {
+    Outgoing,
+    Incoming,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -116,6 +269,12 @@ pub struct PartnerRequest {
     pub assignment_number: usize,
     pub user: String,
     pub status: PartnerRequestStatus,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<PartnerRequestDirection>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<UtcDateTime>,
 }
 
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -124,6 +283,8 @@ pub enum UserRole {
     Student,
     Grader,
     Admin,
+    #[serde(other)]
+    Unknown,
 }
 
 impl UserRole {
@@ -132,6 +293,7 @@ impl UserRole {
             UserRole::Student => "student",
             UserRole::Grader  => "grader",
             UserRole::Admin   => "admin",
+            UserRole::Unknown => "unknown",
         }
     }
 }
@@ -173,6 +335,22 @@ pub struct SelfEval {
     pub permalink: String,
 }
 
+/// What the server reports about itself at `/api/version`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ServerInfo {
+    /// The version of the API that the server speaks, as `MAJOR.MINOR`.
+    pub api_version: String,
+    #[serde(default)]
+    pub server_version: Option<String>,
+    /// The newest release of `gsc`, if the server knows it.
+    #[serde(default)]
+    pub client_version: Option<String>,
+    /// How many days an API key lasts before the server rotates it, if it
+    /// does.
+    #[serde(default)]
+    pub key_rotation_days: Option<u32>,
+}
+
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "snake_case")]
 pub enum SubmissionStatus {
@@ -183,6 +361,8 @@ pub enum SubmissionStatus {
     SelfEval,
     ExtendedEval,
     Closed,
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -192,6 +372,8 @@ pub enum SubmissionEvalStatus {
     Started,
     Overdue,
     Complete,
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Deserialize, Debug)]
@@ -276,43 +458,168 @@ pub struct SubmissionChange {
 }
 
 impl UtcDateTime {
+    pub fn now() -> Self {
+        Self(offset::Utc::now())
+    }
+
+    /// Interprets a date and time in the local time zone. Ambiguous times
+    /// resolve to the earlier instant, and times skipped by a DST change
+    /// are shifted forward across the gap.
+    pub fn from_naive_local(naive: NaiveDateTime) -> Self {
+        use chrono::TimeZone;
+
+        let local = offset::Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .unwrap_or_else(|| {
+                let offset = offset::Local.offset_from_utc_datetime(&naive);
+                offset::Local.from_utc_datetime(&(naive - offset))
+            });
+        Self(local.into())
+    }
+
     pub fn into_local(self) -> DateTime<offset::Local> {
         self.0.into()
     }
 
+    /// How long from now until this time (negative if it’s past).
+    pub fn time_until(&self) -> chrono::Duration {
+        self.time_since(&Self::now())
+    }
+
+    /// How long after `earlier` this time is (negative if it’s before).
+    pub fn time_since(&self, earlier: &UtcDateTime) -> chrono::Duration {
+        self.0.signed_duration_since(earlier.0)
+    }
+
     pub fn format_local<'a>(&self, fmt: &'a str) -> DelayedFormat<StrftimeItems<'a>> {
         self.clone().into_local().format(fmt)
     }
 
+    /// Like `format_local`, but in the zone chosen by `set_display_zone`.
+    pub fn format_display<'a>(&self, fmt: &'a str) -> DelayedFormat<StrftimeItems<'a>> {
+        match *DISPLAY_ZONE.read().unwrap() {
+            DisplayZone::Local => self.format_local(fmt),
+            DisplayZone::Fixed(zone) => self.0.with_timezone(&zone).format(fmt),
+        }
+    }
+
+    /// RFC 3339 in UTC, as the server sends it.
+    pub fn to_rfc3339(&self) -> String {
+        self.0.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+    }
+
+    pub fn to_system_time(&self) -> std::time::SystemTime {
+        self.0.into()
+    }
+
     // [[CC]YY]MMDDhhmm[.ss]
     pub fn touch_t_fmt(&self) -> DelayedFormat<StrftimeItems> {
         self.format_local("%Y%m%d%H%M.%S")
     }
 }
 
+impl<Tz: chrono::TimeZone> From<DateTime<Tz>> for UtcDateTime {
+    fn from(date: DateTime<Tz>) -> Self {
+        Self(date.with_timezone(&offset::Utc))
+    }
+}
+
 impl serde::Serialize for UtcDateTime {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let str = self.0.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
-        serializer.serialize_str(&str)
+        serializer.serialize_str(&self.to_rfc3339())
     }
 }
 
 impl std::fmt::Display for UtcDateTime {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.format_local("%a %d %b, %H:%M (%z)"))
+        write!(f, "{}", self.format_display("%a %d %b, %H:%M (%z)"))
     }
 }
 
 impl std::str::FromStr for UtcDateTime {
     type Err = chrono::format::ParseError;
 
+    /// Accepts ISO-8601-style dates with or without seconds, a `T`
+    /// separator, or a UTC offset. Times without an offset are local, and a
+    /// bare date means 23:59:59 local time that day.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         type Fixed = DateTime<offset::FixedOffset>;
-        let fixed = Fixed::parse_from_str(s, "%Y-%m-%d %H:%M:%S %z")?;
-        Ok(Self(fixed.into()))
+
+        const ZONED: &[&str] = &[
+            "%Y-%m-%d %H:%M:%S %z",
+            "%Y-%m-%d %H:%M %z",
+            "%Y-%m-%d %H:%M:%S%z",
+            "%Y-%m-%d %H:%M%z",
+            "%Y-%m-%dT%H:%M:%S%z",
+            "%Y-%m-%dT%H:%M%z",
+        ];
+        const NAIVE: &[&str] = &[
+            "%Y-%m-%d %H:%M:%S",
+            "%Y-%m-%d %H:%M",
+            "%Y-%m-%dT%H:%M:%S",
+            "%Y-%m-%dT%H:%M",
+        ];
+
+        let s = s.trim();
+
+        if let Ok(fixed) = DateTime::parse_from_rfc3339(s) {
+            return Ok(Self(fixed.into()));
+        }
+
+        for fmt in ZONED {
+            if let Ok(fixed) = Fixed::parse_from_str(s, fmt) {
+                return Ok(Self(fixed.into()));
+            }
+        }
+
+        for fmt in NAIVE {
+            if let Ok(naive) = NaiveDateTime::parse_from_str(s, fmt) {
+                return Ok(Self::from_naive_local(naive));
+            }
+        }
+
+        let date = NaiveDate::parse_from_str(s, "%Y-%m-%d")?;
+        Ok(Self::from_naive_local(date.and_hms(23, 59, 59)))
+    }
+}
+
+impl std::str::FromStr for DisplayZone {
+    type Err = crate::errors::Error;
+
+    /// Accepts `local`, `UTC`, or a fixed offset such as `+05:30` or `-0500`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref OFFSET: Regex = Regex::new(r"^([+-])(\d{1,2}):?(\d{2})?$").unwrap();
+        }
+
+        let s = s.trim();
+
+        if s.eq_ignore_ascii_case("local") {
+            return Ok(DisplayZone::Local);
+        }
+
+        if s.eq_ignore_ascii_case("utc") || s.eq_ignore_ascii_case("gmt") || s == "Z" {
+            return Ok(DisplayZone::utc());
+        }
+
+        let error = || crate::errors::Error::syntax("time zone", s);
+        let captures = OFFSET.captures(s).ok_or_else(error)?;
+        let hours: i32 = captures[2].parse()?;
+        let minutes: i32 = captures.get(3).map_or(Ok(0), |m| m.as_str().parse())?;
+        let seconds = 3600 * hours + 60 * minutes;
+        let seconds = if &captures[1] == "-" {
+            -seconds
+        } else {
+            seconds
+        };
+
+        offset::FixedOffset::east_opt(seconds)
+            .map(DisplayZone::Fixed)
+            .ok_or_else(error)
     }
 }
 
@@ -327,6 +634,7 @@ impl SubmissionStatus {
             SelfEval => "open for self evaluation",
             ExtendedEval => "open for self evaluation (extended)",
             Closed => "closed",
+            Unknown => "unknown",
         }
     }
 
@@ -347,6 +655,26 @@ impl std::fmt::Display for SubmissionStatus {
     }
 }
 
+/// Parses a status by the name the server gives it, e.g. `self_eval`.
+impl std::str::FromStr for SubmissionStatus {
+    type Err = crate::errors::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use self::SubmissionStatus::*;
+
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "future" => Ok(Future),
+            "open" => Ok(Open),
+            "extended" => Ok(Extended),
+            "overtime" => Ok(Overtime),
+            "self_eval" => Ok(SelfEval),
+            "extended_eval" => Ok(ExtendedEval),
+            "closed" => Ok(Closed),
+            _ => Err(crate::errors::Error::syntax("submission status", s)),
+        }
+    }
+}
+
 impl SubmissionEvalStatus {
     fn to_str(&self) -> &'static str {
         use self::SubmissionEvalStatus::*;
@@ -355,6 +683,7 @@ impl SubmissionEvalStatus {
             Started => "started",
             Overdue => "overdue",
             Complete => "complete",
+            Unknown => "unknown",
         }
     }
 }
@@ -365,6 +694,70 @@ impl std::fmt::Display for SubmissionEvalStatus {
     }
 }
 
+impl PartnerRequestStatus {
+    fn as_str(self) -> &'static str {
+        use self::PartnerRequestStatus::*;
+        match self {
+            Outgoing => "pending",
+            Incoming => "pending",
+            Accepted => "accepted",
+            Canceled => "canceled",
+            Unknown => "unknown",
+        }
+    }
+
+    pub fn is_pending(self) -> bool {
+        use self::PartnerRequestStatus::*;
+        match self {
+            Outgoing | Incoming => true,
+            Accepted | Canceled | Unknown => false,
+        }
+    }
+}
+
+impl PartnerRequest {
+    /// Which way the request went, from its status while it’s pending,
+    /// and afterward from `direction`, if the server gave it.
+    pub fn direction(&self) -> Option<PartnerRequestDirection> {
+        match self.status {
+            PartnerRequestStatus::Outgoing => Some(PartnerRequestDirection::Outgoing),
+            PartnerRequestStatus::Incoming => Some(PartnerRequestDirection::Incoming),
+            _ => self.direction,
+        }
+    }
+}
+
+impl std::fmt::Display for PartnerRequestStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ServerInfo {
+    /// The major and minor components of `api_version`, if well formed.
+    pub fn api_version(&self) -> Option<(u32, u32)> {
+        let version = self.api_version.trim();
+        let (major, minor) = version.split_once('.').unwrap_or((version, "0"));
+        Some((major.parse().ok()?, minor.parse().ok()?))
+    }
+}
+
+impl Eval {
+    /// The score that counts for this item: the grader’s, or until there
+    /// is one, the student’s own.
+    pub fn score(&self) -> Option<f64> {
+        self.grader_eval
+            .as_ref()
+            .map(|grader_eval| grader_eval.score)
+            .or_else(|| self.self_eval.as_ref().map(|self_eval| self_eval.score))
+    }
+
+    /// How much this item adds to the grade: its score times its weight.
+    pub fn contribution(&self) -> Option<f64> {
+        self.score().map(|score| score * self.value)
+    }
+}
+
 impl Submission {
     pub fn quota_remaining(&self) -> f32 {
         100.0 * (self.bytes_quota - self.bytes_used) as f32 / self.bytes_quota as f32
@@ -382,6 +775,7 @@ impl FilePurpose {
             Resource => 'r',
             Log => 'l',
             Forbidden => 'F',
+            Unknown => '?',
         }
     }
 
@@ -395,6 +789,45 @@ impl FilePurpose {
             Resource => "Resources",
             Log => ".",
             Forbidden => ".",
+            Unknown => ".",
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        use self::FilePurpose::*;
+
+        match self {
+            Source => "source",
+            Test => "test",
+            Config => "config",
+            Resource => "resource",
+            Log => "log",
+            Forbidden => "forbidden",
+            Unknown => "unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for FilePurpose {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses the file types that a user may choose, by name or by the
+/// letter that `gsc ls` shows.
+impl std::str::FromStr for FilePurpose {
+    type Err = crate::errors::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use self::FilePurpose::*;
+
+        match s.to_lowercase().as_str() {
+            "s" | "source" | "src" => Ok(Source),
+            "t" | "test" => Ok(Test),
+            "c" | "config" => Ok(Config),
+            "r" | "resource" => Ok(Resource),
+            _ => Err(crate::errors::Error::syntax("file type", s)),
         }
     }
 }
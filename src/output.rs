@@ -0,0 +1,65 @@
+//! Command output for `-o FILE`. It’s written to a temporary file beside
+//! `FILE`, which replaces `FILE` only once the command has succeeded, so a
+//! failure can’t leave it half-written.
+
+use crate::errors::{Result, ResultExt};
+
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub struct OutputFile {
+    path: PathBuf,
+    tmp: PathBuf,
+    file: Option<BufWriter<fs::File>>,
+}
+
+impl OutputFile {
+    pub fn create(path: &Path) -> Result<Self> {
+        let mut tmp = OsString::from(".");
+        tmp.push(path.file_name().unwrap_or_default());
+        tmp.push(".tmp");
+        let tmp = path.with_file_name(tmp);
+
+        let file = fs::File::create(&tmp)
+            .chain_err(|| format!("Could not create output file: {}", path.display()))?;
+
+        Ok(OutputFile {
+            path: path.to_owned(),
+            tmp,
+            file: Some(BufWriter::new(file)),
+        })
+    }
+
+    /// Moves the finished output into place.
+    pub fn commit(mut self) -> Result<()> {
+        let file = self.file.take().expect("already committed");
+
+        file.into_inner()
+            .map_err(io::IntoInnerError::into_error)
+            .and_then(|_| fs::rename(&self.tmp, &self.path))
+            .chain_err(|| format!("Could not write output file: {}", self.path.display()))
+    }
+
+    fn file(&mut self) -> &mut BufWriter<fs::File> {
+        self.file.as_mut().expect("already committed")
+    }
+}
+
+impl Write for OutputFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file().flush()
+    }
+}
+
+impl Drop for OutputFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.tmp);
+    }
+}
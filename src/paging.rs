@@ -0,0 +1,89 @@
+//! Reading lists that the server sends a page at a time.
+//!
+//! A paged response names the next page in a `Link: <…>; rel="next"`
+//! header, as GitHub’s API does. A server that doesn’t page sends no such
+//! header, so we read the one response and stop.
+
+use crate::credentials::Credentials;
+use crate::prelude::*;
+
+use reqwest::{header, Url};
+use serde::de::DeserializeOwned;
+
+impl GscClient {
+    /// Reads the list at `uri`, following it from page to page, and passes
+    /// each entry to `f` until `f` returns `false` to say it has seen
+    /// enough. That way a caller that wants only the first few entries
+    /// doesn’t fetch the rest of the pages. For a list that may be long,
+    /// like every submission in a class, `streamed` reads each page as a
+    /// stream, bypassing the memo and the cache.
+    pub(crate) fn for_each_listed<T, F>(
+        &self,
+        uri: &str,
+        creds: &Credentials,
+        streamed: bool,
+        mut f: F,
+    ) -> Result<()>
+    where
+        T: DeserializeOwned,
+        F: FnMut(T) -> bool,
+    {
+        let mut uri = uri.to_owned();
+        let mut more = true;
+
+        loop {
+            let request = self.http.get(&uri);
+            let response = self.send_request_via(request, creds, streamed)?;
+            let next = next_link(response.headers());
+
+            response.for_each_message(|entry| {
+                if more {
+                    more = f(entry);
+                }
+            })?;
+
+            match next {
+                Some(next) if more => {
+                    uri = Url::parse(&uri)
+                        .and_then(|base| base.join(&next))
+                        .chain_err(|| ErrorKind::syntax("next page link", next))?
+                        .to_string();
+                    ve2!("Fetching the next page, {}", uri);
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// The whole list at `uri`, or just the first `limit` entries. Since
+    /// this holds the list anyway, its pages may be remembered or cached.
+    pub(crate) fn fetch_list<T: DeserializeOwned>(
+        &self,
+        uri: &str,
+        creds: &Credentials,
+        limit: Option<usize>,
+    ) -> Result<Vec<T>> {
+        let mut list = Vec::new();
+        self.for_each_listed(uri, creds, false, |entry| {
+            list.push(entry);
+            limit.map_or(true, |limit| list.len() < limit)
+        })?;
+        Ok(list)
+    }
+}
+
+/// The target of the `rel="next"` link in `headers`, if any.
+fn next_link(headers: &header::HeaderMap) -> Option<String> {
+    headers
+        .get_all(header::LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .find_map(|link| {
+            let mut parts = link.split(';').map(str::trim);
+            let target = parts.next()?.strip_prefix('<')?.strip_suffix('>')?;
+            parts
+                .any(|param| param == "rel=\"next\"" || param == "rel=next")
+                .then(|| target.to_owned())
+        })
+}
@@ -0,0 +1,107 @@
+//! Output for scripts (`--porcelain`): one record per line, fields
+//! separated by tabs. Dates are RFC 3339 in UTC, numbers are plain, and
+//! statuses use the server’s names for them, so the format doesn’t depend
+//! on settings or change between versions. The first field of every
+//! record says what kind of record it is.
+
+use crate::messages::{FileMeta, PartnerRequest, SubmissionStatus, UtcDateTime};
+
+use serde::Serialize;
+
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Default)]
+pub struct Record(Vec<String>);
+
+impl Record {
+    pub fn new() -> Self {
+        Record::default()
+    }
+
+    /// Adds a field, escaping backslashes, tabs, and newlines so that it
+    /// can’t break the record apart.
+    pub fn field(mut self, value: impl Display) -> Self {
+        let mut escaped = String::new();
+
+        for c in value.to_string().chars() {
+            match c {
+                '\\' => escaped.push_str("\\\\"),
+                '\t' => escaped.push_str("\\t"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                _ => escaped.push(c),
+            }
+        }
+
+        self.0.push(escaped);
+        self
+    }
+
+    pub fn date(self, date: &UtcDateTime) -> Self {
+        self.field(date.to_rfc3339())
+    }
+
+    /// Adds an enum value by the name the server gives it, e.g.
+    /// `self_eval` rather than “open for self evaluation”.
+    pub fn name(self, value: &impl Serialize) -> Self {
+        let name = match serde_json::to_value(value) {
+            Ok(serde_json::Value::String(name)) => name,
+            _ => String::new(),
+        };
+        self.field(name)
+    }
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&self.0.join("\t"))
+    }
+}
+
+/// `file HW BYTES UPLOADED PURPOSE NAME`
+pub fn file(file: &FileMeta) -> Record {
+    Record::new()
+        .field("file")
+        .field(file.hw)
+        .field(file.byte_count)
+        .date(&file.upload_time)
+        .name(&file.purpose)
+        .field(&file.name)
+}
+
+/// `partner HW STATUS USER TIMESTAMP`, where `TIMESTAMP` may be empty.
+pub fn partner_request(p: &PartnerRequest) -> Record {
+    let timestamp = p.timestamp.as_ref().map(UtcDateTime::to_rfc3339);
+
+    Record::new()
+        .field("partner")
+        .field(p.assignment_number)
+        .name(&p.status)
+        .field(&p.user)
+        .field(timestamp.unwrap_or_default())
+}
+
+/// `verify HW VERDICT NAME`, where `VERDICT` is one of the words that
+/// `gsc verify` shows.
+pub fn verify(hw: usize, verdict: &str, name: &str) -> Record {
+    Record::new()
+        .field("verify")
+        .field(hw)
+        .field(verdict)
+        .field(name)
+}
+
+/// `submission HW STATUS GRADE`, where `GRADE` is empty until the
+/// homework is closed. `gsc status --all` adds more fields after these.
+pub fn submission(hw: usize, status: SubmissionStatus, grade: f64) -> Record {
+    let grade = match status {
+        SubmissionStatus::Closed => grade.to_string(),
+        _ => String::new(),
+    };
+
+    Record::new()
+        .field("submission")
+        .field(hw)
+        .name(&status)
+        .field(grade)
+}
@@ -0,0 +1,607 @@
+//! Human-readable rendering of what the server tells us. These functions
+//! return the text rather than printing it, so that it can be tested
+//! without a server or a terminal. The text doesn’t end with a newline
+//! unless a table does, as it’s meant to be printed with `v1!`.
+
+use crate::filename;
+use crate::messages::{
+    Eval, FileMeta, FilePurpose, PartnerRequest, PartnerRequestDirection, PartnerRequestStatus,
+    Submission, SubmissionStatus, User, UtcDateTime,
+};
+use crate::util::{truncate_to_width, Countdown, Percentage};
+
+use thousands::Separable;
+use unicode_width::UnicodeWidthStr;
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::iter;
+
+/// The flexible column of a [`Table`] is never cut narrower than this,
+/// however narrow the output.
+pub const MIN_FLEX_WIDTH: usize = 20;
+
+/// A table laid out by `tabular`, except that one column, by default the
+/// last, is flexible: when the rows are too wide for the output, its cells
+/// are cut short, or wrapped onto more lines, so that every other column
+/// stays whole. That column usually holds a name or a free-form value.
+#[derive(Clone, Debug)]
+pub struct Table {
+    spec: String,
+    rows: Vec<Row>,
+    flex: Option<usize>,
+    wrap: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Row(Vec<String>);
+
+impl Row {
+    pub fn new() -> Self {
+        Row::default()
+    }
+
+    pub fn with_cell(mut self, cell: impl std::fmt::Display) -> Self {
+        self.0.push(cell.to_string());
+        self
+    }
+}
+
+impl Table {
+    /// A table whose rows are laid out as `spec` says, as for
+    /// `tabular::Table::new`.
+    pub fn new(spec: &str) -> Self {
+        Table {
+            spec: spec.to_owned(),
+            rows: Vec::new(),
+            flex: None,
+            wrap: false,
+        }
+    }
+
+    pub fn add_row(&mut self, row: Row) -> &mut Self {
+        self.rows.push(row);
+        self
+    }
+
+    /// Makes column `column` the flexible one, rather than the last.
+    pub fn flex(mut self, column: usize) -> Self {
+        self.flex = Some(column);
+        self
+    }
+
+    /// Wraps the flexible column’s cells onto more lines, rather than
+    /// cutting them short.
+    pub fn wrap(mut self) -> Self {
+        self.wrap = true;
+        self
+    }
+
+    fn layout<'a>(&self, rows: impl Iterator<Item = &'a [String]>) -> String {
+        let mut table = tabular::Table::new(&self.spec);
+
+        for row in rows {
+            let mut cells = tabular::Row::new();
+            for cell in row {
+                cells.add_cell(cell);
+            }
+            table.add_row(cells);
+        }
+
+        table.to_string()
+    }
+}
+
+impl std::fmt::Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&table(self, None))
+    }
+}
+
+/// How many columns are left for a flexible column when the rest take
+/// `fixed` of the `width` available.
+fn flex_room(width: usize, fixed: usize) -> usize {
+    // One column spare, since filling the last one wraps on some terminals:
+    width.saturating_sub(fixed + 1).max(MIN_FLEX_WIDTH)
+}
+
+/// Breaks `text` into lines of at most `width` columns, at spaces where
+/// it can. There’s always at least one line, if only an empty one.
+fn wrap_lines(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = textwrap::wrap(text, width.max(1))
+        .into_iter()
+        .map(Cow::into_owned)
+        .collect();
+
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+
+    lines
+}
+
+const HANGING_INDENT: &str = "    ";
+
+/// Wraps `text` to `width` columns (by default, the terminal’s), indented.
+fn hanging(text: &str, width: Option<usize>) -> String {
+    let width = width.unwrap_or_else(textwrap::termwidth);
+    let width = width.saturating_sub(HANGING_INDENT.len());
+
+    if text.is_empty() {
+        return String::new();
+    }
+
+    wrap_lines(text, width)
+        .iter()
+        .map(|line| format!("{}{}\n", HANGING_INDENT, line))
+        .collect()
+}
+
+/// Renders `table`, cutting its flexible column short, if need be, so
+/// that it fits in `width` columns.
+pub fn table(table: &Table, width: Option<usize>) -> String {
+    let width = match width {
+        Some(width) => width,
+        None => return table.layout(table.rows.iter().map(|row| &row.0[..])),
+    };
+
+    let flex = table
+        .flex
+        .unwrap_or_else(|| table.rows.iter().map(|row| row.0.len()).max().unwrap_or(1) - 1);
+
+    // How wide the other columns make the rows, with nothing in this one:
+    let blank = |row: &Row| {
+        let mut row = row.0.clone();
+        if let Some(cell) = row.get_mut(flex) {
+            cell.clear();
+        }
+        row
+    };
+    let blanked: Vec<_> = table.rows.iter().map(blank).collect();
+    let fixed = table
+        .layout(blanked.iter().map(Vec::as_slice))
+        .lines()
+        .map(UnicodeWidthStr::width)
+        .max()
+        .unwrap_or(0);
+
+    let room = flex_room(width, fixed);
+
+    let mut fitted = Vec::new();
+
+    for row in &table.rows {
+        let mut row = row.0.clone();
+        let cell = match row.get_mut(flex) {
+            Some(cell) => cell,
+            None => {
+                fitted.push(row);
+                continue;
+            }
+        };
+
+        if !table.wrap {
+            *cell = truncate_to_width(cell, room).into_owned();
+            fitted.push(row);
+            continue;
+        }
+
+        // Lines after the first leave the other columns empty:
+        let lines = wrap_lines(cell, room);
+        let mut rest = vec![String::new(); row.len()];
+        for (i, line) in lines.into_iter().enumerate() {
+            if i == 0 {
+                row[flex] = line;
+                fitted.push(row.clone());
+            } else {
+                rest[flex] = line;
+                fitted.push(rest.clone());
+            }
+        }
+    }
+
+    table.layout(fitted.iter().map(Vec::as_slice))
+}
+
+/// The listing for `gsc ls`. Given the `due` date, files uploaded after
+/// it are marked ‘late’, in a column that’s empty if none are.
+pub fn file_list(files: &[FileMeta], due: Option<&UtcDateTime>, width: Option<usize>) -> String {
+    let mut list = Table::new("{:>}  {:<} {:<} [{:<}] {:<}");
+
+    for file in files {
+        let late = match due {
+            Some(due) if file.upload_time > *due => "late",
+            _ => "",
+        };
+
+        list.add_row(
+            Row::new()
+                .with_cell(file.byte_count.separate_with_commas())
+                .with_cell(&file.upload_time)
+                .with_cell(late)
+                .with_cell(file.purpose.to_char())
+                .with_cell(filename::display(&file.name)),
+        );
+    }
+
+    table(&list, width)
+}
+
+/// The files uploaded after the `due` date, for `gsc status hwN`, or
+/// nothing if there are none.
+pub fn late_files(files: &[FileMeta], due: &UtcDateTime) -> String {
+    let mut list = Table::new("    {:<}  {:<}");
+    let mut any = false;
+
+    for file in files.iter().filter(|file| file.upload_time > *due) {
+        list.add_row(
+            Row::new()
+                .with_cell(filename::display(&file.name))
+                .with_cell(&file.upload_time),
+        );
+        any = true;
+    }
+
+    if any {
+        format!("  Changed after the due date:\n{}", list)
+    } else {
+        String::new()
+    }
+}
+
+/// The listing for `gsc ls --tree`: the files that `gsc cp -a` would
+/// download into a directory named for homework `hw`, in the
+/// subdirectories it would put them in.
+pub fn file_tree(hw: usize, files: &[FileMeta], width: Option<usize>) -> String {
+    let mut top: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+
+    for file in files {
+        if file.purpose == FilePurpose::Log {
+            continue;
+        }
+
+        match file.purpose.to_dir() {
+            "." => {
+                top.entry(&file.name).or_default();
+            }
+            dir => top.entry(dir).or_default().push(&file.name),
+        }
+    }
+
+    for children in top.values_mut() {
+        children.sort_unstable();
+    }
+
+    // Each line is its branches and then a name, and only the name is cut:
+    let mut lines = vec![(String::new(), format!("hw{}/", hw))];
+
+    for (i, (name, children)) in top.iter().enumerate() {
+        let last = i + 1 == top.len();
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+
+        if children.is_empty() {
+            lines.push((branch.to_owned(), filename::display(name).into_owned()));
+            continue;
+        }
+
+        lines.push((branch.to_owned(), format!("{}/", name)));
+
+        for (j, child) in children.iter().enumerate() {
+            let branch = if j + 1 == children.len() {
+                "└── "
+            } else {
+                "├── "
+            };
+            let display = filename::display(child).into_owned();
+            lines.push((format!("{}{}", indent, branch), display));
+        }
+    }
+
+    lines
+        .iter()
+        .map(|(branches, name)| match width {
+            Some(width) => {
+                let room = flex_room(width, branches.width());
+                format!("{}{}", branches, truncate_to_width(name, room))
+            }
+            None => format!("{}{}", branches, name),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The status of homework `number`, for `gsc status hwN`, with countdowns
+/// to its deadlines from `now`.
+pub fn submission(
+    number: usize,
+    submission: &Submission,
+    now: &UtcDateTime,
+    width: Option<usize>,
+) -> String {
+    let in_evaluation = submission.status.is_self_eval();
+    let quota_remaining = submission.quota_remaining();
+    let due_countdown = Countdown(submission.due_date.time_since(now));
+    let eval_countdown = Countdown(submission.eval_date.time_since(now));
+
+    let mut status = Table::new("  {:<}  {:<}").wrap();
+    status.add_row(
+        Row::new()
+            .with_cell("Submission status:")
+            .with_cell(submission.status),
+    );
+
+    if in_evaluation {
+        status.add_row(
+            Row::new()
+                .with_cell("Evaluation status:")
+                .with_cell(submission.eval_status),
+        );
+    }
+
+    status
+        .add_row(
+            Row::new()
+                .with_cell("Open date:")
+                .with_cell(&submission.open_date),
+        )
+        .add_row(
+            Row::new()
+                .with_cell("Submission due date:")
+                .with_cell(&submission.due_date),
+        )
+        .add_row(
+            Row::new()
+                .with_cell("Submission time left:")
+                .with_cell(due_countdown),
+        )
+        .add_row(
+            Row::new()
+                .with_cell("Self-eval due date:")
+                .with_cell(&submission.eval_date),
+        )
+        .add_row(
+            Row::new()
+                .with_cell("Self-eval time left:")
+                .with_cell(eval_countdown),
+        )
+        .add_row(
+            Row::new()
+                .with_cell("Last modified:")
+                .with_cell(&submission.last_modified),
+        )
+        .add_row(Row::new().with_cell("Quota remaining:").with_cell(format!(
+            "{:.1}% ({} of {} bytes used)",
+            quota_remaining,
+            submission.bytes_used.separate_with_commas(),
+            submission.bytes_quota.separate_with_commas()
+        )));
+
+    let owners: Vec<_> = iter::once(&submission.owner1)
+        .chain(&submission.owner2)
+        .map(|owner| owner.name.as_str())
+        .collect();
+
+    format!(
+        "hw{} ({})\n{}",
+        number,
+        owners.join(" and "),
+        table(&status, width)
+    )
+}
+
+/// A user’s grades and partner requests, for `gsc status`; with
+/// `show_future`, homeworks that haven’t opened yet are included. This is
+/// empty if there’s nothing to show.
+pub fn user_record(user: &User, show_future: bool) -> String {
+    let mut sections = Vec::new();
+
+    if user
+        .submissions
+        .iter()
+        .any(|s| show_future || s.status != SubmissionStatus::Future)
+    {
+        let mut grades = Table::new("    hw{:<}: {:>}    {:<}");
+
+        for s in &user.submissions {
+            let grade = match s.status {
+                SubmissionStatus::Future if !show_future => continue,
+                SubmissionStatus::Closed => format!("{:.1}%", 100.0 * s.grade),
+                _ => String::new(),
+            };
+
+            grades.add_row(
+                Row::new()
+                    .with_cell(s.assignment_number)
+                    .with_cell(grade)
+                    .with_cell(s.status),
+            );
+        }
+
+        sections.push(format!("  Submissions:\n{}", grades));
+    }
+
+    if !user.exam_grades.is_empty() {
+        let mut grades = Table::new("    ex{:<}: {:>}%    ({:<} / {:<})");
+
+        for e in &user.exam_grades {
+            let grade = format!("{:.1}", 100.0 * e.points as f64 / e.possible as f64);
+            grades.add_row(
+                Row::new()
+                    .with_cell(e.number)
+                    .with_cell(grade)
+                    .with_cell(e.points)
+                    .with_cell(e.possible),
+            );
+        }
+
+        sections.push(format!("  Exam grades:\n{}", grades));
+    }
+
+    if !user.partner_requests.is_empty() {
+        sections.push(partner_requests(&user.partner_requests, "  "));
+    }
+
+    sections.join("\n")
+}
+
+/// Whom `gsc auth` logged in as, and where, with enough of their record
+/// to tell whether it’s the right account in the right course.
+pub fn auth_summary(user: &User, endpoint: &str) -> String {
+    let active = user
+        .submissions
+        .iter()
+        .filter(|s| {
+            matches!(
+                s.status,
+                SubmissionStatus::Open | SubmissionStatus::Extended | SubmissionStatus::SelfEval
+            )
+        })
+        .count();
+    let pending = user
+        .partner_requests
+        .iter()
+        .filter(|p| {
+            matches!(
+                p.status,
+                PartnerRequestStatus::Outgoing | PartnerRequestStatus::Incoming
+            )
+        })
+        .count();
+
+    format!(
+        "Authenticated as {} ({}) on {}\n  {} active submission{}, {} pending partner request{}",
+        user.name,
+        user.role,
+        endpoint,
+        active,
+        if active == 1 { "" } else { "s" },
+        pending,
+        if pending == 1 { "" } else { "s" },
+    )
+}
+
+/// Outstanding partner requests, under a heading indented by `indent`.
+pub fn partner_requests(requests: &[PartnerRequest], indent: &str) -> String {
+    let mut list = Table::new("    {:<} {:<}");
+
+    for p in requests {
+        let hw = format!("hw{}:", p.assignment_number);
+        let message = match p.status {
+            PartnerRequestStatus::Outgoing => format!("sent to {}", p.user),
+            PartnerRequestStatus::Incoming => format!("received from {}", p.user),
+            _ => continue,
+        };
+
+        list.add_row(Row::new().with_cell(hw).with_cell(message));
+    }
+
+    format!("{}Partner requests:\n{}", indent, list)
+}
+
+/// Every partner request, pending or not, for `gsc partner list`, with
+/// which way it went and when it last changed.
+pub fn partner_history(requests: &[PartnerRequest]) -> String {
+    let mut table = Table::new("    hw{:<}  {:<} {:<}  {:<}  {:<}");
+
+    for p in requests {
+        let direction = match p.direction() {
+            Some(PartnerRequestDirection::Outgoing) => "sent to",
+            Some(PartnerRequestDirection::Incoming) => "received from",
+            None => "with",
+        };
+
+        let timestamp = p
+            .timestamp
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+
+        table.add_row(
+            Row::new()
+                .with_cell(p.assignment_number)
+                .with_cell(direction)
+                .with_cell(&p.user)
+                .with_cell(p.status)
+                .with_cell(timestamp),
+        );
+    }
+
+    table.to_string()
+}
+
+/// Self-evaluation item `number` of homework `hw`, for `gsc eval get`,
+/// with paragraphs wrapped to `width`.
+pub fn eval(hw: usize, number: usize, eval: &Eval, width: Option<usize>) -> String {
+    let mut lines = vec![
+        format!(
+            "Homework {} item {} ({:?}, {})",
+            hw,
+            number,
+            eval.eval_type,
+            Percentage(eval.value)
+        ),
+        hanging(&eval.prompt, width),
+    ];
+
+    if let Some(self_eval) = &eval.self_eval {
+        lines.push(format!(
+            "Self evaluation:   {}",
+            Percentage(self_eval.score)
+        ));
+        lines.push(hanging(&self_eval.explanation, width));
+    }
+
+    if let Some(grader_eval) = &eval.grader_eval {
+        lines.push(format!(
+            "Grader evaluation: {}",
+            Percentage(grader_eval.score)
+        ));
+        lines.push(hanging(&grader_eval.explanation, width));
+    }
+
+    lines.join("\n")
+}
+
+/// The per-item grade breakdown of homework `hw`, for `gsc grade`: each
+/// item’s weight, both scores, and what it contributes, then the grade,
+/// which is only final once the submission is closed.
+pub fn grade(hw: usize, submission: &Submission, evals: &[Eval], width: Option<usize>) -> String {
+    let mut items = Table::new("  {:>}  {:>}  {:>}  {:>}  {:>}");
+    items.add_row(
+        Row::new()
+            .with_cell("Item")
+            .with_cell("Weight")
+            .with_cell("Self")
+            .with_cell("Grader")
+            .with_cell("Contribution"),
+    );
+
+    let percentage =
+        |score: Option<f64>| score.map(|s| Percentage(s).to_string()).unwrap_or_default();
+
+    for eval in evals {
+        items.add_row(
+            Row::new()
+                .with_cell(eval.sequence)
+                .with_cell(Percentage(eval.value))
+                .with_cell(percentage(eval.self_eval.as_ref().map(|e| e.score)))
+                .with_cell(percentage(eval.grader_eval.as_ref().map(|e| e.score)))
+                .with_cell(percentage(eval.contribution())),
+        );
+    }
+
+    let grade = match submission.status {
+        SubmissionStatus::Closed => format!("Grade: {}", Percentage(submission.grade)),
+        status => format!("Grade: not final ({})", status),
+    };
+
+    format!(
+        "hw{} grade breakdown:\n\n{}\n{}",
+        hw,
+        table(&items, width),
+        grade
+    )
+}
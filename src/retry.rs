@@ -0,0 +1,129 @@
+use crate::prelude::*;
+
+use reqwest::{blocking, header, Method, StatusCode};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The most time to spend waiting on a server that is rate-limiting us.
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// How persistently to resend requests that fail for transient reasons.
+#[derive(Debug, Copy, Clone)]
+pub struct RetryPolicy {
+    pub retries: u32,
+    pub delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Sends `request`, resending it with exponential backoff if it is
+    /// idempotent and fails to connect, times out, or gets a 5xx response.
+    /// If the server rate-limits the request, waits as long as it asks
+    /// (up to a limit) and resends. The final response is returned
+    /// unchecked, even if unsuccessful.
+    pub fn execute(
+        &self,
+        http: &blocking::Client,
+        mut request: blocking::Request,
+    ) -> Result<blocking::Response> {
+        let retries = if is_idempotent(request.method()) {
+            self.retries
+        } else {
+            0
+        };
+
+        let mut delay = self.delay;
+        let mut attempt = 0;
+        let mut rate_limit_wait = Duration::from_secs(0);
+
+        loop {
+            let backup = request.try_clone();
+
+            ve3!("> Sending request to {}", request.url());
+            let result = http.execute(request);
+
+            let backup = match backup {
+                None => return Ok(result?),
+                Some(backup) => backup,
+            };
+
+            let pause = match result {
+                Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                    match retry_after(&response) {
+                        Some(wait) if rate_limit_wait + wait <= MAX_RATE_LIMIT_WAIT => {
+                            v2!(
+                                "Server is busy; waiting {} s before trying again...",
+                                wait.as_secs()
+                            );
+                            rate_limit_wait += wait;
+                            wait
+                        }
+                        _ => return Ok(response),
+                    }
+                }
+
+                Ok(response) if !response.status().is_server_error() => return Ok(response),
+                result if attempt >= retries => return Ok(result?),
+                Ok(response) => {
+                    ve2!("Server responded {}.", response.status());
+                    self.next_backoff(&mut attempt, &mut delay, retries)
+                }
+                Err(e) if e.is_connect() || e.is_timeout() => {
+                    ve2!("{}", e);
+                    self.next_backoff(&mut attempt, &mut delay, retries)
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            thread::sleep(pause);
+            request = backup;
+        }
+    }
+
+    fn next_backoff(&self, attempt: &mut u32, delay: &mut Duration, retries: u32) -> Duration {
+        *attempt += 1;
+        let pause = with_jitter(*delay);
+        ve2!(
+            "Retrying in {:.1} s (attempt {} of {})...",
+            pause.as_secs_f64(),
+            attempt,
+            retries
+        );
+        *delay *= 2;
+        pause
+    }
+}
+
+/// Parses the `Retry-After` header, which may give either a number of
+/// seconds or an HTTP date.
+pub fn retry_after(response: &blocking::Response) -> Option<Duration> {
+    let value = response.headers().get(header::RETRY_AFTER)?.to_str().ok()?;
+
+    if let Ok(secs) = value.trim().parse() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let secs = date.signed_duration_since(chrono::Utc::now()).num_seconds();
+    Some(Duration::from_secs(secs.max(0) as u64))
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    [
+        Method::GET,
+        Method::HEAD,
+        Method::PUT,
+        Method::DELETE,
+        Method::OPTIONS,
+    ]
+    .contains(method)
+}
+
+// Adds up to 50% to `delay`, so that clients that failed together don’t
+// all retry together.
+fn with_jitter(delay: Duration) -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    delay + delay.mul_f64(f64::from(nanos % 1000) / 2000.0)
+}
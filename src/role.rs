@@ -0,0 +1,64 @@
+//! Checking that the user is course staff before running an admin
+//! command, so that a student gets one clear error up front rather than
+//! a server error partway through.
+
+use crate::messages::{User, UserRole};
+use crate::prelude::*;
+
+/// How long to trust a cached copy of the user’s record for their role,
+/// which hardly ever changes, before asking the server again.
+const USER_ROLE_TTL_HOURS: i64 = 24;
+
+impl GscClient {
+    /// Fails unless the logged-in user (not `--user`) is a grader or an
+    /// admin. A role the server reports but we don’t know gets the benefit
+    /// of the doubt, as does not being able to find out while offline.
+    pub fn check_staff_role(&self) -> Result<()> {
+        match self.user_role()? {
+            Some(UserRole::Student) => {
+                let creds = self.load_credentials()?;
+                Err(ErrorKind::RequiresStaffRole(
+                    creds.username().to_owned(),
+                    UserRole::Student,
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The logged-in user’s role, fetched at most once per run, and
+    /// taken from the cache if it’s recent enough.
+    pub(crate) fn user_role(&self) -> Result<Option<UserRole>> {
+        if let Some(role) = self.user_role.get() {
+            return Ok(Some(*role));
+        }
+
+        let creds = self.load_credentials()?;
+        let uri = self.user_uri(creds.username());
+
+        let cached = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.lookup(Some(creds.username()), &uri));
+        let user: User = match cached {
+            Some(entry)
+                if self.config.offline()
+                    || -entry.fetched.time_until()
+                        < chrono::Duration::hours(USER_ROLE_TTL_HOURS) =>
+            {
+                ve3!("Using user role cached {}", entry.fetched);
+                crate::messages::from_slice(entry.body.as_bytes())?
+            }
+            _ if self.config.offline() => return Ok(None),
+            _ => {
+                let request = self.http.get(&uri);
+                self.send_request_with_credentials(request, &creds)?
+                    .message()?
+            }
+        };
+
+        ve3!("Logged in as {}, a {}", user.name, user.role);
+        let _ = self.user_role.set(user.role);
+        Ok(Some(user.role))
+    }
+}
@@ -0,0 +1,95 @@
+//! Running many tasks against the server at once without hammering it,
+//! for commands that touch every submission in a class.
+//!
+//! Tasks run on up to `jobs` threads, and no two start less than `pace`
+//! apart. Each request a task sends is already resent as its
+//! `RetryPolicy` says, so the scheduler doesn’t try failed tasks again,
+//! which would multiply the attempts.
+
+use crate::config::Config;
+use crate::prelude::*;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub(crate) struct Scheduler {
+    jobs: usize,
+    pace: Option<Duration>,
+    /// The earliest that the next task may start.
+    next_start: Mutex<Instant>,
+}
+
+impl Scheduler {
+    pub fn new(config: &Config) -> Self {
+        Scheduler {
+            jobs: config.get_jobs(),
+            pace: config.get_pace(),
+            next_start: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Runs `task` on each of `items`, returning the results in the same
+    /// order.
+    pub fn run<I, T, F>(&self, items: &[I], task: F) -> Vec<Result<T>>
+    where
+        I: Sync,
+        T: Send,
+        F: Fn(&I) -> Result<T> + Sync,
+    {
+        // Workers take items in order until they run out:
+        let jobs = self.jobs.min(items.len()).max(1);
+        let next = AtomicUsize::new(0);
+        let mut results: Vec<Option<Result<T>>> = items.iter().map(|_| None).collect();
+
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..jobs)
+                .map(|_| {
+                    let (task, next) = (&task, &next);
+                    scope.spawn(move || {
+                        let mut done = Vec::new();
+                        loop {
+                            let i = next.fetch_add(1, Ordering::SeqCst);
+                            match items.get(i) {
+                                Some(item) => {
+                                    self.wait_turn();
+                                    done.push((i, task(item)));
+                                }
+                                None => return done,
+                            }
+                        }
+                    })
+                })
+                .collect();
+
+            for handle in handles {
+                for (i, result) in handle.join().expect("scheduler thread panicked") {
+                    results[i] = Some(result);
+                }
+            }
+        });
+
+        results.into_iter().map(Option::unwrap).collect()
+    }
+
+    /// Blocks until `pace` has passed since the last task started.
+    fn wait_turn(&self) {
+        let pace = match self.pace {
+            Some(pace) => pace,
+            None => return,
+        };
+
+        let start = {
+            let mut next_start = self.next_start.lock().unwrap();
+            let start = (*next_start).max(Instant::now());
+            *next_start = start + pace;
+            start
+        };
+
+        let now = Instant::now();
+        if start > now {
+            thread::sleep(start - now);
+        }
+    }
+}
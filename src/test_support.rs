@@ -0,0 +1,701 @@
+//! An in-process stand-in for the GSC server, for testing `GscClient` end
+//! to end (feature `test-support`).
+//!
+//! The server knows one student, `alice`, whose hw1 is closed and graded
+//! and whose hw2 is open. Each has a few files, which can be downloaded,
+//! replaced, and deleted, and hw1 has a self evaluation. Every request is
+//! recorded so that tests can check what the client sent:
+//!
+//! ```no_run
+//! use gsc_client::test_support::{MockServer, TempDir};
+//! use gsc_client::{GscClient, RemotePattern};
+//!
+//! let server = MockServer::start();
+//! let dir = TempDir::new();
+//! let client = GscClient::with_config(server.config(dir.path())).unwrap();
+//!
+//! let hw2 = RemotePattern { hw: 2, name: "*.cpp".to_owned() };
+//! client.ls(&[hw2], None).unwrap();
+//! assert_eq!(server.requests().last().unwrap().path, "/api/submissions/2/files");
+//! ```
+
+use crate::config::Config;
+use crate::credentials::Credentials;
+use crate::filename;
+use crate::messages::{FilePurpose, UserRole};
+
+use chrono::{Duration, Utc};
+use percent_encoding as enc;
+use serde_json::{json, Value};
+
+use std::collections::{BTreeMap, VecDeque};
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// The user that the server knows and that `MockServer::config` logs in as.
+pub const USER: &str = "alice";
+
+/// An API key that the server accepts for `USER`, as `gsc auth` or
+/// `$GSC_API_KEY` would send it.
+pub const API_KEY: &str = "0123456789abcdef0123456789abcdef01234567";
+
+/// How long the server says that API keys last.
+const KEY_ROTATION_DAYS: u32 = 120;
+
+const COOKIE_KEY: &str = "gsc_session";
+const COOKIE_VALUE: &str = "mock-session";
+
+/// When the fixtures’ files were uploaded.
+const UPLOAD_TIME: &str = "2024-01-15T12:00:00Z";
+
+/// A request as the server received it.
+#[derive(Clone, Debug)]
+pub struct Request {
+    pub method: String,
+    /// The path, with any query string, e.g. `/api/submissions/2/files`.
+    pub path: String,
+    pub body: Vec<u8>,
+}
+
+#[derive(Default)]
+struct State {
+    files: BTreeMap<(usize, String), Vec<u8>>,
+    /// Types set by PATCH; other files’ types are guessed from their names.
+    purposes: BTreeMap<(usize, String), FilePurpose>,
+    /// Media types set by PATCH; other files are `text/plain`.
+    media_types: BTreeMap<(usize, String), String>,
+    /// The reason given for a regrade of hw1 item 1, once one is requested.
+    regrade_reason: Option<String>,
+    /// `USER`’s role, if set; otherwise `student`.
+    role: Option<UserRole>,
+    /// How many files to list per page, if set; otherwise all of them.
+    page_size: Option<usize>,
+    /// Responses to send instead of handling requests, by method, oldest
+    /// first.
+    failures: VecDeque<(String, Response)>,
+    requests: Vec<Request>,
+}
+
+pub struct MockServer {
+    uri: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockServer {
+    /// Starts a server with the standard fixtures on a free local port. It
+    /// runs until the test process exits.
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+        let uri = format!("http://{}", listener.local_addr().unwrap());
+
+        let mut state = State::default();
+        for &(hw, name, contents) in FILES {
+            state.files.insert((hw, name.to_owned()), contents.into());
+        }
+        let state = Arc::new(Mutex::new(state));
+
+        let shared = Arc::clone(&state);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let state = Arc::clone(&shared);
+                thread::spawn(move || serve(stream, &state));
+            }
+        });
+
+        MockServer { uri, state }
+    }
+
+    /// The server’s endpoint, e.g. `http://127.0.0.1:34567`.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// A configuration that talks to this server as `USER`, keeping its
+    /// credentials, state, and history in `dir`.
+    pub fn config(&self, dir: &Path) -> Config {
+        let config = Config::isolated(&self.uri, dir);
+        let creds = Credentials::new(USER, COOKIE_KEY, COOKIE_VALUE);
+        creds
+            .write(config.get_credentials_file().unwrap())
+            .expect("write mock credentials");
+        config
+    }
+
+    /// Every request received so far, oldest first.
+    pub fn requests(&self) -> Vec<Request> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// The contents of file `name` in homework `hw`, if it exists.
+    pub fn file(&self, hw: usize, name: &str) -> Option<Vec<u8>> {
+        let key = (hw, name.to_owned());
+        self.state.lock().unwrap().files.get(&key).cloned()
+    }
+
+    /// The type of file `name` in homework `hw`, if it exists.
+    pub fn purpose(&self, hw: usize, name: &str) -> Option<FilePurpose> {
+        let state = self.state.lock().unwrap();
+        let key = (hw, name.to_owned());
+        state.files.get(&key).map(|_| purpose_of(&state, &key))
+    }
+
+    /// The media type of file `name` in homework `hw`, if it exists.
+    pub fn media_type(&self, hw: usize, name: &str) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        let key = (hw, name.to_owned());
+        state.files.get(&key).map(|_| media_type_of(&state, &key))
+    }
+
+    /// Adds or replaces file `name` in homework `hw`.
+    pub fn put_file(&self, hw: usize, name: &str, contents: impl Into<Vec<u8>>) {
+        let key = (hw, name.to_owned());
+        self.state
+            .lock()
+            .unwrap()
+            .files
+            .insert(key, contents.into());
+    }
+
+    /// Makes `USER` course staff, or a student again.
+    pub fn set_role(&self, role: UserRole) {
+        self.state.lock().unwrap().role = Some(role);
+    }
+
+    /// Makes file lists come `size` files at a time, with a `Link` header
+    /// pointing at the next page.
+    pub fn set_page_size(&self, size: usize) {
+        self.state.lock().unwrap().page_size = Some(size);
+    }
+
+    /// Answers the next `method` request, after any already set to fail,
+    /// with error `status` instead of handling it.
+    pub fn fail_next(&self, method: &str, status: u16) {
+        let response = Response::error(status, "Injected Failure");
+        let mut state = self.state.lock().unwrap();
+        state.failures.push_back((method.to_owned(), response));
+    }
+
+    /// Answers the next `method` request, after any already set to fail,
+    /// with 429 Too Many Requests, asking the client to wait until
+    /// `retry_after`, either a number of seconds or an HTTP date.
+    pub fn rate_limit_next(&self, method: &str, retry_after: &str) {
+        let response = Response {
+            retry_after: Some(retry_after.to_owned()),
+            ..Response::error(429, "Too Many Requests")
+        };
+        let mut state = self.state.lock().unwrap();
+        state.failures.push_back((method.to_owned(), response));
+    }
+}
+
+/// A scratch directory that is deleted when dropped.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new() -> Self {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let name = format!(
+            "gsc-test-{}-{}",
+            process::id(),
+            COUNT.fetch_add(1, Ordering::SeqCst)
+        );
+        let path = env::temp_dir().join(name);
+        fs::create_dir_all(&path).expect("create temporary directory");
+        TempDir(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Default for TempDir {
+    fn default() -> Self {
+        TempDir::new()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+// Fixtures
+
+/// `(hw, name, contents)` of the files that the server starts with.
+const FILES: &[(usize, &str, &str)] = &[
+    (1, "hello.cpp", "int main() { }\n"),
+    (2, "main.cpp", "#include \"shape.h\"\n\nint main() { }\n"),
+    (2, "shape.h", "struct Shape { };\n"),
+    (2, "test.cpp", "#include \"shape.h\"\n"),
+    (2, "Makefile", "all:\n"),
+];
+
+/// The grade spreadsheet, as `/api/grades.csv` gives it.
+const GRADES: &str = "username,hw1,hw1 self eval,hw2,exam1\n\
+                      alice,0.9,1.0,,45\n\
+                      bob,0.75,0.5,0.8,38\n\
+                      \"o'brien, pat\",1.0,1.0,1.0,50\n";
+
+/// `(hw, status, grade, days from now until due)` for `USER`’s homeworks.
+const HOMEWORKS: &[(usize, &str, f64, i64)] = &[(1, "closed", 0.9, -7), (2, "open", 0.0, 7)];
+
+fn purpose(name: &str) -> FilePurpose {
+    if name.contains("test") {
+        FilePurpose::Test
+    } else if name.ends_with(".cpp") || name.ends_with(".h") {
+        FilePurpose::Source
+    } else if name == "Makefile" || name == "CMakeLists.txt" {
+        FilePurpose::Config
+    } else if name.ends_with(".zip") {
+        FilePurpose::Forbidden
+    } else {
+        FilePurpose::Resource
+    }
+}
+
+fn date(days: i64) -> String {
+    (Utc::now() + Duration::days(days)).to_rfc3339()
+}
+
+fn user_short() -> Value {
+    json!({ "name": USER, "uri": format!("/api/users/{}", USER) })
+}
+
+fn submission_short(&(hw, status, grade, _): &(usize, &str, f64, i64)) -> Value {
+    json!({
+        "assignment_number": hw,
+        "id": hw,
+        "uri": format!("/api/submissions/{}", hw),
+        "status": status,
+        "grade": grade,
+        "owner1": user_short(),
+    })
+}
+
+fn submission(state: &State, &(hw, status, grade, due): &(usize, &str, f64, i64)) -> Value {
+    let bytes_used: usize = files_of(state, hw)
+        .map(|(_, contents)| contents.len())
+        .sum();
+
+    json!({
+        "assignment_number": hw,
+        "id": hw,
+        "uri": format!("/api/submissions/{}", hw),
+        "grade": grade,
+        "files_uri": format!("/api/submissions/{}/files", hw),
+        "evals_uri": format!("/api/submissions/{}/evals", hw),
+        "owner1": user_short(),
+        "bytes_used": bytes_used,
+        "bytes_quota": 1 << 20,
+        "open_date": date(due - 14),
+        "due_date": date(due),
+        "eval_date": date(due + 2),
+        "last_modified": UPLOAD_TIME,
+        "eval_status": if status == "closed" { "complete" } else { "empty" },
+        "status": status,
+    })
+}
+
+fn user(state: &State) -> Value {
+    json!({
+        "name": USER,
+        "uri": format!("/api/users/{}", USER),
+        "submissions_uri": format!("/api/users/{}/submissions", USER),
+        "role": state.role.unwrap_or(UserRole::Student),
+        "exam_grades": [{ "number": 1, "points": 45, "possible": 50 }],
+        "partner_requests": [
+            { "assignment_number": 2, "user": "bob", "status": "incoming" },
+        ],
+        "submissions": submissions(),
+    })
+}
+
+fn submissions() -> Value {
+    HOMEWORKS.iter().map(submission_short).collect()
+}
+
+fn files_of(state: &State, hw: usize) -> impl Iterator<Item = (&str, &Vec<u8>)> + '_ {
+    state
+        .files
+        .iter()
+        .filter(move |((file_hw, _), _)| *file_hw == hw)
+        .map(|((_, name), contents)| (name.as_str(), contents))
+}
+
+fn purpose_of(state: &State, key: &(usize, String)) -> FilePurpose {
+    match state.purposes.get(key) {
+        Some(&purpose) => purpose,
+        None => purpose(&key.1),
+    }
+}
+
+fn media_type_of(state: &State, key: &(usize, String)) -> String {
+    match state.media_types.get(key) {
+        Some(media_type) => media_type.clone(),
+        None if key.1.ends_with(".json") => "application/json".to_owned(),
+        None => "text/plain".to_owned(),
+    }
+}
+
+fn file_meta(state: &State, hw: usize, name: &str, contents: &[u8]) -> Value {
+    let encoded = enc::utf8_percent_encode(name, enc::NON_ALPHANUMERIC);
+
+    json!({
+        "assignment_number": hw,
+        "byte_count": contents.len(),
+        "media_type": media_type_of(state, &(hw, name.to_owned())),
+        "name": name,
+        "purpose": purpose_of(state, &(hw, name.to_owned())),
+        "upload_time": UPLOAD_TIME,
+        "uri": format!("/api/submissions/{}/files/{}", hw, encoded),
+    })
+}
+
+fn eval_short(hw: usize) -> Value {
+    json!({
+        "uri": format!("/api/submissions/{}/evals/1", hw),
+        "sequence": 1,
+        "submission_uri": format!("/api/submissions/{}", hw),
+        "type": "scale",
+    })
+}
+
+fn eval(state: &State, hw: usize) -> Value {
+    let mut eval = eval_short(hw);
+    eval["prompt"] = json!("Does your program compile without warnings?");
+    eval["value"] = json!(1.0);
+    eval["self_eval"] = json!({
+        "uri": format!("/api/submissions/{}/evals/1/self", hw),
+        "score": 1.0,
+        "explanation": "It does.",
+        "permalink": "mock-permalink",
+    });
+    eval["grader_eval"] = json!({
+        "uri": format!("/api/submissions/{}/evals/1/grader", hw),
+        "grader": "tom",
+        "score": 1.0,
+        "explanation": "No warnings.",
+        "status": if state.regrade_reason.is_some() { "regrade" } else { "ready" },
+    });
+    if let Some(reason) = &state.regrade_reason {
+        eval["grader_eval"]["regrade_reason"] = json!(reason);
+    }
+    eval
+}
+
+// Serving
+
+struct Response {
+    status: u16,
+    content_type: String,
+    /// The `rel="next"` target of a `Link` header, if any.
+    next: Option<String>,
+    retry_after: Option<String>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn json(value: Value) -> Self {
+        Response {
+            status: 200,
+            content_type: "application/json".to_owned(),
+            next: None,
+            retry_after: None,
+            body: value.to_string().into_bytes(),
+        }
+    }
+
+    fn text(body: impl Into<Vec<u8>>) -> Self {
+        Response {
+            status: 200,
+            content_type: "text/plain".to_owned(),
+            next: None,
+            retry_after: None,
+            body: body.into(),
+        }
+    }
+
+    /// An error in the format that the real server uses.
+    fn error(status: u16, title: &str) -> Self {
+        Response {
+            status,
+            ..Response::json(json!({
+                "status": status,
+                "title": title,
+                "message": format!("The mock server says: {}", title),
+            }))
+        }
+    }
+}
+
+fn serve(stream: TcpStream, state: &Mutex<State>) {
+    let mut reader = BufReader::new(match stream.try_clone() {
+        Ok(stream) => stream,
+        Err(_) => return,
+    });
+    let mut writer = stream;
+    let expected_cookies = [
+        format!("{}={}", COOKIE_KEY, COOKIE_VALUE),
+        format!("gsc_api_key={}", API_KEY),
+    ];
+
+    while let Ok(Some((request, cookie))) = read_request(&mut reader) {
+        let response = {
+            let mut state = state.lock().unwrap();
+            let response = if request.path == "/api/version" {
+                Response::json(json!({
+                    "api_version": "1.0",
+                    "server_version": "mock",
+                    "key_rotation_days": KEY_ROTATION_DAYS,
+                }))
+            } else if let Some(response) = take_failure(&mut state, &request.method) {
+                response
+            } else if !expected_cookies
+                .iter()
+                .any(|c| cookie.as_deref() == Some(c))
+            {
+                Response::error(401, "Unauthorized")
+            } else {
+                route(&mut state, &request)
+            };
+            state.requests.push(request);
+            response
+        };
+
+        // One write per response, so Nagle’s algorithm doesn’t stall us:
+        let link = match &response.next {
+            Some(next) => format!("Link: <{}>; rel=\"next\"\r\n", next),
+            None => String::new(),
+        };
+        let retry_after = match &response.retry_after {
+            Some(retry_after) => format!("Retry-After: {}\r\n", retry_after),
+            None => String::new(),
+        };
+        let mut bytes = format!(
+            "HTTP/1.1 {} Mock\r\nContent-Type: {}\r\nContent-Length: {}\r\n{}{}\r\n",
+            response.status,
+            response.content_type,
+            response.body.len(),
+            link,
+            retry_after
+        )
+        .into_bytes();
+        bytes.extend(response.body);
+
+        if writer.write_all(&bytes).is_err() {
+            return;
+        }
+    }
+}
+
+/// The first failure set for `method`, if any.
+fn take_failure(state: &mut State, method: &str) -> Option<Response> {
+    let index = state.failures.iter().position(|(m, _)| m == method)?;
+    state.failures.remove(index).map(|(_, response)| response)
+}
+
+/// Reads one request and its `Cookie` header, or `None` at the end of the
+/// connection.
+fn read_request(reader: &mut impl BufRead) -> io::Result<Option<(Request, Option<String>)>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+
+    let mut words = line.split_whitespace();
+    let method = words.next().unwrap_or_default().to_owned();
+    let path = words.next().unwrap_or_default().to_owned();
+
+    let mut length = 0;
+    let mut cookie = None;
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some((key, value)) = header.split_once(':') {
+            let value = value.trim();
+            if key.eq_ignore_ascii_case("content-length") {
+                length = value.parse().unwrap_or(0);
+            } else if key.eq_ignore_ascii_case("cookie") {
+                cookie = Some(value.to_owned());
+            }
+        }
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    Ok(Some((Request { method, path, body }, cookie)))
+}
+
+/// The value of query parameter `name` in `path`, if any.
+fn query_param<'a>(path: &'a str, name: &str) -> Option<&'a str> {
+    path.split_once('?')?
+        .1
+        .split('&')
+        .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+}
+
+fn route(state: &mut State, request: &Request) -> Response {
+    let path = request.path.split('?').next().unwrap_or_default();
+    let segments: Vec<_> = path.trim_matches('/').split('/').collect();
+    let homework = |hw: &str| {
+        hw.parse::<usize>()
+            .ok()
+            .and_then(|hw| HOMEWORKS.iter().find(|fixture| fixture.0 == hw))
+    };
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["api", "whoami"]) => Response::text(USER),
+
+        ("GET", ["api", "grades.csv"]) => Response::text(GRADES),
+
+        ("GET", ["api", "users", USER]) => Response::json(user(state)),
+
+        ("GET", ["api", "users", USER, "submissions"]) => Response::json(submissions()),
+
+        ("GET", ["api", "submissions", spec]) if spec.starts_with("hw") => {
+            match homework(&spec[2..]) {
+                Some(fixture) => Response::json(json!([submission_short(fixture)])),
+                None => Response::error(404, "Not Found"),
+            }
+        }
+
+        ("GET", ["api", "submissions", hw]) => match homework(hw) {
+            Some(fixture) => Response::json(submission(state, fixture)),
+            None => Response::error(404, "Not Found"),
+        },
+
+        ("GET", ["api", "submissions", hw, "files"]) => match homework(hw) {
+            Some(&(hw, ..)) => {
+                let files: Vec<_> = files_of(state, hw)
+                    .map(|(name, contents)| file_meta(state, hw, name, contents))
+                    .collect();
+                let size = state.page_size.unwrap_or(files.len()).max(1);
+                let page = query_param(&request.path, "page")
+                    .and_then(|page| page.parse().ok())
+                    .filter(|&page| page > 0)
+                    .unwrap_or(1);
+                let start = (page - 1) * size;
+                let end = files.len().min(start + size);
+                Response {
+                    next: Some(format!("{}?page={}", path, page + 1)).filter(|_| end < files.len()),
+                    ..Response::json(files[start.min(end)..end].into())
+                }
+            }
+            None => Response::error(404, "Not Found"),
+        },
+
+        (method, ["api", "submissions", hw, "files", name]) => {
+            let hw = match homework(hw) {
+                Some(&(hw, ..)) => hw,
+                None => return Response::error(404, "Not Found"),
+            };
+            let name = filename::decode(name);
+            let key = (hw, name.into_owned());
+
+            match method {
+                "GET" => match state.files.get(&key) {
+                    Some(contents) => Response {
+                        content_type: media_type_of(state, &key),
+                        ..Response::text(contents.clone())
+                    },
+                    None => Response::error(404, "Not Found"),
+                },
+                "PUT" => {
+                    state.purposes.remove(&key);
+                    state.media_types.remove(&key);
+                    state.files.insert(key.clone(), request.body.clone());
+                    Response::json(file_meta(state, hw, &key.1, &request.body))
+                }
+                "PATCH" if state.files.contains_key(&key) => {
+                    // Only changing the type and media type is supported:
+                    let change: Value = match serde_json::from_slice(&request.body) {
+                        Ok(change) => change,
+                        Err(_) => return Response::error(400, "Bad Request"),
+                    };
+                    let purpose = change.get("purpose").cloned().map(serde_json::from_value);
+                    let media_type = change.get("media_type").and_then(Value::as_str);
+                    match (purpose, media_type) {
+                        (Some(Ok(purpose)), None) => {
+                            state.purposes.insert(key.clone(), purpose);
+                        }
+                        (None, Some(media_type)) => {
+                            state.media_types.insert(key.clone(), media_type.to_owned());
+                        }
+                        _ => return Response::error(400, "Bad Request"),
+                    }
+                    Response::json(file_meta(state, hw, &key.1, &state.files[&key]))
+                }
+                "DELETE" => match state.files.get(&key) {
+                    Some(contents) => {
+                        let meta = file_meta(state, hw, &key.1, contents);
+                        state.files.remove(&key);
+                        state.purposes.remove(&key);
+                        state.media_types.remove(&key);
+                        Response::json(meta)
+                    }
+                    None => Response::error(404, "Not Found"),
+                },
+                _ => Response::error(405, "Method Not Allowed"),
+            }
+        }
+
+        ("GET", ["api", "submissions", hw, "evals"]) => match homework(hw) {
+            Some(&(1, ..)) => Response::json(json!([eval_short(1)])),
+            Some(_) => Response::json(json!([])),
+            None => Response::error(404, "Not Found"),
+        },
+
+        ("GET", ["api", "submissions", "1", "evals", "1"]) => Response::json(eval(state, 1)),
+
+        ("PUT", ["api", "submissions", "1", "evals", "1", "self"]) => {
+            let mut self_eval: Value = match serde_json::from_slice(&request.body) {
+                Ok(self_eval) => self_eval,
+                Err(_) => return Response::error(400, "Bad Request"),
+            };
+            self_eval["permalink"] = json!("mock-permalink");
+            Response::json(self_eval)
+        }
+
+        ("PATCH", ["api", "submissions", "1", "evals", "1", "grader"]) => {
+            let change: Value = match serde_json::from_slice(&request.body) {
+                Ok(change) => change,
+                Err(_) => return Response::error(400, "Bad Request"),
+            };
+            if change["status"] == "regrade" {
+                state.regrade_reason = change["regrade_reason"].as_str().map(str::to_owned);
+            }
+            Response::json(eval(state, 1)["grader_eval"].take())
+        }
+
+        ("PUT", ["api", "submissions", "1", "evals", "1", "grader"]) => {
+            match serde_json::from_slice::<Value>(&request.body) {
+                Ok(grader_eval) => Response::json(grader_eval),
+                Err(_) => Response::error(400, "Bad Request"),
+            }
+        }
+
+        ("POST", ["api", "audit"]) => Response::json(json!({})),
+
+        _ => Response::error(404, "Not Found"),
+    }
+}
@@ -0,0 +1,51 @@
+//! Bandwidth limiting (`--limit-rate`) for file transfers.
+
+use std::io::{self, Read};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How many times a second, at most, a throttled stream wakes up.
+const TICKS_PER_SECOND: u64 = 10;
+
+/// A reader that, given a rate in bytes per second, sleeps as needed to
+/// keep its average throughput from exceeding it. With no rate, it just
+/// passes reads through.
+pub struct Throttled<R> {
+    inner: R,
+    rate: Option<u64>,
+    bytes: u64,
+    start: Instant,
+}
+
+impl<R: Read> Throttled<R> {
+    pub fn new(inner: R, rate: Option<u64>) -> Self {
+        Throttled {
+            inner,
+            rate: rate.filter(|&rate| rate > 0),
+            bytes: 0,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl<R: Read> Read for Throttled<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let rate = match self.rate {
+            Some(rate) => rate,
+            None => return self.inner.read(buf),
+        };
+
+        // Reading in small pieces keeps the stream from bursting:
+        let chunk = (rate / TICKS_PER_SECOND).max(1) as usize;
+        let len = buf.len().min(chunk);
+        let count = self.inner.read(&mut buf[..len])?;
+        self.bytes += count as u64;
+
+        let due = Duration::from_secs_f64(self.bytes as f64 / rate as f64);
+        if let Some(wait) = due.checked_sub(self.start.elapsed()) {
+            thread::sleep(wait);
+        }
+
+        Ok(count)
+    }
+}
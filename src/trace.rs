@@ -0,0 +1,96 @@
+use crate::messages::UtcDateTime;
+use crate::prelude::*;
+
+use reqwest::{blocking, header};
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Records a transcript of HTTP traffic, for attaching to bug reports.
+#[derive(Debug)]
+pub(crate) struct Tracer {
+    file: Mutex<fs::File>,
+    started: AtomicBool,
+}
+
+impl Tracer {
+    pub fn new(path: &Path) -> Result<Self> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .chain_err(|| format!("Could not open trace file: {}", path.display()))?;
+
+        Ok(Tracer {
+            file: Mutex::new(file),
+            started: AtomicBool::new(false),
+        })
+    }
+
+    pub fn request(&self, request: &blocking::Request) -> Result<()> {
+        let mut file = self.file.lock().expect("trace file lock poisoned");
+
+        if !self.started.swap(true, Ordering::Relaxed) {
+            writeln!(
+                file,
+                "=== gsc {} at {}\n",
+                env!("CARGO_PKG_VERSION"),
+                UtcDateTime::now()
+            )?;
+        }
+
+        writeln!(file, "> {} {}", request.method(), request.url())?;
+        write_headers(&mut file, "> ", request.headers())?;
+
+        match request.body().map(|body| body.as_bytes()) {
+            None => {}
+            Some(Some(bytes)) => writeln!(file, ">\n{}", String::from_utf8_lossy(bytes))?,
+            Some(None) => writeln!(file, ">\n[streamed body]")?,
+        }
+
+        writeln!(file)?;
+        Ok(())
+    }
+
+    /// Records `response`, returning an equivalent response since doing
+    /// so consumes the body.
+    pub fn response(&self, response: blocking::Response) -> Result<blocking::Response> {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes()?;
+
+        {
+            let mut file = self.file.lock().expect("trace file lock poisoned");
+            writeln!(file, "< {}", status)?;
+            write_headers(&mut file, "< ", &headers)?;
+            writeln!(file, "<\n{}\n", String::from_utf8_lossy(&body))?;
+        }
+
+        let mut rebuilt = http::Response::builder().status(status);
+        for (name, value) in &headers {
+            rebuilt = rebuilt.header(name, value);
+        }
+
+        Ok(rebuilt
+            .body(body.to_vec())
+            .expect("traced response is well formed")
+            .into())
+    }
+}
+
+fn write_headers(file: &mut fs::File, prefix: &str, headers: &header::HeaderMap) -> Result<()> {
+    for (name, value) in headers {
+        let value = if name == header::COOKIE || name == header::SET_COOKIE {
+            "[redacted]"
+        } else {
+            value.to_str().unwrap_or("[binary]")
+        };
+
+        writeln!(file, "{}{}: {}", prefix, name, value)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+//! Letting the user know when a newer `gsc` is available.
+//!
+//! At most once a day, a command looks up the newest client release that
+//! the server reports at `/api/version`, and mentions it at the end if
+//! it’s newer than we are. What the handshake already learned, or a
+//! recently cached response, is used when there is one; otherwise we ask
+//! in a background thread. The time of the last check is kept in the
+//! state file.
+
+use crate::config::Config;
+use crate::messages::{ServerInfo, UtcDateTime};
+use crate::prelude::*;
+
+use serde_derive::{Deserialize, Serialize};
+
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+const CHECK_INTERVAL_HOURS: i64 = 24;
+const CHECK_TIMEOUT_SECS: u64 = 5;
+
+/// How long to wait at the end of a command for a check that hasn’t
+/// finished yet.
+const FINISH_WAIT_MS: u64 = 500;
+
+/// An update check running in the background.
+pub struct UpdateCheck(mpsc::Receiver<Option<String>>);
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct State {
+    #[serde(default)]
+    last_update_check: Option<UtcDateTime>,
+}
+
+impl GscClient {
+    /// Starts checking for a newer release, unless disabled, offline, or
+    /// already checked within the last day.
+    pub fn start_update_check(&self) -> Option<UpdateCheck> {
+        let config = self.config();
+
+        if !config.update_check() || config.offline() {
+            return None;
+        }
+
+        let path = config.get_state_file()?;
+        let mut state = read_state(path);
+
+        if let Some(last) = &state.last_update_check {
+            if -last.time_until() < chrono::Duration::hours(CHECK_INTERVAL_HOURS) {
+                return None;
+            }
+        }
+
+        // Recorded up front, so that a slow or failed check doesn’t get
+        // retried by every command.
+        state.last_update_check = Some(UtcDateTime::now());
+        if let Err(e) = write_state(path, &state) {
+            ve3!("Could not write state file {}: {}", path.display(), e);
+            return None;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+
+        if let Some(info) = self.known_server_info() {
+            let _ = sender.send(info.client_version);
+            return Some(UpdateCheck(receiver));
+        }
+
+        let request = self
+            .http
+            .get(&version_uri(config))
+            .timeout(Duration::from_secs(CHECK_TIMEOUT_SECS));
+
+        thread::spawn(move || {
+            let latest = request
+                .send()
+                .ok()
+                .filter(|response| response.status().is_success())
+                .and_then(|response| response.json::<ServerInfo>().ok())
+                .and_then(|info| info.client_version);
+            let _ = sender.send(latest);
+        });
+
+        Some(UpdateCheck(receiver))
+    }
+
+    /// Prints a notice if the check found a newer release. Doesn’t wait
+    /// long for a check that’s still running.
+    pub fn finish_update_check(&self, check: Option<UpdateCheck>) {
+        let latest = check
+            .and_then(|UpdateCheck(receiver)| {
+                receiver
+                    .recv_timeout(Duration::from_millis(FINISH_WAIT_MS))
+                    .ok()
+            })
+            .flatten();
+
+        if let Some(latest) = latest {
+            if is_newer(&latest, env!("CARGO_PKG_VERSION")) {
+                ve1!(
+                    "gsc {} is available (you have {}).",
+                    latest,
+                    env!("CARGO_PKG_VERSION")
+                );
+            }
+        }
+    }
+
+    /// What the server said about itself, if the handshake already asked
+    /// or we have a response cached within the check interval.
+    fn known_server_info(&self) -> Option<ServerInfo> {
+        if let Some(info) = self.server_info() {
+            return Some(info.clone());
+        }
+
+        let entry = self
+            .cache
+            .as_ref()?
+            .lookup(None, &version_uri(self.config()))?;
+        if -entry.fetched.time_until() >= chrono::Duration::hours(CHECK_INTERVAL_HOURS) {
+            return None;
+        }
+
+        ve3!(
+            "Checking for updates using the server version cached {}",
+            entry.fetched
+        );
+        crate::messages::from_slice(entry.body.as_bytes()).ok()
+    }
+}
+
+fn version_uri(config: &Config) -> String {
+    format!("{}/api/version", config.get_endpoint())
+}
+
+fn read_state(path: &Path) -> State {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn write_state(path: &Path, state: &State) -> Result<()> {
+    fs::write(path, serde_json::to_string(state)?)?;
+    Ok(())
+}
+
+/// Whether version `a` is newer than version `b`, comparing the numeric
+/// `MAJOR.MINOR.PATCH` parts, and then ranking a release above any of its
+/// pre-releases.
+fn is_newer(a: &str, b: &str) -> bool {
+    match (parse_version(a), parse_version(b)) {
+        (Some(a), Some(b)) => a > b,
+        _ => false,
+    }
+}
+
+fn parse_version(version: &str) -> Option<(u64, u64, u64, bool)> {
+    let version = version.trim().trim_start_matches('v');
+    let (numbers, pre) = match version.split_once('-') {
+        Some((numbers, _)) => (numbers, true),
+        None => (version, false),
+    };
+
+    let mut parts = numbers.split('.').map(str::parse::<u64>);
+    let mut next = || parts.next().unwrap_or(Ok(0)).ok();
+
+    Some((next()?, next()?, next()?, !pre))
+}
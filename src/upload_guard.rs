@@ -0,0 +1,70 @@
+//! Checks for files that are almost certainly not meant to be submitted,
+//! like the executable instead of the source it was built from. These
+//! only look at the file’s size, name, and first few bytes.
+
+use thousands::Separable;
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Extensions of compiled files.
+const COMPILED: &[&str] = &[
+    "o", "obj", "a", "lib", "so", "dylib", "dll", "exe", "class", "pyc", "pyo", "gch",
+];
+
+/// Leading bytes of executables and object files: ELF, Mach-O (both
+/// widths and byte orders), and Windows PE.
+const MAGIC: &[&[u8]] = &[
+    b"\x7fELF",
+    b"\xfe\xed\xfa\xce",
+    b"\xfe\xed\xfa\xcf",
+    b"\xce\xfa\xed\xfe",
+    b"\xcf\xfa\xed\xfe",
+    b"MZ",
+];
+
+/// Why the file at `path` looks wrong to upload, if it does: it’s compiled,
+/// or it’s over `max_size` bytes.
+pub fn inspect(path: &Path, max_size: Option<u64>) -> io::Result<Option<String>> {
+    let meta = fs::metadata(path)?;
+    if !meta.is_file() {
+        return Ok(None);
+    }
+
+    let mut start = Vec::with_capacity(4);
+    fs::File::open(path)?.take(4).read_to_end(&mut start)?;
+
+    Ok(inspect_contents(path, meta.len(), &start, max_size))
+}
+
+/// Why contents named `name`, `size` bytes long and beginning with `start`,
+/// look wrong to upload, if they do. This is for uploads that don’t come
+/// from a local file, such as archive entries and git blobs.
+pub fn inspect_contents(
+    name: &Path,
+    size: u64,
+    start: &[u8],
+    max_size: Option<u64>,
+) -> Option<String> {
+    let extension = name
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    if let Some(ext) = extension.filter(|ext| COMPILED.contains(&ext.as_str())) {
+        return Some(format!("‘.{}’ files are compiled, not source", ext));
+    }
+
+    if MAGIC.iter().any(|magic| start.starts_with(magic)) {
+        return Some("it looks like an executable or object file".to_owned());
+    }
+
+    match max_size {
+        Some(max_size) if size > max_size => Some(format!(
+            "it has {} bytes, more than max_upload_size ({})",
+            size.separate_with_commas(),
+            max_size.separate_with_commas()
+        )),
+        _ => None,
+    }
+}
@@ -1,5 +1,10 @@
+use std::borrow::Cow;
 use std::fmt::{Display, Formatter, Result};
+use std::io::IsTerminal;
+use std::time::Instant;
 use textwrap;
+use thousands::Separable;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 pub struct Percentage(pub f64);
 
@@ -9,9 +14,172 @@ impl Display for Percentage {
     }
 }
 
-const HANGING_INDENT: &str = "    ";
+/// Formats a length of time using its two largest units, e.g.,
+/// “2 days 4 hours”. Negative durations are formatted by magnitude.
+pub struct HumanDuration(pub chrono::Duration);
 
-pub fn hanging(text: &str) -> String {
-    let width = textwrap::termwidth() - HANGING_INDENT.len();
-    textwrap::indent(&textwrap::fill(text, width), HANGING_INDENT)
+impl Display for HumanDuration {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let total = self.0.num_minutes().abs();
+
+        if total == 0 {
+            return write!(f, "less than a minute");
+        }
+
+        let units = [
+            (total / (24 * 60), "day"),
+            (total / 60 % 24, "hour"),
+            (total % 60, "minute"),
+        ];
+
+        let mut parts = units
+            .iter()
+            .skip_while(|(n, _)| *n == 0)
+            .take(2)
+            .filter(|(n, _)| *n != 0)
+            .map(|(n, unit)| format!("{} {}{}", n, unit, if *n == 1 { "" } else { "s" }));
+
+        if let Some(first) = parts.next() {
+            write!(f, "{}", first)?;
+        }
+
+        for part in parts {
+            write!(f, " {}", part)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats the time remaining until a deadline, or, if the deadline has
+/// passed, how long ago that was.
+pub struct Countdown(pub chrono::Duration);
+
+impl Display for Countdown {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if self.0 > chrono::Duration::zero() {
+            write!(f, "{}", HumanDuration(self.0))
+        } else {
+            write!(f, "*** PAST DUE *** ({} ago)", HumanDuration(self.0))
+        }
+    }
+}
+
+/// Tallies what a bulk operation, such as copying or deleting a whole
+/// homework, did to how many files, for a one-line summary at the end.
+pub struct Summary {
+    verb: &'static str,
+    show_rate: bool,
+    files: usize,
+    bytes: u64,
+    skipped: usize,
+    start: Instant,
+}
+
+impl Summary {
+    /// For transfers, whose summary includes their throughput.
+    pub fn transfer(verb: &'static str) -> Self {
+        Summary {
+            show_rate: true,
+            ..Summary::new(verb)
+        }
+    }
+
+    pub fn new(verb: &'static str) -> Self {
+        Summary {
+            verb,
+            show_rate: false,
+            files: 0,
+            bytes: 0,
+            skipped: 0,
+            start: Instant::now(),
+        }
+    }
+
+    pub fn add(&mut self, bytes: u64) {
+        self.files += 1;
+        self.bytes += bytes;
+    }
+
+    pub fn skip(&mut self) {
+        self.skipped += 1;
+    }
+
+    /// How many files were either done or skipped.
+    pub fn count(&self) -> usize {
+        self.files + self.skipped
+    }
+}
+
+/// E.g., “Copied 12 files (48,213 bytes) in 1.3 s, 37.1 KB/s; skipped 2.”
+impl Display for Summary {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let seconds = self.start.elapsed().as_secs_f64();
+
+        write!(
+            f,
+            "{} {} file{} ({} bytes) in {:.1} s",
+            self.verb,
+            self.files,
+            if self.files == 1 { "" } else { "s" },
+            self.bytes.separate_with_commas(),
+            seconds
+        )?;
+
+        if self.show_rate && seconds > 0.0 {
+            write!(f, ", {}/s", DecimalBytes(self.bytes as f64 / seconds))?;
+        }
+
+        if self.skipped > 0 {
+            write!(f, "; skipped {}", self.skipped)?;
+        }
+
+        write!(f, ".")
+    }
+}
+
+/// Formats a number of bytes with a decimal (SI) unit, e.g., “37.1 KB”.
+struct DecimalBytes(f64);
+
+impl Display for DecimalBytes {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let mut amount = self.0;
+
+        for unit in &["B", "KB", "MB"] {
+            if amount < 1000.0 {
+                return write!(f, "{:.1} {}", amount, unit);
+            }
+            amount /= 1000.0;
+        }
+
+        write!(f, "{:.1} GB", amount)
+    }
+}
+
+/// The width of the terminal that standard output goes to, or `None` if
+/// it isn’t a terminal, so output for scripts is never cut short.
+pub fn stdout_width() -> Option<usize> {
+    std::io::stdout().is_terminal().then(textwrap::termwidth)
+}
+
+/// Shortens `text` to at most `width` columns, counting wide characters
+/// as two, and ends it with an ellipsis if anything was cut.
+pub fn truncate_to_width(text: &str, width: usize) -> Cow<'_, str> {
+    if text.width() <= width {
+        return Cow::Borrowed(text);
+    }
+
+    let mut result = String::new();
+    let mut used = 1; // for the ellipsis
+
+    for c in text.chars() {
+        used += c.width().unwrap_or(0);
+        if used > width {
+            break;
+        }
+        result.push(c);
+    }
+
+    result.push('…');
+    Cow::Owned(result)
 }
@@ -0,0 +1,171 @@
+//! Round trips through the archive writers and readers, with the entries
+//! that the formats make awkward: long paths, empty files, and several
+//! files in a row.
+
+use gsc_client::archive::{entries, for_each_file, Format, Writer};
+
+use std::fs;
+use std::io::{self, Cursor, Read};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const FORMATS: &[Format] = &[Format::Tar, Format::TarGz, Format::Zip];
+
+fn mtime() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(1_600_000_000)
+}
+
+fn write(format: Format, entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = Writer::new(format, Cursor::new(Vec::new()));
+
+    for &(path, contents) in entries {
+        writer
+            .append(path, contents.len() as u64, mtime(), &mut { contents })
+            .unwrap();
+    }
+
+    writer.finish().unwrap().into_inner()
+}
+
+fn read(format: Format, archive: Vec<u8>) -> Vec<(String, Vec<u8>)> {
+    let mut entries = Vec::new();
+
+    for_each_file(format, Cursor::new(archive), |path, size, contents| {
+        let mut buf = Vec::new();
+        contents.read_to_end(&mut buf)?;
+        assert_eq!(buf.len() as u64, size, "{}", path);
+        entries.push((path.to_owned(), buf));
+        Ok::<_, io::Error>(())
+    })
+    .unwrap();
+
+    entries
+}
+
+fn assert_round_trip(entries: &[(&str, &[u8])]) {
+    let expected: Vec<_> = entries
+        .iter()
+        .map(|&(path, contents)| (path.to_owned(), contents.to_vec()))
+        .collect();
+
+    for &format in FORMATS {
+        let archive = write(format, entries);
+        assert_eq!(read(format, archive), expected, "{:?}", format);
+    }
+}
+
+#[test]
+fn round_trips_several_files() {
+    assert_round_trip(&[
+        ("hw2/Makefile", b"all: main\n"),
+        ("hw2/src/main.cpp", b"int main() {}\n"),
+        ("hw2/src/shape.h", b"#pragma once\n"),
+        ("hw2/test/test.cpp", b"// tests\n"),
+    ]);
+}
+
+#[test]
+fn round_trips_empty_files() {
+    assert_round_trip(&[
+        ("hw2/empty.txt", b""),
+        ("hw2/src/main.cpp", b"int main() {}\n"),
+        ("hw2/also empty", b""),
+    ]);
+}
+
+#[test]
+fn round_trips_long_paths() {
+    let long_name = format!("hw2/src/{}.cpp", "x".repeat(120));
+    let long_dirs = format!("{}/main.cpp", ["directory"; 20].join("/"));
+    assert!(long_name.len() > 100 && long_dirs.len() > 155);
+
+    assert_round_trip(&[
+        (&long_name, b"// long name\n"),
+        (&long_dirs, b"// long directories\n"),
+    ]);
+}
+
+#[test]
+fn round_trips_large_files() {
+    let contents: Vec<u8> = (0..200_000u32).map(|i| (i * 7 % 251) as u8).collect();
+    assert_round_trip(&[("hw2/data.bin", &contents)]);
+}
+
+#[test]
+fn reads_entries_on_another_thread() {
+    let big: Vec<u8> = (0..300_000u32).map(|i| (i % 253) as u8).collect();
+    for &format in FORMATS {
+        let name = format!("gsc-archive-test-{}-{:?}", std::process::id(), format);
+        let path = std::env::temp_dir().join(name);
+        let archive = write(
+            format,
+            &[("a", b"first"), ("skipped", &big), ("b", &big), ("c", b"")],
+        );
+        fs::write(&path, archive).unwrap();
+
+        let mut read = Vec::new();
+        for entry in entries(format, fs::File::open(&path).unwrap()) {
+            let mut entry = entry.unwrap();
+            if entry.path == "skipped" {
+                continue;
+            }
+
+            let mut contents = Vec::new();
+            entry.contents.read_to_end(&mut contents).unwrap();
+            assert_eq!(contents.len() as u64, entry.size);
+            read.push((entry.path, contents));
+        }
+        fs::remove_file(&path).unwrap();
+
+        let expected = vec![
+            ("a".to_owned(), b"first".to_vec()),
+            ("b".to_owned(), big.clone()),
+            ("c".to_owned(), Vec::new()),
+        ];
+        assert_eq!(read, expected, "{:?}", format);
+    }
+}
+
+#[test]
+fn rejects_a_zip_directory_past_the_end() {
+    let mut archive = write(Format::Zip, &[("hw2/main.cpp", b"int main() {}\n")]);
+
+    // Claim the central directory is huge:
+    let eocd = archive.len() - 22;
+    archive[eocd + 12..eocd + 16].copy_from_slice(&0x7fff_ffffu32.to_le_bytes());
+
+    let result = for_each_file(Format::Zip, Cursor::new(archive), |_, _, _| {
+        Ok::<_, io::Error>(())
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn refuses_contents_of_the_wrong_size() {
+    for &format in FORMATS {
+        let mut writer = Writer::new(format, Cursor::new(Vec::new()));
+        let short = writer.append("hw2/short", 10, mtime(), &mut &b"12345"[..]);
+        assert!(short.is_err(), "{:?}", format);
+
+        let mut writer = Writer::new(format, Cursor::new(Vec::new()));
+        let long = writer.append("hw2/long", 3, mtime(), &mut &b"12345"[..]);
+        assert!(long.is_err(), "{:?}", format);
+    }
+}
+
+#[test]
+fn guesses_formats_and_stems() {
+    let cases = [
+        ("hw2.zip", Some(Format::Zip), Some("hw2")),
+        ("hw2.TAR", Some(Format::Tar), Some("hw2")),
+        ("hw2.tar.gz", Some(Format::TarGz), Some("hw2")),
+        ("hw2.tgz", Some(Format::TarGz), Some("hw2")),
+        (".zip", Some(Format::Zip), None),
+        ("hw2.rar", None, None),
+    ];
+
+    for &(name, format, stem) in &cases {
+        let path = std::path::Path::new(name);
+        assert_eq!(Format::from_path(path), format, "{}", name);
+        assert_eq!(format.and_then(|f| f.stem(path)), stem, "{}", name);
+    }
+}
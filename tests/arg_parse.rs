@@ -0,0 +1,230 @@
+//! Property tests for the spec grammar in `gsc_client::args::parse`.
+
+use gsc_client::args::parse::*;
+use gsc_client::messages::EvalType;
+use gsc_client::{CpArg, EvalScore, Result};
+
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use std::path::Path;
+
+fn hw() -> impl Strategy<Value = usize> {
+    prop_oneof![
+        0..10usize,
+        0..1000usize,
+        any::<u32>().prop_map(|n| n as usize),
+    ]
+}
+
+/// A homework number along with any of the ways the grammar allows to
+/// spell it, with the prefix optional only if `bare` is.
+fn hw_spec(bare: bool) -> impl Strategy<Value = (usize, String)> {
+    let prefixes: &[&str] = if bare {
+        &["hw", "HW", "Hw", "hW", ""]
+    } else {
+        &["hw", "HW", "Hw", "hW"]
+    };
+
+    (hw(), select(prefixes), 0..3usize)
+        .prop_map(|(hw, prefix, zeros)| (hw, format!("{}{}{}", prefix, "0".repeat(zeros), hw)))
+}
+
+/// A file name, possibly empty, drawn from characters that have given
+/// trouble before.
+fn name() -> impl Strategy<Value = String> {
+    const PIECES: &[&str] = &[
+        "a", "Z", "0", "7", ":", "::", ".", "*", "?", "[", "]", " ", "-", "_", "é", "名", "hw",
+        "current", "/",
+    ];
+    prop::collection::vec(select(PIECES), 0..6).prop_map(|pieces| pieces.concat())
+}
+
+fn name_without(c: char) -> impl Strategy<Value = String> {
+    name().prop_filter("empty or has the character", move |name| {
+        !name.is_empty() && !name.contains(c)
+    })
+}
+
+fn no_current() -> Result<usize> {
+    panic!("looked up the current homework needlessly")
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(500))]
+
+    #[test]
+    fn hw_accepts_any_spelling((hw, spec) in hw_spec(true), colon in any::<bool>()) {
+        let spec = format!("{}{}", spec, if colon { ":" } else { "" });
+        prop_assert_eq!(parse_hw(&spec, no_current).unwrap(), hw);
+    }
+
+    #[test]
+    fn hw_strict_requires_the_prefix((hw, spec) in hw_spec(true)) {
+        let parsed = parse_hw_strict(&spec);
+
+        if spec.starts_with(char::is_numeric) {
+            prop_assert!(parsed.is_err());
+        } else {
+            prop_assert_eq!(parsed.unwrap(), hw);
+        }
+    }
+
+    #[test]
+    fn hw_rejects_names((_, spec) in hw_spec(true), name in name()) {
+        prop_assume!(!name.is_empty());
+        let spec = format!("{}:{}", spec, name);
+        prop_assert!(parse_hw(&spec, no_current).is_err());
+    }
+
+    #[test]
+    fn hw_file_keeps_everything_after_the_first_colon((hw, spec) in hw_spec(true), name in name()) {
+        let spec = format!("{}:{}", spec, name);
+        let rp = parse_hw_file(&spec, no_current).unwrap();
+        prop_assert_eq!((rp.hw, rp.name.as_str()), (hw, name.as_str()));
+    }
+
+    #[test]
+    fn hw_file_requires_the_colon((_, spec) in hw_spec(true)) {
+        prop_assert!(parse_hw_file(&spec, no_current).is_err());
+    }
+
+    #[test]
+    fn hw_opt_file_name_defaults_to_empty(
+        (hw, spec) in hw_spec(true),
+        name in prop::option::of(name()),
+    ) {
+        let spec = match &name {
+            Some(name) => format!("{}:{}", spec, name),
+            None => spec,
+        };
+
+        let rp = parse_hw_opt_file(&spec, no_current).unwrap();
+        prop_assert_eq!(rp.hw, hw);
+        prop_assert_eq!(rp.name, name.unwrap_or_default());
+    }
+
+    #[test]
+    fn current_means_the_current_homework(
+        hw in hw(),
+        word in select(&["current", "CURRENT", "Current"][..]),
+        name in name(),
+    ) {
+        let spec = format!("{}:{}", word, name);
+
+        prop_assert_eq!(parse_hw(word, || Ok(hw)).unwrap(), hw);
+        let rp = parse_hw_file(&spec, || Ok(hw)).unwrap();
+        prop_assert_eq!((rp.hw, rp.name), (hw, name));
+    }
+
+    #[test]
+    fn cp_arg_without_colon_is_local(name in name_without(':')) {
+        match parse_cp_arg(&name, no_current).unwrap() {
+            CpArg::Local(path) => prop_assert_eq!(path, Path::new(&name)),
+            CpArg::Remote(rp) => panic!("parsed as remote {:?}", rp),
+        }
+    }
+
+    #[test]
+    fn cp_arg_with_leading_colon_is_local(name in name()) {
+        prop_assume!(!name.is_empty());
+        let spec = format!(":{}", name);
+        match parse_cp_arg(&spec, no_current).unwrap() {
+            CpArg::Local(path) => prop_assert_eq!(path, Path::new(&name)),
+            CpArg::Remote(rp) => panic!("parsed as remote {:?}", rp),
+        }
+    }
+
+    #[test]
+    fn cp_arg_with_hw_is_remote((hw, spec) in hw_spec(true), name in name()) {
+        let spec = format!("{}:{}", spec, name);
+        match parse_cp_arg(&spec, no_current).unwrap() {
+            CpArg::Remote(rp) => prop_assert_eq!((rp.hw, rp.name), (hw, name)),
+            CpArg::Local(path) => panic!("parsed as local {:?}", path),
+        }
+    }
+
+    #[test]
+    fn remote_dest_without_colon_is_a_name(name in name_without(':')) {
+        prop_assume!(parse_hw_strict(&name).is_err());
+        let dest = parse_remote_dest(&name, no_current).unwrap();
+        prop_assert_eq!((dest.hw, dest.name), (None, name));
+    }
+
+    #[test]
+    fn remote_dest_bare_number_is_a_name(hw in hw()) {
+        let spec = hw.to_string();
+        let dest = parse_remote_dest(&spec, no_current).unwrap();
+        prop_assert_eq!((dest.hw, dest.name), (None, spec));
+    }
+
+    #[test]
+    fn remote_dest_with_hw_is_remote((hw, spec) in hw_spec(false), name in name()) {
+        let spec = format!("{}:{}", spec, name);
+        let dest = parse_remote_dest(&spec, no_current).unwrap();
+        prop_assert_eq!((dest.hw, dest.name), (Some(hw), name));
+    }
+
+    #[test]
+    fn fractions_fit_scales_and_sometimes_yes_no(
+        (num, den) in (1..=10usize).prop_flat_map(|den| (0..=den, Just(den))),
+        slash in select(&["/", " / "][..]),
+    ) {
+        let score = parse_eval_score(&format!("{}{}{}", num, slash, den)).unwrap();
+        prop_assert_eq!(score.for_type(EvalType::Scale), Some(num as f64 / den as f64));
+        prop_assert_eq!(
+            score.for_type(EvalType::Boolean).is_some(),
+            num == 0 || num == den
+        );
+    }
+
+    #[test]
+    fn percentages_fit_scales_up_to_100(percent in 0..=200usize, sign in any::<bool>()) {
+        let spec = format!("{}{}", percent, if sign { "%" } else { "" });
+        let score = parse_eval_score(&spec).unwrap();
+        prop_assert_eq!(score.for_type(EvalType::Scale).is_some(), percent <= 100);
+    }
+}
+
+#[test]
+fn numbers_too_big_are_errors() {
+    let big = "9".repeat(40);
+    for spec in &[
+        format!("hw{}", big),
+        format!("{}:", big),
+        format!("hw{}:main.cpp", big),
+    ] {
+        assert!(parse_hw(spec, no_current).is_err(), "{:?}", spec);
+        assert!(parse_hw_opt_file(spec, no_current).is_err(), "{:?}", spec);
+        if spec.contains(':') {
+            assert!(parse_cp_arg(spec, no_current).is_err(), "{:?}", spec);
+        }
+    }
+}
+
+#[test]
+fn cp_arg_rejects_empty_and_lone_colon() {
+    assert!(parse_cp_arg("", no_current).is_err());
+    assert!(parse_cp_arg(":", no_current).is_err());
+}
+
+#[test]
+fn remote_dest_rejects_empty_and_stray_colons() {
+    assert!(parse_remote_dest("", no_current).is_err());
+    assert!(parse_remote_dest(":", no_current).is_err());
+    assert!(parse_remote_dest("notes:txt", no_current).is_err());
+}
+
+#[test]
+fn eval_scores_spell_yes_and_no() {
+    for spec in &["yes", "Y", "no", "N"] {
+        let score = parse_eval_score(spec).unwrap();
+        assert!(matches!(score, EvalScore::Yes | EvalScore::No), "{}", spec);
+        assert!(score.for_type(EvalType::Boolean).is_some(), "{}", spec);
+        assert_eq!(score.for_type(EvalType::Scale), None, "{}", spec);
+    }
+
+    for spec in &["", "maybe", "1/0", "3/", "/5"] {
+        assert!(parse_eval_score(spec).is_err(), "{:?}", spec);
+    }
+}
@@ -0,0 +1,166 @@
+//! Checks that the connection settings in `Config` reach the HTTP client,
+//! and measures what keep-alive buys on many small GETs. Run the
+//! benchmark with `cargo test -- --ignored --nocapture`.
+
+use gsc_client::config::Config;
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A tiny HTTP/1.1 server that answers every request with a small JSON
+/// body, counts how many connections it accepts, and records each
+/// request’s `Accept-Encoding` header.
+struct Server {
+    uri: String,
+    connections: Arc<AtomicUsize>,
+    accept_encodings: Arc<Mutex<Vec<String>>>,
+}
+
+impl Server {
+    fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let uri = format!("http://{}/", listener.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&connections);
+        let accept_encodings = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&accept_encodings);
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let stream = stream.unwrap();
+                let recorder = Arc::clone(&recorder);
+                thread::spawn(move || serve(stream, &recorder));
+            }
+        });
+
+        Server {
+            uri,
+            connections,
+            accept_encodings,
+        }
+    }
+
+    /// The `Accept-Encoding` of each request so far, or "" if it had none.
+    fn accept_encodings(&self) -> Vec<String> {
+        self.accept_encodings.lock().unwrap().clone()
+    }
+
+    fn connections(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+}
+
+fn serve(stream: TcpStream, accept_encodings: &Mutex<Vec<String>>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut writer = stream;
+    let body = "{\"ok\":true}";
+
+    loop {
+        let mut line = String::new();
+        let mut accept_encoding = String::new();
+        loop {
+            line.clear();
+            match reader.read_line(&mut line) {
+                Ok(0) | Err(_) => return,
+                Ok(_) if line == "\r\n" => break,
+                Ok(_) => {
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("accept-encoding") {
+                            accept_encoding = value.trim().to_owned();
+                        }
+                    }
+                }
+            }
+        }
+        accept_encodings.lock().unwrap().push(accept_encoding);
+
+        // One write per response, so Nagle’s algorithm doesn’t stall us:
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        if writer.write_all(response.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+fn run_gets(config: &Config, server: &Server, count: usize) -> Duration {
+    let client = config.build_http_client().unwrap();
+    let start = Instant::now();
+
+    for _ in 0..count {
+        let response = client.get(&server.uri).send().unwrap();
+        assert!(response.status().is_success());
+        response.bytes().unwrap();
+    }
+
+    start.elapsed()
+}
+
+#[test]
+fn default_config_reuses_connections() {
+    let server = Server::start();
+    run_gets(&Config::new(), &server, 20);
+    assert_eq!(server.connections(), 1);
+}
+
+#[test]
+fn empty_pool_opens_a_connection_per_request() {
+    let server = Server::start();
+    let mut config = Config::new();
+    config.set_pool_max_idle(Some(0));
+    run_gets(&config, &server, 20);
+    assert_eq!(server.connections(), 20);
+}
+
+#[test]
+fn compression_is_negotiated_by_default() {
+    let server = Server::start();
+    run_gets(&Config::new(), &server, 1);
+    let accepted = server.accept_encodings();
+    assert!(accepted[0].contains("gzip"), "{:?}", accepted);
+    assert!(accepted[0].contains("br"), "{:?}", accepted);
+}
+
+#[test]
+fn compression_can_be_disabled() {
+    let server = Server::start();
+    let mut config = Config::new();
+    config.set_gzip(false);
+    config.set_brotli(false);
+    run_gets(&config, &server, 1);
+    let accepted = server.accept_encodings();
+    assert!(!accepted[0].contains("gzip"), "{:?}", accepted);
+    assert!(!accepted[0].contains("br"), "{:?}", accepted);
+}
+
+#[test]
+#[ignore]
+fn bench_keep_alive_vs_no_pool() {
+    const COUNT: usize = 500;
+
+    let pooled_server = Server::start();
+    let pooled = run_gets(&Config::new(), &pooled_server, COUNT);
+
+    let unpooled_server = Server::start();
+    let mut config = Config::new();
+    config.set_pool_max_idle(Some(0));
+    let unpooled = run_gets(&config, &unpooled_server, COUNT);
+
+    println!(
+        "{} GETs: keep-alive {:?} ({} connections), no pool {:?} ({} connections)",
+        COUNT,
+        pooled,
+        pooled_server.connections(),
+        unpooled,
+        unpooled_server.connections(),
+    );
+}
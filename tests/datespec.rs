@@ -0,0 +1,136 @@
+//! Date specifications and the date formats they build on, resolved
+//! against a fixed base time so that the results don’t depend on today.
+
+use gsc_client::datespec::DateSpec;
+use gsc_client::messages::UtcDateTime;
+
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+
+/// Local time on the given day, as `admin extend` reads times without an
+/// offset.
+fn local(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> UtcDateTime {
+    UtcDateTime::from_naive_local(NaiveDate::from_ymd(y, m, d).and_hms(h, min, s))
+}
+
+fn utc(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> UtcDateTime {
+    Utc.from_utc_datetime(&NaiveDate::from_ymd(y, m, d).and_hms(h, min, s))
+        .into()
+}
+
+/// Wednesday 13 March 2024, noon.
+fn base() -> UtcDateTime {
+    local(2024, 3, 13, 12, 0, 0)
+}
+
+fn resolve(spec: &str) -> UtcDateTime {
+    spec.parse::<DateSpec>()
+        .unwrap_or_else(|e| panic!("{:?}: {}", spec, e))
+        .resolve(&base())
+}
+
+#[test]
+fn offsets_move_the_base() {
+    let cases = [
+        ("+3d", Duration::days(3)),
+        ("+48h", Duration::hours(48)),
+        ("+90m", Duration::minutes(90)),
+        ("+1w", Duration::weeks(1)),
+        ("-1d12h", -Duration::hours(36)),
+        (
+            "+ 1d 6h 30m",
+            Duration::days(1) + Duration::hours(6) + Duration::minutes(30),
+        ),
+        ("+0d", Duration::zero()),
+    ];
+
+    for &(spec, offset) in &cases {
+        let parsed: DateSpec = spec.parse().unwrap();
+        assert!(parsed.is_relative(), "{}", spec);
+        assert_eq!(resolve(spec).time_since(&base()), offset, "{}", spec);
+    }
+}
+
+#[test]
+fn weekdays_mean_the_next_one_after_the_base() {
+    let cases = [
+        ("next friday 23:59", local(2024, 3, 15, 23, 59, 0)),
+        ("friday", local(2024, 3, 15, 23, 59, 59)),
+        ("Thu 9:30", local(2024, 3, 14, 9, 30, 0)),
+        ("next Wednesday", local(2024, 3, 20, 23, 59, 59)),
+        ("tuesday 08:00:15", local(2024, 3, 19, 8, 0, 15)),
+        ("NEXT SUNDAY 0:00", local(2024, 3, 17, 0, 0, 0)),
+    ];
+
+    for (spec, expected) in &cases {
+        assert_eq!(&resolve(spec), expected, "{}", spec);
+    }
+}
+
+#[test]
+fn absolute_dates_ignore_the_base() {
+    let cases = [
+        ("2024-04-01 17:00", local(2024, 4, 1, 17, 0, 0)),
+        ("2024-04-01", local(2024, 4, 1, 23, 59, 59)),
+        ("2024-04-01T17:00:00Z", utc(2024, 4, 1, 17, 0, 0)),
+    ];
+
+    for (spec, expected) in &cases {
+        let parsed: DateSpec = spec.parse().unwrap();
+        assert!(!parsed.is_relative(), "{}", spec);
+        assert_eq!(&resolve(spec), expected, "{}", spec);
+    }
+}
+
+#[test]
+fn rejects_what_isnt_a_date() {
+    for spec in &[
+        "",
+        "+",
+        "+3",
+        "+3x",
+        "3d",
+        "-d",
+        "next",
+        "next fooday",
+        "friday 25:00",
+        "friday noon",
+        "tomorrow",
+        "2024-13-01",
+        "2024-02-30 12:00",
+    ] {
+        assert!(spec.parse::<DateSpec>().is_err(), "{:?}", spec);
+    }
+}
+
+#[test]
+fn parses_flexible_date_formats() {
+    let cases = [
+        ("2024-03-15T23:59:00Z", utc(2024, 3, 15, 23, 59, 0)),
+        ("2024-03-15T23:59:00+02:00", utc(2024, 3, 15, 21, 59, 0)),
+        ("2024-03-15 23:59:00 +0000", utc(2024, 3, 15, 23, 59, 0)),
+        ("2024-03-15 23:59 -0500", utc(2024, 3, 16, 4, 59, 0)),
+        ("2024-03-15 23:59:30+0100", utc(2024, 3, 15, 22, 59, 30)),
+        ("2024-03-15 23:59+0530", utc(2024, 3, 15, 18, 29, 0)),
+        ("2024-03-15T23:59-0800", utc(2024, 3, 16, 7, 59, 0)),
+        ("2024-03-15 23:59:30", local(2024, 3, 15, 23, 59, 30)),
+        ("2024-03-15 23:59", local(2024, 3, 15, 23, 59, 0)),
+        ("2024-03-15T23:59:30", local(2024, 3, 15, 23, 59, 30)),
+        ("2024-03-15T23:59", local(2024, 3, 15, 23, 59, 0)),
+        ("2024-03-15", local(2024, 3, 15, 23, 59, 59)),
+        ("  2024-03-15  ", local(2024, 3, 15, 23, 59, 59)),
+    ];
+
+    for (s, expected) in &cases {
+        let parsed: UtcDateTime = s.parse().unwrap_or_else(|e| panic!("{:?}: {}", s, e));
+        assert_eq!(&parsed, expected, "{}", s);
+    }
+
+    for s in &[
+        "2024-03-15 23",
+        "15/03/2024",
+        "2024-03-15T",
+        "2024-03-15 23:59 EST",
+    ] {
+        assert!(s.parse::<UtcDateTime>().is_err(), "{:?}", s);
+    }
+}
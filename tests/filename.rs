@@ -0,0 +1,80 @@
+//! Tests for remote file names that have given trouble: spaces, unicode,
+//! percent signs, slashes, and names that could escape a directory.
+
+use gsc_client::filename;
+
+const TRICKY: &[&str] = &[
+    "main.cpp",
+    "my notes.txt",
+    "naïve café.md",
+    "名前.h",
+    "50% done+more.txt",
+    "a?b#c.txt",
+    "C:notes",
+    "hw1:main.cpp",
+    "...",
+    ".hidden",
+    "~",
+];
+
+#[test]
+fn accepts_tricky_but_safe_names() {
+    for name in TRICKY {
+        assert_eq!(filename::check(name).ok(), Some(*name));
+    }
+}
+
+#[test]
+fn rejects_names_that_could_be_paths() {
+    for name in &[
+        "",
+        ".",
+        "..",
+        "../main.cpp",
+        "src/main.cpp",
+        "/etc/passwd",
+        "..\\x",
+        "a\\b",
+    ] {
+        assert!(filename::check(name).is_err(), "{:?}", name);
+    }
+}
+
+#[test]
+fn rejects_control_characters() {
+    for name in &["a\nb", "tab\there", "nul\0", "\u{1b}[31mred"] {
+        let error = filename::check(name).unwrap_err().to_string();
+        assert!(
+            !error.contains(|c: char| c.is_control() && c != '\n'),
+            "{:?}",
+            error
+        );
+    }
+}
+
+#[test]
+fn encoding_is_one_path_segment() {
+    for name in TRICKY.iter().chain(&["a/b", "a b", "%2F"]) {
+        let encoded = filename::encode(name).to_string();
+        assert!(
+            !encoded.contains(&['/', ' ', '?', '#', '+'][..]),
+            "{:?}",
+            encoded
+        );
+        assert_eq!(filename::decode(&encoded), *name);
+    }
+}
+
+#[test]
+fn decodes_any_percent_encoding() {
+    assert_eq!(filename::decode("shape%2Eh"), "shape.h");
+    assert_eq!(filename::decode("%E5%90%8D.h"), "名.h");
+    assert_eq!(filename::decode("bad%FF"), "bad\u{FFFD}");
+}
+
+#[test]
+fn display_escapes_control_characters() {
+    assert_eq!(filename::display("naïve café.md"), "naïve café.md");
+    assert_eq!(filename::display("a\nb\tc"), "a\\nb\\tc");
+    assert_eq!(filename::display("\u{1b}[31m"), "\\u{1b}[31m");
+}
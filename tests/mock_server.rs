@@ -0,0 +1,1251 @@
+//! End-to-end tests of `GscClient` against the mock server from the
+//! `test-support` feature. Run them with
+//! `cargo test --features test-support`.
+
+#![cfg(feature = "test-support")]
+
+use gsc_client::archive::{self, Format};
+use gsc_client::config::{Config, OverwritePolicy, RateLimit};
+use gsc_client::messages::{FilePurpose, SubmissionStatus, UserRole};
+use gsc_client::test_support::{MockServer, TempDir, API_KEY, USER};
+use gsc_client::{CpArg, EvalScore, GscClient, RemoteDestination, RemotePattern, Unqualified};
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+fn hw(hw: usize, name: &str) -> RemotePattern {
+    RemotePattern {
+        hw,
+        name: name.to_owned(),
+    }
+}
+
+/// Runs `command` with output going to a file, and returns the output.
+fn output_of(mut config: Config, dir: &TempDir, command: impl FnOnce(&GscClient)) -> String {
+    let path = dir.path().join("output");
+    config.set_output_file(path.clone());
+
+    let client = GscClient::with_config(config).unwrap();
+    command(&client);
+    client.finish_output().unwrap();
+
+    fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn ls_lists_remote_files() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let mut config = server.config(dir.path());
+    config.set_porcelain_output(true);
+
+    let output = output_of(config, &dir, |client| {
+        client.ls(&[hw(2, "")], None).unwrap()
+    });
+    let names: Vec<_> = output
+        .lines()
+        .map(|line| line.rsplit('\t').next().unwrap())
+        .collect();
+
+    assert_eq!(names, ["Makefile", "main.cpp", "shape.h", "test.cpp"]);
+}
+
+#[test]
+fn ls_follows_pages_up_to_the_limit() {
+    let server = MockServer::start();
+    server.set_page_size(1);
+    let dir = TempDir::new();
+    let mut config = server.config(dir.path());
+    config.set_porcelain_output(true);
+
+    let pages = || {
+        server
+            .requests()
+            .iter()
+            .filter(|request| request.path.starts_with("/api/submissions/2/files"))
+            .count()
+    };
+
+    let output = output_of(config.clone(), &dir, |client| {
+        client.ls(&[hw(2, "")], None).unwrap()
+    });
+    assert_eq!(output.lines().count(), 4);
+    assert_eq!(pages(), 4);
+
+    let output = output_of(config, &dir, |client| {
+        client.ls(&[hw(2, "*.cpp")], Some(1)).unwrap()
+    });
+    assert!(output.ends_with("main.cpp\n"));
+    assert_eq!(pages(), 4 + 2);
+}
+
+#[test]
+fn status_reports_grades() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let mut config = server.config(dir.path());
+    config.set_porcelain_output(true);
+
+    let output = output_of(config, &dir, |client| client.status_user().unwrap());
+
+    assert!(output.contains("submission\t1\tclosed\t0.9\n"));
+    assert!(output.contains("submission\t2\topen\t\n"));
+}
+
+#[test]
+fn cp_downloads_a_whole_homework() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    let dst = dir.path().join("hw2");
+
+    client
+        .cp(&[CpArg::Remote(hw(2, ""))], &CpArg::Local(dst.clone()))
+        .unwrap();
+
+    let main = fs::read(dst.join("src").join("main.cpp")).unwrap();
+    assert_eq!(Some(main), server.file(2, "main.cpp"));
+}
+
+#[test]
+fn cp_uploads_a_file() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let src = dir.path().join("circle.cpp");
+    fs::write(&src, "// circle\n").unwrap();
+
+    let mut config = server.config(dir.path());
+    config.set_overwrite_policy(OverwritePolicy::Always);
+    let client = GscClient::with_config(config).unwrap();
+    client
+        .cp(&[CpArg::Local(src)], &CpArg::Remote(hw(2, "")))
+        .unwrap();
+
+    assert_eq!(server.file(2, "circle.cpp"), Some(b"// circle\n".to_vec()));
+}
+
+#[test]
+fn rm_deletes_matching_files() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let mut config = server.config(dir.path());
+    config.set_assume_yes(true);
+
+    let client = GscClient::with_config(config).unwrap();
+    client.rm(&[hw(2, "*.h")]).unwrap();
+
+    assert_eq!(server.file(2, "shape.h"), None);
+    assert!(server.file(2, "main.cpp").is_some());
+
+    let deletes: Vec<_> = server
+        .requests()
+        .into_iter()
+        .filter(|request| request.method == "DELETE")
+        .map(|request| request.path)
+        .collect();
+    assert_eq!(deletes, ["/api/submissions/2/files/shape%2Eh"]);
+}
+
+#[test]
+fn rm_on_behalf_asks_even_with_yes() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let mut config = server.config(dir.path());
+    config.set_assume_yes(true);
+    config.set_no_input(true);
+    config.set_on_behalf(USER.to_owned());
+
+    let client = GscClient::with_config(config).unwrap();
+    client.rm(&[hw(2, "*.h")]).unwrap();
+
+    assert!(server.file(2, "shape.h").is_some());
+    assert!(server
+        .requests()
+        .iter()
+        .all(|request| request.method != "DELETE"));
+}
+
+#[test]
+fn cp_uploads_names_with_spaces_and_unicode() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let src = dir.path().join("naïve café 50%.txt");
+    fs::write(&src, "notes\n").unwrap();
+
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    client
+        .cp(&[CpArg::Local(src)], &CpArg::Remote(hw(2, "")))
+        .unwrap();
+
+    assert_eq!(
+        server.file(2, "naïve café 50%.txt"),
+        Some(b"notes\n".to_vec())
+    );
+}
+
+#[test]
+fn cp_refuses_remote_names_that_escape() {
+    let server = MockServer::start();
+    server.put_file(2, "../escaped", "gotcha\n");
+    let dir = TempDir::new();
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    let dst = dir.path().join("hw2");
+
+    let error = client
+        .cp(&[CpArg::Remote(hw(2, ""))], &CpArg::Local(dst.clone()))
+        .unwrap_err();
+
+    assert!(error.to_string().contains("‘../escaped’"), "{}", error);
+    assert!(!dst.join("escaped").exists());
+    assert!(!dir.path().join("escaped").exists());
+}
+
+#[test]
+fn mv_refuses_names_with_slashes() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+
+    let dst = RemoteDestination::just_name("src/main.cpp");
+    client.mv(&hw(2, "main.cpp"), &dst).unwrap();
+
+    assert!(server.file(2, "main.cpp").is_some());
+    assert!(server
+        .requests()
+        .iter()
+        .all(|request| request.method != "PATCH"));
+}
+
+#[test]
+fn cp_into_renames_uploads() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let srcs: Vec<_> = ["circle.cpp", "Makefile", "notes.tar.gz"]
+        .iter()
+        .map(|name| {
+            let path = dir.path().join(name);
+            fs::write(&path, *name).unwrap();
+            CpArg::Local(path)
+        })
+        .collect();
+
+    let mut config = server.config(dir.path());
+    config.set_upload_name_template("jd123_{stem}{ext}".parse().unwrap());
+    let client = GscClient::with_config(config).unwrap();
+    client.cp(&srcs, &CpArg::Remote(hw(2, ""))).unwrap();
+
+    assert_eq!(
+        server.file(2, "jd123_circle.cpp"),
+        Some(b"circle.cpp".to_vec())
+    );
+    assert_eq!(server.file(2, "jd123_Makefile"), Some(b"Makefile".to_vec()));
+    assert_eq!(
+        server.file(2, "jd123_notes.tar.gz"),
+        Some(b"notes.tar.gz".to_vec())
+    );
+}
+
+#[test]
+fn cp_into_refuses_to_upload_two_files_as_one() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let srcs: Vec<_> = ["a.cpp", "b.cpp"]
+        .iter()
+        .map(|name| {
+            let path = dir.path().join(name);
+            fs::write(&path, *name).unwrap();
+            CpArg::Local(path)
+        })
+        .collect();
+
+    let mut config = server.config(dir.path());
+    config.set_upload_name_template("solution{ext}".parse().unwrap());
+    let client = GscClient::with_config(config).unwrap();
+
+    assert!(client.cp(&srcs, &CpArg::Remote(hw(2, ""))).is_err());
+    assert_eq!(server.file(2, "solution.cpp"), None);
+}
+
+#[test]
+fn cp_up_all_sets_types_from_directories() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let src = dir.path().join("hw2");
+    for (path, contents) in &[
+        ("Makefile", "all:\n"),
+        (".DS_Store", ""),
+        ("src/helper.txt", "helper\n"),
+        ("test/shape_checks.cpp", "// checks\n"),
+        ("Resources/test_data.cpp", "1 2 3\n"),
+    ] {
+        let path = src.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    client.cp_up_all(&[src], 2).unwrap();
+
+    assert_eq!(server.purpose(2, "Makefile"), Some(FilePurpose::Config));
+    assert_eq!(server.purpose(2, "helper.txt"), Some(FilePurpose::Source));
+    assert_eq!(
+        server.purpose(2, "shape_checks.cpp"),
+        Some(FilePurpose::Test)
+    );
+    assert_eq!(
+        server.purpose(2, "test_data.cpp"),
+        Some(FilePurpose::Resource)
+    );
+    assert_eq!(server.file(2, ".DS_Store"), None);
+
+    let patches = server
+        .requests()
+        .iter()
+        .filter(|request| request.method == "PATCH")
+        .count();
+    assert_eq!(patches, 3);
+}
+
+#[test]
+fn cp_corrects_generic_media_types() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let srcs: Vec<_> = ["lab.rkt", "notes.txt"]
+        .iter()
+        .map(|name| {
+            let path = dir.path().join(name);
+            fs::write(&path, *name).unwrap();
+            CpArg::Local(path)
+        })
+        .collect();
+
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    client.cp(&srcs, &CpArg::Remote(hw(2, ""))).unwrap();
+
+    assert_eq!(server.media_type(2, "lab.rkt").unwrap(), "text/x-racket");
+    assert_eq!(server.media_type(2, "notes.txt").unwrap(), "text/plain");
+}
+
+#[test]
+fn cp_sets_the_given_media_type() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let src = dir.path().join("solution");
+    fs::write(&src, "#lang racket\n").unwrap();
+
+    let mut config = server.config(dir.path());
+    config.set_media_type("text/x-racket".parse().unwrap());
+    let client = GscClient::with_config(config).unwrap();
+    client
+        .cp(&[CpArg::Local(src)], &CpArg::Remote(hw(2, "")))
+        .unwrap();
+
+    assert_eq!(server.media_type(2, "solution").unwrap(), "text/x-racket");
+}
+
+#[test]
+fn verify_passes_after_downloading() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    let local = dir.path().join("hw2");
+
+    client
+        .cp(&[CpArg::Remote(hw(2, ""))], &CpArg::Local(local.clone()))
+        .unwrap();
+    client.verify(2, &local).unwrap();
+
+    assert!(!client.had_warning());
+}
+
+#[test]
+fn verify_reports_what_doesnt_match() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let local = dir.path().join("hw2");
+
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    client
+        .cp(&[CpArg::Remote(hw(2, ""))], &CpArg::Local(local.clone()))
+        .unwrap();
+
+    fs::write(local.join("src").join("main.cpp"), "int main() {}\n").unwrap();
+    fs::remove_file(local.join("Makefile")).unwrap();
+    fs::write(local.join("README"), "notes\n").unwrap();
+    server.put_file(2, ".DS_Store", "");
+
+    let mut config = server.config(dir.path());
+    config.set_porcelain_output(true);
+    let output = output_of(config, &dir, |client| {
+        client.verify(2, &local).unwrap();
+        assert!(client.had_warning());
+    });
+
+    let verdicts: Vec<_> = output
+        .lines()
+        .map(|line| {
+            let fields: Vec<_> = line.split('\t').collect();
+            (fields[2], fields[3])
+        })
+        .collect();
+
+    assert_eq!(
+        verdicts,
+        [
+            ("forbidden", ".DS_Store"),
+            ("extra", "Makefile"),
+            ("missing", "README"),
+            ("size", "main.cpp"),
+            ("ok", "shape.h"),
+            ("ok", "test.cpp"),
+        ]
+    );
+}
+
+#[test]
+fn cp_refuses_compiled_files() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let src = dir.path().join("shape");
+    fs::write(&src, b"\x7fELF\x02\x01\x01\x00").unwrap();
+
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    let result = client.cp(&[CpArg::Local(src)], &CpArg::Remote(hw(2, "")));
+
+    assert!(result.is_err());
+    assert!(server.requests().iter().all(|r| r.method != "PUT"));
+}
+
+#[test]
+fn cp_uploads_suspicious_files_with_force() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let src = dir.path().join("shape.o");
+    fs::write(&src, "not really").unwrap();
+
+    let mut config = server.config(dir.path());
+    config.set_force(true);
+    let client = GscClient::with_config(config).unwrap();
+    client
+        .cp(&[CpArg::Local(src)], &CpArg::Remote(hw(2, "")))
+        .unwrap();
+
+    assert!(client.had_warning());
+    assert!(server.file(2, "shape.o").is_some());
+}
+
+#[test]
+fn cp_refuses_files_over_max_upload_size() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let src = dir.path().join("data.txt");
+    fs::write(&src, "x".repeat(2048)).unwrap();
+
+    let mut config = server.config(dir.path());
+    config.set_max_upload_size("1K".parse().unwrap());
+    let client = GscClient::with_config(config).unwrap();
+    let result = client.cp(&[CpArg::Local(src)], &CpArg::Remote(hw(2, "")));
+
+    assert!(result.is_err());
+    assert_eq!(server.file(2, "data.txt"), None);
+}
+
+#[test]
+fn cp_takes_back_files_the_server_forbids() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let src = dir.path().join("hw2.zip");
+    fs::write(&src, "PK").unwrap();
+
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    let result = client.cp(&[CpArg::Local(src)], &CpArg::Remote(hw(2, "")));
+
+    assert!(result.is_err());
+    assert_eq!(server.file(2, "hw2.zip"), None);
+}
+
+#[test]
+fn submit_receipt_has_each_files_digest() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    server.put_file(2, "abc.txt", "abc");
+    let receipt = dir.path().join("receipt.json");
+
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    client.submit(2, Some(&receipt)).unwrap();
+
+    let receipt = fs::read_to_string(receipt).unwrap();
+    assert!(
+        receipt.contains("\"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad\"")
+    );
+}
+
+/// Writes an archive at `path` holding `entries`.
+fn write_archive(path: &std::path::Path, entries: &[(&str, &str)]) {
+    let format = Format::from_path(path).unwrap();
+    let mut writer = archive::Writer::new(format, fs::File::create(path).unwrap());
+
+    for (name, contents) in entries {
+        let mtime = std::time::SystemTime::now();
+        writer
+            .append(name, contents.len() as u64, mtime, &mut contents.as_bytes())
+            .unwrap();
+    }
+
+    writer.finish().unwrap();
+}
+
+#[test]
+fn cp_extract_sets_types_from_directories() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let src = dir.path().join("hw2.zip");
+    write_archive(
+        &src,
+        &[
+            ("hw2/Makefile", "all:\n"),
+            ("hw2/src/helper.txt", "helper\n"),
+            ("hw2/test/shape_checks.cpp", "// checks\n"),
+            ("hw2/Resources/test_data.cpp", "1 2 3\n"),
+            ("hw2/notes/todo.txt", "finish\n"),
+        ],
+    );
+
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    client.cp_extract(&[src], 2).unwrap();
+
+    assert_eq!(server.file(2, "helper.txt"), Some(b"helper\n".to_vec()));
+    assert_eq!(server.purpose(2, "Makefile"), Some(FilePurpose::Config));
+    assert_eq!(server.purpose(2, "helper.txt"), Some(FilePurpose::Source));
+    assert_eq!(
+        server.purpose(2, "shape_checks.cpp"),
+        Some(FilePurpose::Test)
+    );
+    assert_eq!(
+        server.purpose(2, "test_data.cpp"),
+        Some(FilePurpose::Resource)
+    );
+    assert_eq!(server.file(2, "todo.txt"), None);
+}
+
+#[test]
+fn cp_extract_refuses_compiled_entries_before_uploading() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let src = dir.path().join("hw2.tar.gz");
+    write_archive(
+        &src,
+        &[("src/shape.cpp", "// shape\n"), ("src/shape.o", "")],
+    );
+
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    let result = client.cp_extract(&[src], 2);
+
+    assert!(result.is_err());
+    assert!(server.requests().iter().all(|r| r.method != "PUT"));
+}
+
+#[test]
+fn cp_extract_takes_back_entries_the_server_forbids() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let src = dir.path().join("hw2.tar");
+    write_archive(&src, &[("old.zip", "PK")]);
+
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    let result = client.cp_extract(&[src], 2);
+
+    assert!(result.is_err());
+    assert_eq!(server.file(2, "old.zip"), None);
+}
+
+#[test]
+fn admin_csv_filters_rows_and_columns() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+
+    let output = output_of(server.config(dir.path()), &dir, |client| {
+        client.admin_csv(&[1], Some("[ab]*"), &[], false).unwrap()
+    });
+    assert_eq!(
+        output,
+        "username,hw1,hw1 self eval,exam1\n\
+         alice,0.9,1.0,45\n\
+         bob,0.75,0.5,38\n"
+    );
+
+    let columns = ["hw2".to_owned(), "USER*".to_owned()];
+    let output = output_of(server.config(dir.path()), &dir, |client| {
+        client.admin_csv(&[], None, &columns, true).unwrap()
+    });
+    assert_eq!(
+        output,
+        "hw2\tusername\n\
+         \talice\n\
+         0.8\tbob\n\
+         1.0\to'brien, pat\n"
+    );
+}
+
+#[test]
+fn admin_csv_converts_to_json_in_column_order() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let mut config = server.config(dir.path());
+    config.set_json_output(true);
+
+    let columns = ["exam1".to_owned(), "username".to_owned()];
+    let output = output_of(config, &dir, |client| {
+        client.admin_csv(&[], Some("bob"), &columns, false).unwrap()
+    });
+
+    assert_eq!(
+        output.split_whitespace().collect::<String>(),
+        r#"[{"exam1":"38","username":"bob"}]"#
+    );
+}
+
+#[test]
+fn admin_csv_suggests_near_column_names() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+
+    let error = client
+        .admin_csv(&[], None, &["exam".to_owned()], false)
+        .unwrap_err();
+    assert!(error.to_string().contains("exam1"), "{}", error);
+}
+
+#[test]
+fn admin_submissions_filters_by_status() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let mut config = server.config(dir.path());
+    config.set_json_output(true);
+
+    let output = output_of(config, &dir, |client| {
+        client
+            .admin_submissions(1, &[SubmissionStatus::Closed], "grade", true, false, None)
+            .unwrap()
+    });
+    assert_eq!(
+        output.split_whitespace().collect::<String>(),
+        r#"[{"id":1,"owner1":"alice","owner2":null,"status":"closed","grade":0.9,"last_modified":"2024-01-15T12:00:00.000Z"}]"#
+    );
+
+    let mut config = server.config(dir.path());
+    config.set_json_output(true);
+    let output = output_of(config, &dir, |client| {
+        client
+            .admin_submissions(1, &[SubmissionStatus::Open], "id", false, false, None)
+            .unwrap()
+    });
+    assert_eq!(output.trim(), "[]");
+}
+
+#[test]
+fn admin_submissions_prints_csv() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+
+    let output = output_of(server.config(dir.path()), &dir, |client| {
+        client
+            .admin_submissions(2, &[], "modified", false, true, None)
+            .unwrap()
+    });
+    assert_eq!(
+        output,
+        "id,owner1,owner2,status,grade,last_modified\n\
+         2,alice,,open,0.0,2024-01-15T12:00:00.000Z\n"
+    );
+}
+
+#[test]
+fn admin_whois_looks_up_a_submission_id() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let mut config = server.config(dir.path());
+    config.set_json_output(true);
+
+    let output = output_of(config, &dir, |client| client.admin_whois(2).unwrap());
+    let submission: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(submission["assignment_number"], 2);
+    assert_eq!(submission["owner1"]["name"], "alice");
+
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    assert!(client.admin_whois(99).is_err());
+}
+
+#[test]
+fn admin_progress_counts_grader_evals() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let mut config = server.config(dir.path());
+    config.set_json_output(true);
+
+    let output = output_of(config, &dir, |client| client.admin_progress(1).unwrap());
+    let progress: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(progress["submissions"], 1);
+    assert_eq!(
+        progress["items"],
+        serde_json::json!([{
+            "sequence": 1, "ungraded": 0, "editing": 0,
+            "held_back": 0, "ready": 1, "regrade": 0,
+        }])
+    );
+    assert_eq!(
+        progress["graders"],
+        serde_json::json!([{ "grader": "tom", "completed": 1, "in_progress": 0 }])
+    );
+}
+
+#[test]
+fn admin_permalinks_prints_csv() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let config = server.config(dir.path());
+    let expected = format!(
+        "user,hw,item,permalink\nalice,1,1,{}/gsc/permalink/mock-permalink\n",
+        config.get_endpoint()
+    );
+
+    let output = output_of(config, &dir, |client| {
+        client.admin_permalinks(1, None, true).unwrap()
+    });
+    assert_eq!(output, expected);
+
+    let output = output_of(server.config(dir.path()), &dir, |client| {
+        client.admin_permalinks(1, Some(2), true).unwrap()
+    });
+    assert_eq!(output, "");
+}
+
+#[test]
+fn regrade_request_shows_up_for_admins() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    client
+        .regrade(1, 1, "The warning is from the starter code.")
+        .unwrap();
+    assert!(!client.had_warning());
+
+    let patch = server.requests().into_iter().last().unwrap();
+    assert_eq!(patch.method, "PATCH");
+    assert_eq!(patch.path, "/api/submissions/1/evals/1/grader");
+
+    client.regrade(1, 1, "Again.").unwrap();
+    assert!(client.had_warning());
+
+    let mut config = server.config(dir.path());
+    config.set_json_output(true);
+    let output = output_of(config, &dir, |client| client.admin_regrades(1).unwrap());
+    let requests: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(requests[0]["owners"], "alice");
+    assert_eq!(requests[0]["sequence"], 1);
+    assert_eq!(
+        requests[0]["regrade_reason"],
+        "The warning is from the starter code."
+    );
+}
+
+#[test]
+fn eval_set_rejects_scores_that_dont_fit_the_item() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let mut config = server.config(dir.path());
+    config.set_force(true);
+    let client = GscClient::with_config(config).unwrap();
+
+    let error = client
+        .set_eval(1, 1, EvalScore::Yes, "", false)
+        .unwrap_err();
+    assert!(error.to_string().contains("fraction"), "{}", error);
+    assert!(server.requests().iter().all(|r| r.method == "GET"));
+}
+
+#[test]
+fn eval_set_refuses_outside_the_self_eval_window() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    let score = EvalScore::Percent(100.0);
+
+    let error = client.set_eval(1, 1, score, "", false).unwrap_err();
+    assert!(error.to_string().contains("closed on"), "{}", error);
+
+    let error = client.set_eval(2, 1, score, "", false).unwrap_err();
+    assert!(error.to_string().contains("opens"), "{}", error);
+
+    assert!(server.requests().iter().all(|r| r.method == "GET"));
+}
+
+#[test]
+fn eval_set_wants_an_explanation_for_a_high_score() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let mut config = server.config(dir.path());
+    config.set_force(true);
+    config.set_no_input(true);
+    let client = GscClient::with_config(config).unwrap();
+
+    let error = client
+        .set_eval(1, 1, EvalScore::Fraction(4.0, 5.0), " ", false)
+        .unwrap_err();
+    assert!(error.to_string().contains("--allow-empty"), "{}", error);
+    assert!(server.requests().iter().all(|r| r.method == "GET"));
+
+    client
+        .set_eval(1, 1, EvalScore::Percent(40.0), "", false)
+        .unwrap();
+    client
+        .set_eval(1, 1, EvalScore::Fraction(4.0, 5.0), "", true)
+        .unwrap();
+    client
+        .set_eval(
+            1,
+            1,
+            EvalScore::Percent(90.0),
+            "Tested on the lab machines.",
+            false,
+        )
+        .unwrap();
+    let puts = server
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == "PUT")
+        .count();
+    assert_eq!(puts, 3);
+}
+
+#[test]
+fn admin_commands_require_staff_role() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+
+    let error = client.check_staff_role().unwrap_err();
+    assert!(error.to_string().contains("grader or admin"), "{}", error);
+
+    server.set_role(UserRole::Grader);
+    let client = GscClient::with_config(server.config(dir.path())).unwrap();
+    client.check_staff_role().unwrap();
+    client.check_staff_role().unwrap();
+
+    let user_fetches = server
+        .requests()
+        .into_iter()
+        .filter(|r| r.path == format!("/api/users/{}", USER))
+        .count();
+    assert_eq!(user_fetches, 2);
+}
+
+#[test]
+fn admin_set_grade_is_audited() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let mut config = server.config(dir.path());
+    config.set_audit_post(true);
+
+    let client = GscClient::with_config(config).unwrap();
+    client.admin_set_grade(USER, 1, 1, 0.75, "Close.").unwrap();
+    assert!(!client.had_warning());
+
+    let posts: Vec<_> = server
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == "POST")
+        .map(|r| r.path)
+        .collect();
+    assert_eq!(posts, ["/api/audit"]);
+
+    let mut config = server.config(dir.path());
+    config.set_json_output(true);
+    let output = output_of(config, &dir, |client| client.admin_audit(None, 20).unwrap());
+    let entries: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(entries.as_array().unwrap().len(), 1);
+    assert_eq!(entries[0]["actor"], USER);
+    assert_eq!(entries[0]["action"], "set_grade");
+    assert_eq!(entries[0]["target"], USER);
+    assert_eq!(entries[0]["hw"], 1);
+    assert_eq!(entries[0]["payload"]["score"], 0.75);
+    assert_eq!(entries[0]["payload"]["comment"], "Close.");
+}
+
+#[test]
+fn pace_spaces_out_parallel_requests() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let mut config = server.config(dir.path());
+    config.set_jobs(8);
+    config.set_pace(std::time::Duration::from_millis(200));
+
+    let client = GscClient::with_config(config).unwrap();
+    let start = std::time::Instant::now();
+    client.status_all().unwrap();
+    let elapsed = start.elapsed();
+
+    // Two homeworks, so the second request waits for the first’s turn:
+    assert!(
+        elapsed >= std::time::Duration::from_millis(200),
+        "{:?}",
+        elapsed
+    );
+}
+
+#[test]
+fn repeated_gets_are_sent_once_until_a_change() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let mut config = server.config(dir.path());
+    config.set_assume_yes(true);
+    config.set_output_file(dir.path().join("output"));
+
+    let client = GscClient::with_config(config).unwrap();
+    let gets = || {
+        server
+            .requests()
+            .iter()
+            .filter(|request| request.method == "GET" && request.path.ends_with("/files"))
+            .count()
+    };
+
+    client.ls(&[hw(2, "")], None).unwrap();
+    let first = gets();
+    assert!(first > 0);
+    client.ls(&[hw(2, "")], None).unwrap();
+    assert_eq!(gets(), first);
+
+    client.rm(&[hw(2, "*.h")]).unwrap();
+    let before = gets();
+    client.ls(&[hw(2, "")], None).unwrap();
+    assert!(gets() > before);
+}
+
+#[test]
+fn the_cache_keeps_file_lists_but_not_file_contents() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let contents = format!("{{\"digits\": \"{}\"}}\n", "0123456789".repeat(100));
+    server.put_file(2, "data.json", contents.clone());
+
+    let cache_dir = dir.path().join("cache");
+    let mut config = server.config(dir.path());
+    config.set_cache_dir(cache_dir.clone());
+    let client = GscClient::with_config(config).unwrap();
+    let dst = dir.path().join("data.json");
+    client
+        .cp(
+            &[CpArg::Remote(hw(2, "data.json"))],
+            &CpArg::Local(dst.clone()),
+        )
+        .unwrap();
+    assert_eq!(fs::read_to_string(&dst).unwrap(), contents);
+
+    let cached: Vec<String> = fs::read_dir(&cache_dir)
+        .unwrap()
+        .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect();
+    assert!(cached.iter().any(|entry| entry.contains("data.json")));
+    assert!(cached.iter().all(|entry| !entry.contains("0123456789")));
+}
+
+#[test]
+fn limit_rate_applies_to_json_downloads() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let contents = format!("[{}0]\n", "0,".repeat(1000));
+    server.put_file(2, "data.json", contents.clone());
+
+    let mut config = server.config(dir.path());
+    config.set_limit_rate(RateLimit(10_000));
+    let client = GscClient::with_config(config).unwrap();
+    let dst = dir.path().join("data.json");
+
+    let start = Instant::now();
+    client
+        .cp(
+            &[CpArg::Remote(hw(2, "data.json"))],
+            &CpArg::Local(dst.clone()),
+        )
+        .unwrap();
+
+    assert_eq!(fs::read_to_string(&dst).unwrap(), contents);
+    assert!(start.elapsed() >= Duration::from_millis(200));
+}
+
+#[test]
+fn submission_listings_bypass_the_cache_and_memo() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let cache_dir = dir.path().join("cache");
+    let mut config = server.config(dir.path());
+    config.set_cache_dir(cache_dir.clone());
+
+    output_of(config, &dir, |client| {
+        for _ in 0..2 {
+            client
+                .admin_submissions(2, &[], "id", false, true, None)
+                .unwrap();
+        }
+    });
+
+    let listings = server
+        .requests()
+        .iter()
+        .filter(|request| request.path.starts_with("/api/submissions/hw2"))
+        .count();
+    assert_eq!(listings, 2);
+
+    let cached: Vec<String> = fs::read_dir(&cache_dir)
+        .into_iter()
+        .flatten()
+        .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect();
+    assert!(cached
+        .iter()
+        .all(|entry| !entry.contains("/api/submissions/hw2\"")));
+}
+
+#[test]
+fn whoami_reports_role_and_endpoint() {
+    let server = MockServer::start();
+    server.set_role(UserRole::Grader);
+    let dir = TempDir::new();
+    let mut config = server.config(dir.path());
+    config.set_json_output(true);
+
+    let output = output_of(config, &dir, |client| client.whoami().unwrap());
+    let info: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+    assert_eq!(info["username"], USER);
+    assert_eq!(info["role"], "grader");
+    assert_eq!(info["endpoint"], server.uri());
+    assert_eq!(info["cached"], false);
+    assert!(info["validated"].is_string());
+}
+
+#[test]
+fn pasted_api_keys_are_unwrapped() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let path = dir.path().join("api_key");
+    std::env::set_var("GSC_USER", USER);
+
+    for pasted in [
+        format!("  \"{}\"\n", API_KEY),
+        format!("API key: {}", API_KEY),
+        format!("Cookie: gsc_api_key={}; theme=dark", API_KEY),
+        format!("%E2%80%98{}%E2%80%99", API_KEY.to_uppercase()),
+    ] {
+        fs::write(&path, &pasted).unwrap();
+        let mut config = server.config(dir.path());
+        config.set_api_key_file(path.clone());
+
+        let client = GscClient::with_config(config).unwrap();
+        assert!(client.whoami().is_ok(), "rejected {:?}", pasted);
+    }
+}
+
+#[test]
+fn accepted_keys_are_restamped_only_once_a_day() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let config = server.config(dir.path());
+    let creds_file = config.get_credentials_file().unwrap().to_owned();
+    let key_line = fs::read_to_string(&creds_file).unwrap();
+
+    let recent = format!(
+        "{}saved 2020-01-01T00:00:00Z\nvalidated {}\n",
+        key_line,
+        chrono::Utc::now().to_rfc3339()
+    );
+    fs::write(&creds_file, &recent).unwrap();
+    let client = GscClient::with_config(config.clone()).unwrap();
+    client.whoami().unwrap();
+    assert_eq!(fs::read_to_string(&creds_file).unwrap(), recent);
+
+    let stale = format!(
+        "{}saved 2020-01-01T00:00:00Z\nvalidated 2020-01-02T00:00:00Z\n",
+        key_line
+    );
+    fs::write(&creds_file, &stale).unwrap();
+    let client = GscClient::with_config(config).unwrap();
+    client.whoami().unwrap();
+    let contents = fs::read_to_string(&creds_file).unwrap();
+    assert!(contents.contains("saved 2020-01-01"), "{}", contents);
+    assert!(!contents.contains("validated 2020-01-02"), "{}", contents);
+
+    let leftovers = fs::read_dir(creds_file.parent().unwrap())
+        .unwrap()
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .unwrap()
+                .path()
+                .to_string_lossy()
+                .ends_with(".tmp")
+        })
+        .count();
+    assert_eq!(leftovers, 0);
+}
+
+#[test]
+fn accepted_keys_are_stamped_and_old_rejected_ones_called_expired() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let config = server.config(dir.path());
+    let creds_file = config.get_credentials_file().unwrap().to_owned();
+
+    let client = GscClient::with_config(config.clone()).unwrap();
+    client.whoami().unwrap();
+    let contents = fs::read_to_string(&creds_file).unwrap();
+    assert!(contents.contains("\nvalidated "), "{}", contents);
+
+    fs::write(
+        &creds_file,
+        "alice:gsc_api_key=ffffffffffffffffffffffffffffffffffffffff\n\
+         saved 2020-01-01T00:00:00Z\n",
+    )
+    .unwrap();
+    let client = GscClient::with_config(config).unwrap();
+    let error = client.whoami().unwrap_err();
+    assert!(error.to_string().contains("likely expired on"), "{}", error);
+}
+
+fn retrying_client(server: &MockServer, dir: &TempDir) -> GscClient {
+    let mut config = server.config(dir.path());
+    config.set_retries(3);
+    config.set_retry_delay(Duration::from_millis(50));
+    GscClient::with_config(config).unwrap()
+}
+
+/// How many times the server was asked for `USER`’s submissions, which is
+/// the first request that listing files sends.
+fn submission_list_gets(server: &MockServer) -> usize {
+    let path = format!("/api/users/{}/submissions", USER);
+    server
+        .requests()
+        .iter()
+        .filter(|r| r.method == "GET" && r.path == path)
+        .count()
+}
+
+#[test]
+fn gets_are_resent_after_server_errors() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let client = retrying_client(&server, &dir);
+
+    server.fail_next("GET", 503);
+    server.fail_next("GET", 503);
+    let started = Instant::now();
+    client.ls(&[hw(2, "*")], None).unwrap();
+
+    // Backing off 50 ms and then 100 ms, plus jitter:
+    assert!(started.elapsed() >= Duration::from_millis(150));
+    assert_eq!(submission_list_gets(&server), 3);
+    assert!(!client.had_warning());
+}
+
+#[test]
+fn gets_give_up_after_the_last_retry() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let client = retrying_client(&server, &dir);
+
+    for _ in 0..4 {
+        server.fail_next("GET", 503);
+    }
+    let started = Instant::now();
+    client.ls(&[hw(2, "*")], None).unwrap();
+
+    // Doubling the delay each time, 50 + 100 + 200 ms:
+    assert!(started.elapsed() >= Duration::from_millis(350));
+    assert!(client.had_warning());
+    assert_eq!(submission_list_gets(&server), 4);
+
+    let client = retrying_client(&server, &dir);
+    client.ls(&[hw(2, "*")], None).unwrap();
+    assert!(!client.had_warning());
+}
+
+#[test]
+fn posts_are_not_resent_after_server_errors() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let client = retrying_client(&server, &dir);
+
+    server.fail_next("POST", 503);
+    assert!(client.admin_add_user("bob", UserRole::Student).is_err());
+
+    let posts = server
+        .requests()
+        .iter()
+        .filter(|r| r.method == "POST")
+        .count();
+    assert_eq!(posts, 1);
+}
+
+#[test]
+fn rate_limited_requests_wait_the_seconds_asked() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let client = retrying_client(&server, &dir);
+
+    server.rate_limit_next("GET", "1");
+    let started = Instant::now();
+    client.ls(&[hw(2, "*")], None).unwrap();
+
+    assert!(started.elapsed() >= Duration::from_secs(1));
+    assert_eq!(submission_list_gets(&server), 2);
+    assert!(!client.had_warning());
+}
+
+#[test]
+fn rate_limited_requests_wait_until_the_date_asked() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let client = retrying_client(&server, &dir);
+
+    let until = chrono::Utc::now() + chrono::Duration::seconds(2);
+    server.rate_limit_next(
+        "GET",
+        &until.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+    );
+    let started = Instant::now();
+    client.ls(&[hw(2, "*")], None).unwrap();
+
+    // The date is rounded down to the second:
+    assert!(started.elapsed() >= Duration::from_secs(1));
+    assert_eq!(submission_list_gets(&server), 2);
+    assert!(!client.had_warning());
+}
+
+#[test]
+fn rate_limited_requests_give_up_past_the_longest_wait() {
+    let server = MockServer::start();
+    let dir = TempDir::new();
+    let client = retrying_client(&server, &dir);
+
+    server.rate_limit_next("GET", "61");
+    let started = Instant::now();
+    let error = client.whoami().unwrap_err();
+
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert!(
+        error.to_string().contains("try again in 61 seconds"),
+        "{}",
+        error
+    );
+
+    // Waiting over a minute is too long, so the request isn’t resent:
+    let sent = server
+        .requests()
+        .iter()
+        .filter(|r| r.path != "/api/version")
+        .count();
+    assert_eq!(sent, 1);
+}
@@ -0,0 +1,282 @@
+//! Snapshot tests for human-readable output. Each rendering is compared
+//! with a file in `tests/snapshots`; after a deliberate change to the
+//! output, regenerate them with `UPDATE_SNAPSHOTS=1 cargo test`, and
+//! review the differences before committing.
+
+use gsc_client::messages::{self, DisplayZone, UtcDateTime};
+use gsc_client::render;
+use gsc_client::{ErrorKind, RemotePattern};
+
+use serde::de::DeserializeOwned;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Compares `actual` with snapshot `name`. Snapshots end with a newline,
+/// even if the rendering doesn’t, so that they’re well-formed text files.
+fn assert_snapshot(name: &str, actual: &str) {
+    let actual = format!("{}\n", actual);
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "snapshots", name]
+        .iter()
+        .collect::<PathBuf>()
+        .with_extension("txt");
+
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "no snapshot {}; create it with UPDATE_SNAPSHOTS=1",
+            path.display()
+        )
+    });
+
+    assert!(
+        expected == actual,
+        "rendering differs from {}\n--- expected:\n{}\n--- actual:\n{}",
+        path.display(),
+        expected,
+        actual
+    );
+}
+
+/// Parses a server message, and shows dates in UTC so that snapshots
+/// don’t depend on where they’re run.
+fn message<T: DeserializeOwned>(json: &str) -> T {
+    messages::set_display_zone(DisplayZone::utc());
+    serde_json::from_str(json).unwrap()
+}
+
+fn now() -> UtcDateTime {
+    message("\"2024-03-01T12:00:00Z\"")
+}
+
+const FILES: &str = r#"[
+    {"assignment_number": 2, "byte_count": 1234, "media_type": "text/x-c++",
+     "name": "main.cpp", "purpose": "source", "upload_time": "2024-02-28T09:15:00Z",
+     "uri": "/api/submissions/2/files/main.cpp"},
+    {"assignment_number": 2, "byte_count": 56, "media_type": "text/x-c++",
+     "name": "test.cpp", "purpose": "test", "upload_time": "2024-02-29T17:40:00Z",
+     "uri": "/api/submissions/2/files/test.cpp"},
+    {"assignment_number": 2, "byte_count": 1048576, "media_type": "text/plain",
+     "name": "a rather long file name with spaces.txt", "purpose": "resource",
+     "upload_time": "2024-03-01T00:00:00Z",
+     "uri": "/api/submissions/2/files/a%20rather%20long%20file%20name%20with%20spaces.txt"}
+]"#;
+
+fn submission(status: &str, owner2: Option<&str>) -> messages::Submission {
+    let owner2 = owner2
+        .map(|name| {
+            format!(
+                r#", "owner2": {{"name": "{}", "uri": "/api/users/{0}"}}"#,
+                name
+            )
+        })
+        .unwrap_or_default();
+
+    message(&format!(
+        r#"{{"assignment_number": 2, "id": 17, "uri": "/api/submissions/17", "grade": 0,
+            "files_uri": "/api/submissions/17/files", "evals_uri": "/api/submissions/17/evals",
+            "owner1": {{"name": "alice", "uri": "/api/users/alice"}}{},
+            "bytes_used": 2345, "bytes_quota": 5000000,
+            "open_date": "2024-02-20T12:00:00Z", "due_date": "2024-03-04T23:59:00Z",
+            "eval_date": "2024-03-06T23:59:00Z", "last_modified": "2024-02-29T17:40:00Z",
+            "eval_status": "started", "status": "{}"}}"#,
+        owner2, status
+    ))
+}
+
+const USER: &str = r#"{
+    "name": "alice", "uri": "/api/users/alice", "submissions_uri": "/api/users/alice/submissions",
+    "role": "student",
+    "exam_grades": [{"number": 1, "points": 45, "possible": 50},
+                    {"number": 2, "points": 38, "possible": 40}],
+    "partner_requests": [
+        {"assignment_number": 3, "user": "bob", "status": "incoming"},
+        {"assignment_number": 4, "user": "carol", "status": "outgoing"},
+        {"assignment_number": 2, "user": "dave", "status": "accepted", "direction": "incoming",
+         "timestamp": "2024-01-10T09:30:00Z"}
+    ],
+    "submissions": [
+        {"assignment_number": 1, "id": 9, "uri": "/api/submissions/9", "status": "closed",
+         "grade": 0.95, "owner1": {"name": "alice", "uri": "/api/users/alice"}},
+        {"assignment_number": 2, "id": 17, "uri": "/api/submissions/17", "status": "self_eval",
+         "grade": 0, "owner1": {"name": "alice", "uri": "/api/users/alice"}},
+        {"assignment_number": 3, "id": 21, "uri": "/api/submissions/21", "status": "open",
+         "grade": 0, "owner1": {"name": "alice", "uri": "/api/users/alice"}},
+        {"assignment_number": 4, "id": 25, "uri": "/api/submissions/25", "status": "future",
+         "grade": 0, "owner1": {"name": "alice", "uri": "/api/users/alice"}}
+    ]
+}"#;
+
+const EVAL: &str = r#"{
+    "uri": "/api/submissions/17/evals/3", "sequence": 3,
+    "submission_uri": "/api/submissions/17", "type": "scale",
+    "prompt": "Does your Shape class keep its invariant in every public member function, including the constructors and the assignment operators?",
+    "value": 1.0,
+    "self_eval": {"uri": "/api/self_evals/3", "score": 1.0,
+                  "explanation": "Yes; see the check in Shape::set_size, line 42.",
+                  "permalink": "abc123"},
+    "grader_eval": {"uri": "/api/grader_evals/3", "grader": "tom", "score": 0.5,
+                    "explanation": "The copy assignment operator doesn’t check for self-assignment.",
+                    "status": "ready"}
+}"#;
+
+#[test]
+fn ls() {
+    let files: Vec<messages::FileMeta> = message(FILES);
+    assert_snapshot("ls", &render::file_list(&files, None, None));
+}
+
+#[test]
+fn ls_narrow() {
+    let files: Vec<messages::FileMeta> = message(FILES);
+    assert_snapshot("ls_narrow", &render::file_list(&files, None, Some(40)));
+}
+
+#[test]
+fn ls_late() {
+    let files: Vec<messages::FileMeta> = message(FILES);
+    let due: UtcDateTime = message("\"2024-02-29T12:00:00Z\"");
+    assert_snapshot("ls_late", &render::file_list(&files, Some(&due), None));
+}
+
+#[test]
+fn ls_none_late() {
+    let files: Vec<messages::FileMeta> = message(FILES);
+    let due: UtcDateTime = message("\"2024-03-04T23:59:00Z\"");
+    assert_snapshot("ls", &render::file_list(&files, Some(&due), None));
+}
+
+#[test]
+fn status_late_files() {
+    let files: Vec<messages::FileMeta> = message(FILES);
+    let due: UtcDateTime = message("\"2024-02-29T12:00:00Z\"");
+    assert_snapshot("status_late_files", &render::late_files(&files, &due));
+}
+
+#[test]
+fn ls_tree() {
+    let mut files: Vec<messages::FileMeta> = message(FILES);
+    files.extend(message::<Vec<messages::FileMeta>>(
+        r#"[
+        {"assignment_number": 2, "byte_count": 80, "media_type": "text/x-makefile",
+         "name": "Makefile", "purpose": "config", "upload_time": "2024-02-28T09:15:00Z",
+         "uri": "/api/submissions/2/files/Makefile"},
+        {"assignment_number": 2, "byte_count": 900, "media_type": "text/plain",
+         "name": "build.log", "purpose": "log", "upload_time": "2024-02-29T17:41:00Z",
+         "uri": "/api/submissions/2/files/build.log"},
+        {"assignment_number": 2, "byte_count": 300, "media_type": "text/x-c++",
+         "name": "shape.h", "purpose": "source", "upload_time": "2024-02-28T09:15:00Z",
+         "uri": "/api/submissions/2/files/shape.h"}
+    ]"#,
+    ));
+
+    assert_snapshot("ls_tree", &render::file_tree(2, &files, None));
+    assert_snapshot("ls_tree_narrow", &render::file_tree(2, &files, Some(24)));
+}
+
+#[test]
+fn status_hw_open() {
+    let submission = submission("open", None);
+    let text = render::submission(2, &submission, &now(), None);
+    assert_snapshot("status_hw_open", &text);
+}
+
+#[test]
+fn status_hw_narrow() {
+    let submission = submission("open", None);
+    let text = render::submission(2, &submission, &now(), Some(40));
+    assert_snapshot("status_hw_narrow", &text);
+}
+
+#[test]
+fn status_hw_self_eval_with_partner() {
+    let submission = submission("self_eval", Some("bob"));
+    let text = render::submission(2, &submission, &now(), None);
+    assert_snapshot("status_hw_self_eval_with_partner", &text);
+}
+
+#[test]
+fn status_hw_past_due() {
+    let submission = submission("overtime", None);
+    let later: UtcDateTime = message("\"2024-03-05T02:30:00Z\"");
+    let text = render::submission(2, &submission, &later, None);
+    assert_snapshot("status_hw_past_due", &text);
+}
+
+#[test]
+fn status_user() {
+    let user: messages::User = message(USER);
+    assert_snapshot("status_user", &render::user_record(&user, false));
+}
+
+#[test]
+fn status_user_with_future() {
+    let user: messages::User = message(USER);
+    assert_snapshot("status_user_with_future", &render::user_record(&user, true));
+}
+
+#[test]
+fn auth_summary() {
+    let user: messages::User = message(USER);
+    let text = render::auth_summary(&user, "https://gsc.example.edu");
+    assert_snapshot("auth_summary", &text);
+}
+
+#[test]
+fn partner_list() {
+    let user: messages::User = message(USER);
+    let text = render::partner_requests(&user.partner_requests, "");
+    assert_snapshot("partner_list", &text);
+}
+
+#[test]
+fn partner_history() {
+    let user: messages::User = message(USER);
+    let text = render::partner_history(&user.partner_requests);
+    assert_snapshot("partner_history", &text);
+}
+
+#[test]
+fn eval() {
+    let eval: messages::Eval = message(EVAL);
+    assert_snapshot("eval", &render::eval(2, 3, &eval, Some(60)));
+}
+
+#[test]
+fn grade() {
+    let submission = submission("self_eval", None);
+    let evals: Vec<messages::Eval> = message(&format!(
+        r#"[{}, {{"uri": "/api/submissions/17/evals/4", "sequence": 4,
+              "submission_uri": "/api/submissions/17", "type": "boolean",
+              "prompt": "Did you write tests?", "value": 0.5}}]"#,
+        EVAL
+    ));
+    assert_snapshot("grade", &render::grade(2, &submission, &evals, None));
+}
+
+#[test]
+fn errors() {
+    let pattern = RemotePattern {
+        hw: 2,
+        name: "*.h".to_owned(),
+    };
+
+    let errors = [
+        ErrorKind::LoginPlease,
+        ErrorKind::UnknownHomework(7),
+        ErrorKind::NoSuchRemoteFile(pattern),
+        ErrorKind::syntax("file type", "sauce"),
+        ErrorKind::hw_syntax("homework", "HW 3"),
+        ErrorKind::DestinationFileExists("src/main.cpp".to_owned()),
+        ErrorKind::RateLimited(Some(30)),
+    ];
+
+    let text: Vec<_> = errors.iter().map(ToString::to_string).collect();
+    assert_snapshot("errors", &text.join("\n"));
+}